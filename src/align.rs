@@ -0,0 +1,119 @@
+//! The `align` module lines up the messages of whispers printed one after another.
+//!
+//! Icons take 1 to 2 columns, so the messages of a list of whispers start at different columns.
+//! An `Aligner` pads every icon to the same width, also whispers without icon, so the messages
+//! line up like a table.
+//!
+//! ```
+//! use murmur::{Aligner, IconKind, RenderOptions, Whisper};
+//!
+//! let aligner = Aligner::new();
+//! let options = RenderOptions::plain();
+//! let ok = aligner.render(&Whisper::new().icon(IconKind::NfFaCheck).message("build"), &options);
+//! let warning = aligner.render(&Whisper::new().icon(IconKind::UnicodeWarningSign).message("lint"), &options);
+//! # #[cfg(not(feature = "plain"))]
+//! assert_eq!(ok, ["\u{f00c}  build"]);
+//! # #[cfg(not(feature = "plain"))]
+//! assert_eq!(warning, ["\u{26a0}\u{fe0f} lint"]);
+//! ```
+//!
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::WhisperError;
+use crate::{width, RenderOptions, Whisper};
+
+/// `Aligner` pads the icons of whispers to the same width, so their messages start at the same column.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Aligner {
+    /// The width icons are padded to, including the space after them.
+    width: usize,
+}
+
+impl Default for Aligner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Aligner {
+    /// Creates an `Aligner` padding icons to the width of the widest built-in icon.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            width: width::widest_icon(),
+        }
+    }
+
+    /// Pads icons to `width` columns, including the space after them, e.g. for wider custom icons.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{Aligner, RenderOptions, Whisper};
+    ///
+    /// let lines = Aligner::new()
+    ///     .width(4)
+    ///     .render(&Whisper::new().messages(["first", "second"]), &RenderOptions::plain());
+    /// assert_eq!(lines, ["    first", "    second"]);
+    /// ```
+    #[must_use]
+    pub const fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Renders `whisper` with `options`, its icon padded to the width of the `Aligner`.
+    ///
+    /// The following lines are indented to the same column.
+    #[must_use]
+    pub fn render(&self, whisper: &Whisper, options: &RenderOptions) -> Vec<String> {
+        whisper.render(&RenderOptions {
+            icon_width: self.width,
+            ..options.clone()
+        })
+    }
+
+    /// Whispers `whisper` like `Whisper::whisper()`, its icon padded to the width of the `Aligner`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `Whisper::whisper()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{Aligner, IconKind, Whisper};
+    ///
+    /// let aligner = Aligner::new();
+    /// for (icon_kind, step) in [(IconKind::NfFaCheck, "build"), (IconKind::UnicodeCrossMark, "test")] {
+    ///     aligner.whisper(&Whisper::new().icon(icon_kind).message(step)).unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn whisper(&self, whisper: &Whisper) -> Result<(), WhisperError> {
+        whisper.whisper_with(self.width)
+    }
+}
+
+#[cfg(test)]
+mod align_tests {
+    use super::*;
+    use crate::IconKind;
+
+    #[test]
+    fn test_aligned_messages_start_at_the_same_column() {
+        let aligner = Aligner::new();
+        let options = RenderOptions {
+            ascii_icons: true,
+            ..RenderOptions::plain()
+        };
+        let lines = [
+            aligner.render(
+                &Whisper::new().icon(IconKind::NfFaCheck).message("a"),
+                &options,
+            ),
+            aligner.render(&Whisper::new().message("b").message("c"), &options),
+        ];
+        assert_eq!(lines, [vec!["v  a"], vec!["   b", "   c"]]);
+    }
+}
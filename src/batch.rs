@@ -0,0 +1,147 @@
+//! The `batch` module whispers many whispers with a single emission.
+//!
+//! `whisper_all` renders every whisper first, then emits the whole batch while holding the output
+//! lock once, and `StdoutSink` prints it with a single write and flush. Report generators building
+//! many whispers get the report in one piece, even with other threads whispering.
+//!
+//! ```
+//! use murmur::{BatchPolicy, IconKind, Whisper};
+//!
+//! let results = [("build", true), ("test", false), ("lint", true)];
+//! murmur::whisper_all_with(
+//!     results.iter().map(|&(step, ok)| {
+//!         let icon = if ok { IconKind::NfFaCheck } else { IconKind::NfFaTimes };
+//!         Whisper::new().icon(icon).message(step)
+//!     }),
+//!     BatchPolicy::Continue,
+//! )
+//! .unwrap();
+//! ```
+//!
+use crate::{history, observer, sink, tally, RenderedWhisper, Whisper, WhisperError};
+
+/// `BatchPolicy` decides what `whisper_all_with` does when a whisper of the batch fails to render.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum BatchPolicy {
+    /// Stop at the first failing whisper, the whispers before it are still emitted.
+    #[default]
+    FailFast,
+    /// Skip failing whispers and emit all the others, the first error is returned.
+    Continue,
+}
+
+/// Whispers every whisper of `whispers` with a single emission, stopping at the first failure.
+///
+/// See `whisper_all_with`.
+///
+/// # Errors
+///
+/// Returns the first error rendering a whisper or emitting the batch.
+///
+/// # Examples
+/// ```
+/// use murmur::Whisper;
+///
+/// murmur::whisper_all((1..=3).map(|index| Whisper::new().message(format!("row {index}")))).unwrap();
+/// ```
+pub fn whisper_all<I: IntoIterator<Item = Whisper>>(whispers: I) -> Result<(), WhisperError> {
+    whisper_all_with(whispers, BatchPolicy::FailFast)
+}
+
+/// Whispers every whisper of `whispers` with a single emission, following `policy` on failures.
+///
+/// Every whisper is rate limited, added to the history and counted like with `whisper()`, then
+/// the batch is emitted to every sink at once. Sinks are all tried even if one fails.
+///
+/// # Errors
+///
+/// Returns the first error rendering a whisper or emitting the batch.
+pub fn whisper_all_with<I: IntoIterator<Item = Whisper>>(
+    whispers: I,
+    policy: BatchPolicy,
+) -> Result<(), WhisperError> {
+    let mut batch = Vec::new();
+    let mut first_error = None;
+    for whisper in whispers {
+        match whisper.prepare(0) {
+            Ok(Some(rendered)) => batch.push(rendered),
+            Ok(None) => {}
+            Err(error) => {
+                first_error.get_or_insert(error);
+                if policy == BatchPolicy::FailFast {
+                    break;
+                }
+            }
+        }
+    }
+
+    let emitted = emit(&batch);
+    first_error.map_or(emitted, Err)
+}
+
+/// Adds every whisper of `batch` to the history, the tally and the observers, then emits it to
+/// every sink at once.
+pub fn emit(batch: &[RenderedWhisper]) -> Result<(), WhisperError> {
+    for rendered in batch {
+        history::push(rendered);
+        tally::count(rendered.level);
+        observer::notify(rendered);
+    }
+    if batch.is_empty() {
+        Ok(())
+    } else {
+        sink::emit_batch(batch)
+    }
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+    use crate::{RenderOptions, Sink};
+    use std::sync::{Arc, Mutex};
+
+    /// A sink recording the size of the batches it receives.
+    struct Batches(Arc<Mutex<Vec<usize>>>);
+
+    impl Sink for Batches {
+        fn emit(&self, _rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+            self.0.lock().unwrap().push(1);
+            Ok(())
+        }
+
+        fn emit_batch(&self, batch: &[RenderedWhisper]) -> Result<(), WhisperError> {
+            self.0.lock().unwrap().push(batch.len());
+            Ok(())
+        }
+    }
+
+    /// A failing sink only implementing `emit`.
+    struct Single(Arc<Mutex<Vec<usize>>>);
+
+    impl Sink for Single {
+        fn emit(&self, _rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+            self.0.lock().unwrap().push(1);
+            Err(WhisperError::Sink)
+        }
+    }
+
+    #[test]
+    fn test_default_emit_batch_emits_every_whisper() {
+        let sizes = Arc::new(Mutex::new(Vec::new()));
+        let rendered = RenderedWhisper::new(&Whisper::new(), &RenderOptions::plain());
+        let result = Single(Arc::clone(&sizes)).emit_batch(&[rendered.clone(), rendered]);
+        assert!(matches!(result, Err(WhisperError::Sink)));
+        assert_eq!(*sizes.lock().unwrap(), [1, 1]);
+    }
+
+    #[test]
+    fn test_whisper_all_emits_one_batch() {
+        let sizes = Arc::new(Mutex::new(Vec::new()));
+        crate::add_sink(Batches(Arc::clone(&sizes)));
+        whisper_all(
+            (0..3).map(|index| Whisper::new().message(format!("test_whisper_all {index}"))),
+        )
+        .unwrap();
+        assert!(sizes.lock().unwrap().contains(&3));
+    }
+}
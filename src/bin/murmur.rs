@@ -0,0 +1,196 @@
+//! `murmur` prints whispers from shell scripts, with the styling of the library.
+//!
+//! The messages are the arguments, joined with spaces, or else every line read from stdin is
+//! whispered as it arrives. Messages are protocol lines, see `from_protocol_line`, whose directives
+//! take precedence over the options. Colors and icons follow the same environment as the library,
+//! e.g. `NO_COLOR`, `CLICOLOR_FORCE` and `MURMUR_DETERMINISTIC`.
+//!
+//! ```text
+//! murmur --icon check --color green "build done"
+//! some-cmd | murmur --icon warn
+//! echo "::icon=error,label=deploy:: rollback failed" | murmur
+//! ```
+//!
+use std::env;
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+use murmur::{Color, IconKind, Whisper, WhisperError};
+
+/// The usage of the command.
+const USAGE: &str = "\
+Usage: murmur [OPTIONS] [MESSAGE]...
+
+Whispers MESSAGE, or every line read from stdin when there is no MESSAGE.
+Messages may start with directives, e.g. \"::icon=warn,color=yellow:: disk almost full\",
+which take precedence over the options. The directives are icon, color, level, label and id.
+
+Options:
+  -i, --icon <ICON>    Icon name, e.g. check, warn, info-circle or NfFaBug
+  -c, --color <COLOR>  Color: red, green, white, cyan, yellow, blue, magenta, black,
+                       bright_black or dimmed
+  -h, --help           Print this help
+  -V, --version        Print the version";
+
+/// `Command` is what the arguments ask for.
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    /// Print the usage.
+    Help,
+    /// Print the version.
+    Version,
+    /// Whisper messages.
+    Whisper(Options),
+}
+
+/// `Options` are the style and messages of the whispers.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Options {
+    /// The icon of every whisper.
+    icon: Option<IconKind>,
+    /// The color of every whisper.
+    color: Option<Color>,
+    /// The message arguments, stdin is read when empty.
+    messages: Vec<String>,
+}
+
+impl Options {
+    /// Whispers the protocol line `line`, with the icon and color of the options unless the line
+    /// sets its own.
+    fn whisper(&self, line: &str) -> Result<(), WhisperError> {
+        let whisper = murmur::from_protocol_line(line)?;
+        Whisper {
+            icon_kind: whisper.icon_kind.or_else(|| self.icon.clone()),
+            color: whisper.color.or(self.color),
+            ..whisper
+        }
+        .whisper()
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Command::Whisper(options)) => options,
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            println!("murmur {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("murmur: {error}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("murmur: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Whispers the message arguments of `options`, or every line of stdin.
+fn run(options: &Options) -> Result<(), WhisperError> {
+    if !options.messages.is_empty() {
+        return options.whisper(&options.messages.join(" "));
+    }
+    for line in io::stdin().lock().lines() {
+        options.whisper(&line.map_err(|_| WhisperError::Read)?)?;
+    }
+    Ok(())
+}
+
+/// Parses the command line arguments `args`, without the program name.
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // Accept `--option=value` as well as `--option value`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(String::from(value))),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("missing value for {flag}"))
+        };
+        match flag {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-i" | "--icon" => {
+                let name = value()?;
+                let icon =
+                    IconKind::from_name(&name).ok_or_else(|| format!("unknown icon {name}"))?;
+                options.icon = Some(icon);
+            }
+            "-c" | "--color" => {
+                let name = value()?;
+                let color = Color::builtin(&name).ok_or_else(|| format!("unknown color {name}"))?;
+                options.color = Some(color);
+            }
+            "--" => options.messages.extend(args.by_ref()),
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option {flag}"));
+            }
+            _ => options.messages.push(arg),
+        }
+    }
+    Ok(Command::Whisper(options))
+}
+
+#[cfg(test)]
+mod murmur_tests {
+    use super::*;
+
+    /// Parses `args` given as string slices.
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|&arg| String::from(arg)))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse(&["--icon", "check", "--color=green", "build", "done"]),
+            Ok(Command::Whisper(Options {
+                icon: Some(IconKind::NfFaCheck),
+                color: Some(Color::Green),
+                messages: vec![String::from("build"), String::from("done")],
+            }))
+        );
+        assert_eq!(
+            parse(&["-i", "warn", "--", "-x"]),
+            Ok(Command::Whisper(Options {
+                icon: Some(IconKind::NfFaWarning),
+                messages: vec![String::from("-x")],
+                ..Options::default()
+            }))
+        );
+        assert_eq!(parse(&["-c", "yellow", "-h"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert_eq!(
+            parse(&["--icon"]),
+            Err(String::from("missing value for --icon"))
+        );
+        assert_eq!(
+            parse(&["-i", "nope"]),
+            Err(String::from("unknown icon nope"))
+        );
+        assert_eq!(
+            parse(&["-c", "orange"]),
+            Err(String::from("unknown color orange"))
+        );
+        assert_eq!(
+            parse(&["--loud"]),
+            Err(String::from("unknown option --loud"))
+        );
+    }
+}
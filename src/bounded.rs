@@ -0,0 +1,261 @@
+//! The `bounded` module keeps slow sinks from freezing the program that whispers.
+//!
+//! Whispers reach every sink one at a time, while the output is locked, so a sink waiting for a
+//! stalled network or a full disk holds up every following whisper. A `BoundedSink` emits to its
+//! sink from a worker thread instead, through a queue of a fixed capacity. When the queue is full,
+//! its `Overflow` policy decides whether to wait for room, drop the oldest whisper or the new one.
+//!
+//! ```no_run
+//! use murmur::{BoundedSink, FileSink, Overflow, Whisper};
+//!
+//! murmur::add_sink(BoundedSink::new(FileSink::new("/mnt/nfs/murmur.log").unwrap(), 256).overflow(Overflow::DropOldest));
+//!
+//! // Printed at once, appended to the log file when the mount answers
+//! Whisper::new().message("not held up").whisper().unwrap();
+//! ```
+//!
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use crate::{Readiness, RenderedWhisper, Sink, WhisperError};
+
+/// `Overflow` decides what a `BoundedSink` does with a whisper when its queue is full.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum Overflow {
+    /// Wait until the worker makes room, nothing is lost but the whisper blocks, the default.
+    #[default]
+    Block,
+    /// Drop the oldest queued whisper to make room for the new one.
+    DropOldest,
+    /// Drop the new whisper, the queued ones are kept.
+    DropNew,
+}
+
+/// The queue of a `BoundedSink`, shared with its worker.
+#[derive(Debug, Default)]
+struct Queue {
+    /// The whispers waiting for the worker, oldest first.
+    whispers: VecDeque<RenderedWhisper>,
+    /// Whether the worker is emitting a whisper.
+    busy: bool,
+    /// Whether the sink was dropped, the worker stops once the queue is empty.
+    closed: bool,
+    /// The number of whispers dropped by the `Overflow` policy.
+    dropped: usize,
+    /// The first error of the worker not returned yet.
+    error: Option<WhisperError>,
+}
+
+/// The state shared by a `BoundedSink` and its worker.
+#[derive(Debug, Default)]
+struct Shared {
+    /// The queue.
+    queue: Mutex<Queue>,
+    /// Notified whenever the queue changes.
+    changed: Condvar,
+}
+
+impl Shared {
+    /// Locks the queue.
+    fn lock(&self) -> Result<MutexGuard<'_, Queue>, WhisperError> {
+        self.queue.lock().map_err(|_| WhisperError::Lock)
+    }
+}
+
+/// `BoundedSink` emits to a sink from a worker thread through a bounded queue, see the `bounded`
+/// module.
+#[derive(Debug)]
+pub struct BoundedSink {
+    /// The state shared with the worker.
+    shared: Arc<Shared>,
+    /// The number of whispers queued at most.
+    capacity: usize,
+    /// What happens to a whisper when the queue is full.
+    overflow: Overflow,
+}
+
+impl BoundedSink {
+    /// Starts a worker emitting to `sink` the whispers of a queue of `capacity` whispers, at least 1.
+    #[must_use]
+    pub fn new<S: Sink + 'static>(sink: S, capacity: usize) -> Self {
+        let shared = Arc::new(Shared::default());
+        let worker = Arc::clone(&shared);
+        thread::spawn(move || work(&worker, &sink));
+        Self {
+            shared,
+            capacity: capacity.max(1),
+            overflow: Overflow::Block,
+        }
+    }
+
+    /// Sets what happens to a whisper when the queue is full, `Overflow::Block` by default.
+    #[must_use]
+    pub const fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Returns the number of whispers dropped by the `Overflow` policy so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if the queue lock is poisoned.
+    pub fn dropped(&self) -> Result<usize, WhisperError> {
+        Ok(self.shared.lock()?.dropped)
+    }
+
+    /// Waits up to `timeout` for the worker to emit every queued whisper, e.g. before exiting.
+    ///
+    /// Returns whether the queue was emptied in time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if the queue lock is poisoned.
+    pub fn flush(&self, timeout: Duration) -> Result<bool, WhisperError> {
+        let queue = self
+            .shared
+            .changed
+            .wait_timeout_while(self.shared.lock()?, timeout, |queue| {
+                queue.busy || !queue.whispers.is_empty()
+            })
+            .map_err(|_| WhisperError::Lock)?
+            .0;
+        Ok(!queue.busy && queue.whispers.is_empty())
+    }
+}
+
+impl Sink for BoundedSink {
+    /// Queues `rendered` for the worker, following the `Overflow` policy when the queue is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if the queue lock is poisoned, or the first error the worker
+    /// got from the sink since the last call, the whisper is queued anyway.
+    fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+        let mut queue = self.shared.lock()?;
+        if queue.whispers.len() >= self.capacity {
+            match self.overflow {
+                Overflow::Block => {
+                    queue = self
+                        .shared
+                        .changed
+                        .wait_while(queue, |queue| queue.whispers.len() >= self.capacity)
+                        .map_err(|_| WhisperError::Lock)?;
+                }
+                Overflow::DropOldest => {
+                    queue.whispers.pop_front();
+                    queue.dropped += 1;
+                }
+                Overflow::DropNew => {
+                    queue.dropped += 1;
+                    return queue.error.take().map_or(Ok(()), Err);
+                }
+            }
+        }
+        queue.whispers.push_back(rendered.clone());
+        self.shared.changed.notify_all();
+        queue.error.take().map_or(Ok(()), Err)
+    }
+
+    /// Returns `Readiness::Busy` while the queue is full.
+    fn poll_ready(&self) -> Readiness {
+        match self.shared.lock() {
+            Ok(queue) if queue.whispers.len() < self.capacity => Readiness::Ready,
+            _ => Readiness::Busy,
+        }
+    }
+}
+
+impl Drop for BoundedSink {
+    /// Stops the worker once it emitted the queued whispers, without waiting for it, see `flush`.
+    fn drop(&mut self) {
+        if let Ok(mut queue) = self.shared.lock() {
+            queue.closed = true;
+        }
+        self.shared.changed.notify_all();
+    }
+}
+
+/// Emits the whispers of the queue of `shared` to `sink` until the `BoundedSink` is dropped.
+fn work(shared: &Shared, sink: &dyn Sink) {
+    let Ok(mut queue) = shared.lock() else {
+        return;
+    };
+    loop {
+        let Some(rendered) = queue.whispers.pop_front() else {
+            if queue.closed {
+                return;
+            }
+            match shared.changed.wait(queue) {
+                Ok(guard) => queue = guard,
+                Err(_) => return,
+            }
+            continue;
+        };
+        queue.busy = true;
+        drop(queue);
+        // The room made is visible before the slow part
+        shared.changed.notify_all();
+        let result = sink.emit(&rendered);
+        let Ok(guard) = shared.lock() else {
+            return;
+        };
+        queue = guard;
+        queue.busy = false;
+        if let Err(error) = result {
+            queue.error.get_or_insert(error);
+        }
+        shared.changed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod bounded_tests {
+    use super::*;
+    use crate::{RenderOptions, Whisper};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A sink blocked until its gate opens, counting the whispers it emitted.
+    #[derive(Default)]
+    struct Stalled {
+        /// Whether the sink emits.
+        gate: Mutex<bool>,
+        /// Notified when the gate opens.
+        opened: Condvar,
+        /// The number of whispers emitted.
+        emitted: AtomicUsize,
+    }
+
+    impl Sink for Arc<Stalled> {
+        fn emit(&self, _rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+            let gate = self.gate.lock().unwrap();
+            drop(self.opened.wait_while(gate, |open| !*open).unwrap());
+            self.emitted.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stalled_sink_drops_instead_of_blocking() {
+        let stalled = Arc::new(Stalled::default());
+        let sink = BoundedSink::new(Arc::clone(&stalled), 2).overflow(Overflow::DropNew);
+        let rendered = RenderedWhisper::new(&Whisper::new().message("m"), &RenderOptions::plain());
+        for _ in 0..5 {
+            sink.emit(&rendered).unwrap();
+        }
+        // The worker took at most one whisper, the queue holds 2, the others were dropped
+        assert!(sink.dropped().unwrap() >= 2);
+        assert!(!sink.flush(Duration::from_millis(10)).unwrap());
+
+        *stalled.gate.lock().unwrap() = true;
+        stalled.opened.notify_all();
+        assert!(sink.flush(Duration::from_secs(5)).unwrap());
+        assert_eq!(
+            stalled.emitted.load(Ordering::Relaxed) + sink.dropped().unwrap(),
+            5
+        );
+        assert_eq!(sink.poll_ready(), Readiness::Ready);
+    }
+}
@@ -0,0 +1,107 @@
+//! The `clock` module is the time source of the timestamps and durations whispers print.
+//!
+//! Timestamps and elapsed times are read from a `Clock` instead of the system time, so embedded
+//! targets without the standard library plug in the ticks of a hardware timer, and tests plug in a
+//! `testing::FakeClock` to assert on exact durations.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use murmur::testing::FakeClock;
+//! use murmur::Whisper;
+//!
+//! let clock = Arc::new(FakeClock::new());
+//! let start = murmur::Clock::now(&clock);
+//! clock.advance(Duration::from_millis(1500));
+//! let whisper = Whisper::new().message("linked").timestamp_relative_from(start, &clock);
+//! murmur::assert_whisper_eq!(whisper, ["   +1.5s linked"]);
+//! ```
+//!
+//! With the `std` feature, `set_clock` replaces the `SystemClock` used by `Whisper::timestamp()`
+//! and the completion line of `progress_iter`.
+//!
+use alloc::sync::Arc;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::sync::{LazyLock, RwLock};
+#[cfg(feature = "std")]
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// `Clock` is a source of time, see the `clock` module.
+pub trait Clock: Send + Sync {
+    /// Returns the monotonic time elapsed since an arbitrary origin, e.g. the ticks of a timer.
+    fn now(&self) -> Duration;
+
+    /// Returns the wall-clock time as the duration since the Unix epoch, `None` if it is unknown,
+    /// e.g. on a board without a real-time clock. `None` by default.
+    fn since_epoch(&self) -> Option<Duration> {
+        None
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+
+    fn since_epoch(&self) -> Option<Duration> {
+        (**self).since_epoch()
+    }
+}
+
+/// `SystemClock` reads the time of the operating system, it is the default clock.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+/// The origin of the monotonic time of `SystemClock`.
+#[cfg(feature = "std")]
+static ORIGIN: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        ORIGIN.elapsed()
+    }
+
+    fn since_epoch(&self) -> Option<Duration> {
+        SystemTime::now().duration_since(UNIX_EPOCH).ok()
+    }
+}
+
+/// The clock set with `set_clock`, `None` for the `SystemClock`.
+#[cfg(feature = "std")]
+static CLOCK: RwLock<Option<Arc<dyn Clock>>> = RwLock::new(None);
+
+/// Replaces the clock timestamps and durations are read from, the `SystemClock` by default.
+///
+/// # Panics
+///
+/// Panics if the clock lock is poisoned.
+#[cfg(feature = "std")]
+pub fn set_clock<C: Clock + 'static>(clock: C) {
+    *CLOCK.write().unwrap() = Some(Arc::new(clock));
+}
+
+/// Returns the clock set with `set_clock`, the `SystemClock` by default.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn clock() -> Arc<dyn Clock> {
+    CLOCK
+        .read()
+        .ok()
+        .and_then(|clock| clock.clone())
+        .unwrap_or_else(|| Arc::new(SystemClock))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod clock_tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock() {
+        let start = SystemClock.now();
+        assert!(SystemClock.now() >= start);
+        assert!(SystemClock.since_epoch().unwrap() > Duration::from_secs(1_600_000_000));
+    }
+}
@@ -1,69 +1,349 @@
 //! The `color_map` module provides functionality for mapping color names to their corresponding color functions.
-//! It contains a `Lazy` static `COLOR_MAP` which is a thread-safe `HashMap` that maps color names to color functions.
 //!
-//! The `COLOR_MAP` is used to apply color to text based on the color name.
-//! The `COLOR_MAP` is lazily initialized and contains mappings for the colors "red", "green", "white", "cyan", and "yellow".
+//! Colors need the default `color` feature, and are compiled out by the `plain` feature: `color_fn`
+//! then always returns `None`.
 //!
-use once_cell::sync::Lazy;
+//! `color_fn` looks up the color function of a color name: the built-in colors "red", "green", "white",
+//! "cyan", "yellow", "blue", "magenta", "black", `"bright_black"` and "dimmed" are resolved by a `match`
+//! of plain function pointers, without allocation or lazy initialization. With the `std` feature, colors
+//! registered at runtime with `register_color` are looked up first, so they can add new color names or
+//! replace built-in ones.
+//!
+//! Whispers, icons and themes name their colors with the `Color` enum: the built-in colors,
+//! `Ansi256` and `Rgb` colors printed without lookup, and `Custom` colors registered by name.
+//!
+//! ```
+//! # #[cfg(feature = "color")]
+//! # fn main() {
+//! use owo_colors::OwoColorize;
+//! use murmur::{Category, Theme};
+//!
+//! murmur::register_color("orange", |text| text.truecolor(255, 165, 0).to_string());
+//! murmur::set_theme(Theme::dark().category_color(Category::Vcs, "orange"));
+//! # }
+//! # #[cfg(not(feature = "color"))]
+//! # fn main() {}
+//! ```
+//!
+use alloc::string::String;
+#[cfg(all(feature = "color", not(feature = "plain")))]
+use alloc::string::ToString;
+#[cfg(all(feature = "color", not(feature = "plain")))]
 use owo_colors::OwoColorize;
-use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+/// A color function, which wraps a text in the escape sequences of its color.
+pub type ColorFn = fn(&str) -> String;
+
+/// `Color` is the color a whisper, an icon or a line is printed in.
+///
+/// The named colors are looked up in the color map, so `register_color` can replace them, and
+/// `Custom` names a color registered with `register_color`, printed without color until it is.
+/// `Ansi256` and `Rgb` colors need a terminal supporting them.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Color {
+    /// Red.
+    Red,
+    /// Green.
+    Green,
+    /// White.
+    White,
+    /// Cyan.
+    Cyan,
+    /// Yellow.
+    Yellow,
+    /// Blue.
+    Blue,
+    /// Magenta.
+    Magenta,
+    /// Black.
+    Black,
+    /// Bright black, i.e. gray.
+    BrightBlack,
+    /// The dimmed default color of the terminal.
+    Dimmed,
+    /// A color of the 256 color palette.
+    Ansi256(u8),
+    /// A true color, from its red, green and blue components.
+    Rgb(u8, u8, u8),
+    /// A color registered with `register_color`, by name.
+    Custom(&'static str),
+}
+
+impl Color {
+    /// Returns the name of the color in the color map, `None` for `Ansi256` and `Rgb` colors.
+    #[must_use]
+    pub const fn name(self) -> Option<&'static str> {
+        Some(match self {
+            Self::Red => "red",
+            Self::Green => "green",
+            Self::White => "white",
+            Self::Cyan => "cyan",
+            Self::Yellow => "yellow",
+            Self::Blue => "blue",
+            Self::Magenta => "magenta",
+            Self::Black => "black",
+            Self::BrightBlack => "bright_black",
+            Self::Dimmed => "dimmed",
+            Self::Custom(name) => name,
+            Self::Ansi256(_) | Self::Rgb(..) => return None,
+        })
+    }
+
+    /// Returns the built-in color named `name`, or a `Custom` color for other names.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::Color;
+    ///
+    /// assert_eq!(Color::from_name("bright_black"), Color::BrightBlack);
+    /// assert_eq!(Color::from_name("orange"), Color::Custom("orange"));
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &'static str) -> Self {
+        match name {
+            "red" => Self::Red,
+            "green" => Self::Green,
+            "white" => Self::White,
+            "cyan" => Self::Cyan,
+            "yellow" => Self::Yellow,
+            "blue" => Self::Blue,
+            "magenta" => Self::Magenta,
+            "black" => Self::Black,
+            "bright_black" => Self::BrightBlack,
+            "dimmed" => Self::Dimmed,
+            name => Self::Custom(name),
+        }
+    }
+
+    /// Returns the built-in color named `name`, `None` for other names.
+    ///
+    /// Unlike `from_name`, `name` needs not be `'static`, e.g. a name read from the command line.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::Color;
+    ///
+    /// assert_eq!(Color::builtin("yellow"), Some(Color::Yellow));
+    /// assert_eq!(Color::builtin("orange"), None);
+    /// ```
+    #[must_use]
+    pub fn builtin(name: &str) -> Option<Self> {
+        [
+            Self::Red,
+            Self::Green,
+            Self::White,
+            Self::Cyan,
+            Self::Yellow,
+            Self::Blue,
+            Self::Magenta,
+            Self::Black,
+            Self::BrightBlack,
+            Self::Dimmed,
+        ]
+        .into_iter()
+        .find(|color| color.name() == Some(name))
+    }
+
+    /// Returns `text` wrapped in the escape sequences of the color, `None` if the color cannot be
+    /// printed: colors are compiled out, or a `Custom` color is not registered.
+    #[must_use]
+    pub fn paint(self, text: &str) -> Option<String> {
+        self.name().map_or_else(
+            || paint_code(self, text),
+            |name| color_fn(name).map(|color_fn| color_fn(text)),
+        )
+    }
+}
+
+impl From<&'static str> for Color {
+    fn from(name: &'static str) -> Self {
+        Self::from_name(name)
+    }
+}
+
+/// Returns `text` in the `Ansi256` or `Rgb` `color`, `None` for named colors.
+#[cfg(all(feature = "color", not(feature = "plain")))]
+fn paint_code(color: Color, text: &str) -> Option<String> {
+    match color {
+        Color::Ansi256(code) => Some(text.color(owo_colors::XtermColors::from(code)).to_string()),
+        Color::Rgb(red, green, blue) => Some(text.truecolor(red, green, blue).to_string()),
+        _ => None,
+    }
+}
 
-/// A type alias for a boxed function that takes a `&str` and returns a `String`.
-type ColorFn = Box<dyn Fn(&str) -> String + Send + Sync>;
+/// Returns `None`, colors are compiled out.
+#[cfg(not(all(feature = "color", not(feature = "plain"))))]
+const fn paint_code(_color: Color, _text: &str) -> Option<String> {
+    None
+}
 
-/// A type alias for a `HashMap` that maps color names to color functions.
-type ColorMapType = HashMap<&'static str, ColorFn>;
+/// The colors registered at runtime, looked up before the built-in colors.
+#[cfg(feature = "std")]
+static CUSTOM_COLORS: RwLock<Vec<(&'static str, ColorFn)>> = RwLock::new(Vec::new());
 
-/// A tuple type that represents a color.
-type Color = (&'static str, fn(&str) -> String);
+/// Registers the color `name`, replacing a color registered or built in with the same name.
+///
+/// # Panics
+///
+/// Panics if the color lock is poisoned.
+#[cfg(feature = "std")]
+pub fn register_color(name: &'static str, color_fn: ColorFn) {
+    let mut custom_colors = CUSTOM_COLORS.write().unwrap();
+    custom_colors.retain(|&(existing, _)| existing != name);
+    custom_colors.push((name, color_fn));
+}
 
-/// A `Lazy` static `HashMap` that maps color names to color functions.
-const COLORS: [Color; 5] = [
-    ("red", |text: &str| text.red().to_string()),
-    ("green", |text: &str| text.green().to_string()),
-    ("white", |text: &str| text.white().to_string()),
-    ("cyan", |text: &str| text.cyan().to_string()),
-    ("yellow", |text: &str| text.yellow().to_string()),
+/// The colors assigned by `key_color`, without the red of errors nor the dimmed colors.
+pub const KEY_PALETTE: [Color; 5] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
 ];
 
-/// A `Lazy` static `HashMap` that maps color names to color functions.
-#[rustfmt::skip]
-pub static COLOR_MAP: Lazy<ColorMapType> = Lazy::new(|| {
-    let mut map: ColorMapType = HashMap::new();
-    for &color in &COLORS {
-        map.insert(color.0, Box::new(color.1));
+/// Returns the color of `KEY_PALETTE` assigned to `key`, always the same for the same key.
+///
+/// The key is hashed with FNV-1a, which does not depend on the platform, the Rust version or the
+/// run, so a source keeps its color across runs.
+#[must_use]
+pub fn key_color(key: &str) -> Color {
+    let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let index = hash % KEY_PALETTE.len() as u64;
+    KEY_PALETTE[usize::try_from(index).unwrap_or_default()]
+}
+
+/// Returns the color function for the color `name`, `None` if the color is unknown.
+#[cfg(not(feature = "plain"))]
+pub fn color_fn(name: &str) -> Option<ColorFn> {
+    #[cfg(feature = "std")]
+    if let Ok(custom_colors) = CUSTOM_COLORS.read() {
+        if let Some(&(_, color_fn)) = custom_colors
+            .iter()
+            .find(|&&(existing, _)| existing == name)
+        {
+            return Some(color_fn);
+        }
     }
-    map
-});
+    builtin_color_fn(name)
+}
+
+/// Returns the color function for the color `name`, always `None` with the `plain` feature.
+#[cfg(feature = "plain")]
+pub const fn color_fn(_name: &str) -> Option<ColorFn> {
+    None
+}
+
+/// Returns the color function for the built-in color `name`.
+#[cfg(all(feature = "color", not(feature = "plain")))]
+fn builtin_color_fn(name: &str) -> Option<ColorFn> {
+    let color_fn: ColorFn = match name {
+        "red" => |text| text.red().to_string(),
+        "green" => |text| text.green().to_string(),
+        "white" => |text| text.white().to_string(),
+        "cyan" => |text| text.cyan().to_string(),
+        "yellow" => |text| text.yellow().to_string(),
+        "blue" => |text| text.blue().to_string(),
+        "magenta" => |text| text.magenta().to_string(),
+        "black" => |text| text.black().to_string(),
+        "bright_black" => |text| text.bright_black().to_string(),
+        "dimmed" => |text| text.dimmed().to_string(),
+        _ => return None,
+    };
+    Some(color_fn)
+}
+
+/// Returns the color function for the built-in color `name`, there are none without the `color` feature.
+#[cfg(all(not(feature = "color"), not(feature = "plain")))]
+const fn builtin_color_fn(_name: &str) -> Option<ColorFn> {
+    None
+}
 
 #[cfg(test)]
+#[cfg(all(feature = "std", feature = "color", not(feature = "plain")))]
 mod color_map_tests {
     use super::*;
 
     #[test]
     fn color_map_contains_expected_colors() {
-        let expected_colors = vec!["red", "green", "white", "cyan", "yellow"];
+        let expected_colors = vec![
+            "red",
+            "green",
+            "white",
+            "cyan",
+            "yellow",
+            "blue",
+            "magenta",
+            "black",
+            "bright_black",
+            "dimmed",
+        ];
         for color in expected_colors {
-            assert!(COLOR_MAP.contains_key(color));
+            assert!(color_fn(color).is_some());
         }
     }
 
     #[test]
     fn color_map_applies_correct_color() {
-        // let red_text = (COLOR_MAP.get("red").unwrap().lock().unwrap())("test");
-        let red_text = (COLOR_MAP.get("red").unwrap())("test");
+        let red_text = (color_fn("red").unwrap())("test");
         assert_eq!(red_text, "test".red().to_string());
     }
 
     #[test]
     fn color_map_returns_none_for_unknown_color() {
-        assert!(COLOR_MAP.get("unknown_color").is_none());
+        assert!(color_fn("unknown_color").is_none());
+    }
+
+    #[test]
+    fn color_map_registers_custom_colors() {
+        register_color("test_custom", |text| text.bright_red().to_string());
+        register_color("test_custom", |text| text.bright_blue().to_string());
+        assert_eq!(
+            color_fn("test_custom").unwrap()("test"),
+            "test".bright_blue().to_string()
+        );
+        assert_eq!(
+            color_fn("green").unwrap()("test"),
+            "test".green().to_string()
+        );
+    }
+
+    #[test]
+    fn color_map_assigns_stable_key_colors() {
+        assert_eq!(key_color("web"), key_color("web"));
+        assert!(KEY_PALETTE.contains(&key_color("db")));
+        let colors: Vec<_> = ["web", "db", "cache", "worker", "proxy"]
+            .iter()
+            .map(|key| key_color(key))
+            .collect();
+        assert!(colors.iter().any(|&color| color != colors[0]));
+    }
+
+    #[test]
+    fn color_map_paints_colors() {
+        assert_eq!(Color::Red.paint("test").unwrap(), "test".red().to_string());
+        assert_eq!(
+            Color::Rgb(255, 165, 0).paint("test").unwrap(),
+            "test".truecolor(255, 165, 0).to_string()
+        );
+        assert_eq!(
+            Color::Ansi256(208).paint("test").unwrap(),
+            "test".color(owo_colors::XtermColors::from(208)).to_string()
+        );
+        assert!(Color::Custom("color_map_unregistered")
+            .paint("test")
+            .is_none());
+        assert_eq!(Color::from("dimmed"), Color::Dimmed);
+        assert_eq!(Color::BrightBlack.name(), Some("bright_black"));
     }
 
     #[test]
     fn color_map_handles_empty_string() {
-        // let red_text = (COLOR_MAP.get("red").unwrap().lock().unwrap())("");
-        let red_text = (COLOR_MAP.get("red").unwrap())("");
+        let red_text = (color_fn("red").unwrap())("");
         assert_eq!(red_text, "".red().to_string());
     }
 }
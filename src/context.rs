@@ -0,0 +1,262 @@
+//! The `context` module applies a label, an indent and a correlation ID to the whispers of a thread.
+//!
+//! Thread-pool based tools whisper from workers that do not know which job they run for.
+//! `context()` builds a `Context` that, once entered, applies to every whisper rendered on the
+//! current thread until its guard is dropped: whispers without label or correlation ID take the
+//! ones of the context, and are indented by its indent. Contexts nest, the innermost label and
+//! correlation ID win and the indents add up.
+//!
+//! ```
+//! use murmur::{IconKind, Whisper};
+//!
+//! let handle = std::thread::spawn(|| {
+//!     let _context = murmur::context().label("worker-3").correlation_id("job-42").enter();
+//!     Whisper::new().icon(IconKind::NfFaCheck).message("resized 12 images").whisper().unwrap();
+//! });
+//! handle.join().unwrap();
+//! ```
+//!
+//! Async tasks move between threads at every `.await`. `Context::instrument` wraps a future so its
+//! context is entered each time it is polled, on whatever thread polls it, and `Context::current`
+//! captures the context of the caller, so a spawned task inherits it with any executor.
+//!
+//! ```
+//! use murmur::{Context, Whisper};
+//!
+//! let _context = murmur::context().label("api").enter();
+//! let task = Context::current().instrument(async {
+//!     // Labeled `api`, wherever the task is polled
+//!     Whisper::new().message("request served").whisper().unwrap();
+//! });
+//! # drop(task);
+//! ```
+//!
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task;
+
+use crate::{indent, Whisper, INDENT_WIDTH};
+
+thread_local! {
+    /// The contexts entered on the current thread, innermost last.
+    static CONTEXTS: RefCell<Vec<Context>> = const { RefCell::new(Vec::new()) };
+}
+
+/// `Context` is the label, indent and correlation ID applied to the whispers of a thread once
+/// entered, see `context`.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Context {
+    /// The label of the whispers without label, see `Whisper::labeled()`.
+    label: Option<String>,
+    /// The number of indent levels, of `INDENT_WIDTH` spaces each, see `indent_scope`.
+    indent: usize,
+    /// The correlation ID of the whispers without one, see `Whisper::correlation_id()`.
+    correlation_id: Option<String>,
+}
+
+impl Context {
+    /// Sets the label of the whispers without label.
+    #[must_use]
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(String::from(label));
+        self
+    }
+
+    /// Sets the number of levels the whispers are indented by, of `INDENT_WIDTH` spaces each.
+    #[must_use]
+    pub const fn indent(mut self, levels: usize) -> Self {
+        self.indent = levels;
+        self
+    }
+
+    /// Sets the correlation ID of the whispers without one.
+    #[must_use]
+    pub fn correlation_id<T: Display + ?Sized>(mut self, id: &T) -> Self {
+        self.correlation_id = Some(id.to_string());
+        self
+    }
+
+    /// Returns the context applied to the whispers of the current thread: the innermost label and
+    /// correlation ID, and the indent of its contexts and indent scopes.
+    #[must_use]
+    pub fn current() -> Self {
+        CONTEXTS.with_borrow(|contexts| Self {
+            label: contexts
+                .iter()
+                .rev()
+                .find_map(|context| context.label.clone()),
+            indent: indent::width() / INDENT_WIDTH,
+            correlation_id: contexts
+                .iter()
+                .rev()
+                .find_map(|context| context.correlation_id.clone()),
+        })
+    }
+
+    /// Wraps `future` so the context is entered each time it is polled, see the module
+    /// documentation.
+    pub fn instrument<F: Future>(self, future: F) -> Instrumented<F> {
+        Instrumented {
+            future: Box::pin(future),
+            context: self,
+        }
+    }
+
+    /// Applies the context to the whispers of the current thread until the guard is dropped.
+    pub fn enter(self) -> ContextGuard {
+        let depth = CONTEXTS.with_borrow_mut(|contexts| {
+            contexts.push(self);
+            contexts.len() - 1
+        });
+        ContextGuard {
+            depth,
+            thread: PhantomData,
+        }
+    }
+}
+
+/// `ContextGuard` applies an entered `Context` while it is alive, see `Context::enter()`.
+#[derive(Debug)]
+#[must_use = "the context ends when the guard is dropped"]
+pub struct ContextGuard {
+    /// The index of the context in the contexts of the thread.
+    depth: usize,
+    /// Keeps the guard on the thread whose whispers the context applies to.
+    thread: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        // Contexts entered after this one and still alive end with it
+        CONTEXTS.with_borrow_mut(|contexts| contexts.truncate(self.depth));
+    }
+}
+
+/// `Instrumented` is a future entering a `Context` while it is polled, see `Context::instrument()`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Instrumented<F> {
+    /// The wrapped future.
+    future: Pin<Box<F>>,
+    /// The context entered while the future is polled.
+    context: Context,
+}
+
+impl<F: Future> Future for Instrumented<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        let _context = self.context.clone().enter();
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Returns an empty `Context`, to configure and enter.
+#[must_use]
+pub fn context() -> Context {
+    Context::default()
+}
+
+/// Returns the number of indent levels of the contexts entered on the current thread.
+pub fn indent() -> usize {
+    CONTEXTS.with_borrow(|contexts| contexts.iter().map(|context| context.indent).sum())
+}
+
+/// Returns `whisper` with the label and correlation ID of the innermost contexts setting them,
+/// where it has none.
+pub fn apply(whisper: &Whisper) -> Cow<'_, Whisper> {
+    let innermost = |field: fn(&Context) -> Option<&String>| {
+        CONTEXTS.with_borrow(|contexts| contexts.iter().rev().find_map(field).cloned())
+    };
+    let label = whisper
+        .label
+        .is_none()
+        .then(|| innermost(|context| context.label.as_ref()))
+        .flatten();
+    let correlation_id = whisper
+        .correlation_id
+        .is_none()
+        .then(|| innermost(|context| context.correlation_id.as_ref()))
+        .flatten();
+    if label.is_none() && correlation_id.is_none() {
+        return Cow::Borrowed(whisper);
+    }
+    let mut whisper = whisper.clone();
+    whisper.label = whisper.label.or(label);
+    whisper.correlation_id = whisper.correlation_id.or(correlation_id);
+    Cow::Owned(whisper)
+}
+
+#[cfg(test)]
+mod context_tests {
+    use super::*;
+
+    #[test]
+    fn test_contexts_nest() {
+        let whisper = Whisper::new().message("context_tests");
+        assert!(matches!(apply(&whisper), Cow::Borrowed(_)));
+        let outer = context()
+            .label("outer")
+            .correlation_id(&7)
+            .indent(1)
+            .enter();
+        {
+            let _inner = context().label("inner").indent(2).enter();
+            let applied = apply(&whisper);
+            assert_eq!(applied.label.as_deref(), Some("inner"));
+            assert_eq!(applied.correlation_id.as_deref(), Some("7"));
+            assert_eq!(indent(), 3);
+            let labeled = Whisper::labeled("own");
+            assert_eq!(apply(&labeled).label.as_deref(), Some("own"));
+        }
+        assert_eq!(apply(&whisper).label.as_deref(), Some("outer"));
+        drop(outer);
+        assert_eq!(indent(), 0);
+        assert!(matches!(apply(&whisper), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_instrumented_future() {
+        /// A future pending once, as if awaiting another task.
+        struct YieldOnce(bool);
+
+        impl Future for YieldOnce {
+            type Output = ();
+
+            fn poll(mut self: Pin<&mut Self>, _cx: &mut task::Context<'_>) -> task::Poll<()> {
+                if self.0 {
+                    return task::Poll::Ready(());
+                }
+                self.0 = true;
+                task::Poll::Pending
+            }
+        }
+
+        let outer = context().label("task").indent(1).enter();
+        let _indent = crate::indent_scope();
+        let mut task = Context::current().instrument(async {
+            YieldOnce(false).await;
+            (apply(&Whisper::new()).label.clone(), indent())
+        });
+        drop(outer);
+        let mut cx = task::Context::from_waker(task::Waker::noop());
+        assert!(Pin::new(&mut task).poll(&mut cx).is_pending());
+        assert_eq!(indent(), 0);
+        assert_eq!(
+            Pin::new(&mut task).poll(&mut cx),
+            task::Poll::Ready((Some(String::from("task")), 2))
+        );
+    }
+
+    #[test]
+    fn test_context_applied_when_frozen() {
+        let _context = context().indent(1).correlation_id("job-42").enter();
+        let rendered = Whisper::new().message("resized").freeze().unwrap();
+        assert_eq!(rendered.plain_lines(), ["  resized [job-42]"]);
+        assert_eq!(rendered.correlation_id.as_deref(), Some("job-42"));
+    }
+}
@@ -0,0 +1,80 @@
+//! The `countdown` module waits while showing the remaining time on a single line.
+//!
+//! Retry loops printing a new line every second flood the output. `countdown` updates a single
+//! line in place each second and clears it when the time is up. When stdout is not a terminal, a
+//! single line with the whole duration is whispered instead.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! murmur::countdown(Duration::from_secs(5), "retrying in {}s").unwrap();
+//! ```
+//!
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{cursor, osc, IconKind, Whisper, WhisperError};
+
+/// Waits for `duration`, showing `template` with `{}` replaced by the remaining seconds.
+///
+/// # Errors
+///
+/// Returns the error of `Whisper::whisper()` when stdout is not a terminal, or the error of
+/// rendering the line.
+pub fn countdown(duration: Duration, template: &str) -> Result<(), WhisperError> {
+    if !osc::enabled() {
+        let whisper = line(template, seconds(duration));
+        whisper.whisper()?;
+        thread::sleep(duration);
+        return Ok(());
+    }
+    let end = Instant::now() + duration;
+    loop {
+        let remaining = end.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let seconds = seconds(remaining);
+        let lines = line(template, seconds).freeze()?.lines();
+        osc::write(&format!("{}{}", cursor::CLEAR_LINE, lines.join(" ")))?;
+        // Sleep until the displayed number of seconds changes
+        thread::sleep(remaining.saturating_sub(Duration::from_secs(seconds - 1)));
+    }
+    osc::write(cursor::CLEAR_LINE)
+}
+
+/// Returns the whisper showing `template` with `seconds` remaining.
+fn line(template: &str, seconds: u64) -> Whisper {
+    Whisper::new()
+        .icon(IconKind::NfFaClock)
+        .message(template.replacen("{}", &seconds.to_string(), 1))
+}
+
+/// Returns `duration` in seconds, rounded up.
+fn seconds(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+#[cfg(test)]
+mod countdown_tests {
+    use super::*;
+
+    #[test]
+    fn test_line() {
+        let whisper = line("retrying in {}s", 3);
+        assert_eq!(whisper.messages, ["retrying in 3s"]);
+        assert_eq!(line("waiting", 3).messages, ["waiting"]);
+    }
+
+    #[test]
+    fn test_seconds() {
+        assert_eq!(seconds(Duration::from_millis(2_001)), 3);
+        assert_eq!(seconds(Duration::from_secs(2)), 2);
+        assert_eq!(seconds(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn test_countdown_zero() {
+        countdown(Duration::ZERO, "countdown_tests {}").unwrap();
+    }
+}
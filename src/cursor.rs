@@ -0,0 +1,128 @@
+//! The `cursor` module holds the escape sequences moving the cursor and clearing lines.
+//!
+//! They build in-place updating whispers, e.g. a status line redrawn until a task completes,
+//! without depending on a terminal library. The sequences are plain strings, written with the
+//! lines of a whisper or printed directly.
+//!
+//! ```no_run
+//! use murmur::{cursor, IconKind, RenderOptions, Whisper};
+//!
+//! let _hidden = cursor::HiddenCursor::hide();
+//! for step in 1..=3 {
+//!     let lines = Whisper::new()
+//!         .icon(IconKind::NfFaRefresh)
+//!         .message(format!("step {step} of 3"))
+//!         .render(&RenderOptions::detect().unwrap());
+//!     print!("{}{}", cursor::CLEAR_LINE, lines.join("\n"));
+//! }
+//! println!();
+//! ```
+//!
+use alloc::format;
+use alloc::string::String;
+
+/// Hides the cursor.
+pub const HIDE: &str = "\x1b[?25l";
+
+/// Shows the cursor.
+pub const SHOW: &str = "\x1b[?25h";
+
+/// Moves the cursor to the start of the line and clears the line.
+pub const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Clears the screen from the cursor to the end.
+pub const CLEAR_BELOW: &str = "\x1b[J";
+
+/// Returns the sequence moving the cursor up `lines` lines, an empty string for 0.
+///
+/// # Examples
+/// ```
+/// use murmur::cursor;
+///
+/// assert_eq!(cursor::up(2), "\x1b[2A");
+/// assert_eq!(cursor::up(0), "");
+/// ```
+#[must_use]
+pub fn up(lines: usize) -> String {
+    move_by(lines, 'A')
+}
+
+/// Returns the sequence moving the cursor down `lines` lines, an empty string for 0.
+#[must_use]
+pub fn down(lines: usize) -> String {
+    move_by(lines, 'B')
+}
+
+/// Returns the sequence moving the cursor to `column`, counted from 0.
+#[must_use]
+pub fn column(column: usize) -> String {
+    format!("\x1b[{}G", column + 1)
+}
+
+/// Returns the sequence clearing the `lines` lines ending with the line of the cursor, leaving the
+/// cursor at the start of the first one, to redraw a whisper of `lines` lines in place.
+///
+/// # Examples
+/// ```
+/// use murmur::cursor;
+///
+/// assert_eq!(cursor::clear_lines(3), "\r\x1b[2A\x1b[J");
+/// ```
+#[must_use]
+pub fn clear_lines(lines: usize) -> String {
+    format!("\r{}{CLEAR_BELOW}", up(lines.saturating_sub(1)))
+}
+
+/// Returns the sequence moving the cursor by `lines` lines in the direction of `code`.
+fn move_by(lines: usize, code: char) -> String {
+    if lines == 0 {
+        String::new()
+    } else {
+        format!("\x1b[{lines}{code}")
+    }
+}
+
+/// `HiddenCursor` hides the cursor of the terminal until it is dropped.
+///
+/// Nothing is written when stdout is not a terminal, see `murmur::set_title`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct HiddenCursor {
+    /// Whether the cursor was hidden, and must be shown on drop.
+    hidden: bool,
+}
+
+#[cfg(feature = "std")]
+impl HiddenCursor {
+    /// Hides the cursor.
+    #[must_use]
+    pub fn hide() -> Self {
+        Self {
+            hidden: crate::osc::enabled() && crate::osc::write(HIDE).is_ok(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for HiddenCursor {
+    fn drop(&mut self) {
+        if self.hidden {
+            let _ = crate::osc::write(SHOW);
+        }
+    }
+}
+
+#[cfg(test)]
+mod cursor_tests {
+    use super::*;
+
+    #[test]
+    fn test_sequences() {
+        assert_eq!(up(1), "\x1b[1A");
+        assert_eq!(down(3), "\x1b[3B");
+        assert_eq!(down(0), "");
+        assert_eq!(column(0), "\x1b[1G");
+        assert_eq!(clear_lines(1), "\r\x1b[J");
+        assert_eq!(clear_lines(0), "\r\x1b[J");
+    }
+}
@@ -0,0 +1,107 @@
+//! The `delimiter` module wraps whispers written to logs in sentinel lines, to split them again.
+//!
+//! Captured logs concatenate whispers of several lines, and nothing tells where one ends and the
+//! next begins. With `set_delimiters(true)`, `FileSink` and `StdoutSink` when stdout is not a
+//! terminal write every whisper between a begin and an end line carrying the same ID:
+//!
+//! ```text
+//! --- murmur:begin 9f2c41d07a6be813 ---
+//!  build failed
+//!   src/lib.rs:3: expected `;`
+//! --- murmur:end 9f2c41d07a6be813 ---
+//! ```
+//!
+//! The ID is the correlation ID of the whisper if it has one, otherwise a hash of its uncolored
+//! lines, so it is the same in every sink and every run. The terminal never shows the sentinels.
+//!
+//! ```
+//! use murmur::Whisper;
+//!
+//! murmur::set_delimiters(true);
+//! Whisper::new().message("split me").whisper().unwrap();
+//! # murmur::set_delimiters(false);
+//! ```
+//!
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::RenderedWhisper;
+
+/// Whether whispers are wrapped in sentinel lines, see `set_delimiters`.
+static DELIMITERS: AtomicBool = AtomicBool::new(false);
+
+/// Wraps every whisper written to a file or a non-terminal stdout in sentinel lines, `false` by default.
+pub fn set_delimiters(delimiters: bool) {
+    DELIMITERS.store(delimiters, Ordering::Relaxed);
+}
+
+/// Returns whether whispers are wrapped in sentinel lines, see `set_delimiters`.
+#[must_use]
+pub fn delimiters() -> bool {
+    DELIMITERS.load(Ordering::Relaxed)
+}
+
+/// Returns the begin and end lines of `rendered`, see the `delimiter` module.
+///
+/// # Examples
+/// ```
+/// use murmur::{RenderOptions, RenderedWhisper, Whisper};
+///
+/// let whisper = Whisper::new().message("deployed").correlation_id("req-7");
+/// let (begin, end) = murmur::sentinels(&RenderedWhisper::new(&whisper, &RenderOptions::plain()));
+/// assert_eq!(begin, "--- murmur:begin req-7 ---");
+/// assert_eq!(end, "--- murmur:end req-7 ---");
+/// ```
+#[must_use]
+pub fn sentinels(rendered: &RenderedWhisper) -> (String, String) {
+    let id = rendered
+        .correlation_id
+        .clone()
+        .unwrap_or_else(|| format!("{:016x}", lines_hash(&rendered.plain_lines())));
+    (
+        format!("--- murmur:begin {id} ---"),
+        format!("--- murmur:end {id} ---"),
+    )
+}
+
+/// Returns `lines` between the sentinel lines of `rendered` if delimiters are on, `lines` otherwise.
+pub fn wrap<'a>(rendered: &RenderedWhisper, lines: Cow<'a, [String]>) -> Cow<'a, [String]> {
+    if !delimiters() {
+        return lines;
+    }
+    let (begin, end) = sentinels(rendered);
+    let mut wrapped = Vec::with_capacity(lines.len() + 2);
+    wrapped.push(begin);
+    wrapped.extend(lines.iter().cloned());
+    wrapped.push(end);
+    Cow::Owned(wrapped)
+}
+
+/// Returns the FNV-1a hash of `lines`, each followed by a newline, which does not depend on the
+/// platform or the run.
+fn lines_hash(lines: &[String]) -> u64 {
+    lines
+        .iter()
+        .flat_map(|line| line.bytes().chain([b'\n']))
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+#[cfg(test)]
+mod delimiter_tests {
+    use super::*;
+    use crate::{RenderOptions, Whisper};
+
+    #[test]
+    fn test_sentinels_are_stable() {
+        let rendered = |message| {
+            RenderedWhisper::new(&Whisper::new().message(message), &RenderOptions::plain())
+        };
+        let (begin, end) = sentinels(&rendered("a"));
+        assert_eq!(sentinels(&rendered("a")), (begin.clone(), end.clone()));
+        assert_ne!(sentinels(&rendered("b")).0, begin);
+        assert_eq!(begin.replace("begin", "end"), end);
+        assert_eq!(lines_hash(&[]), 0xcbf2_9ce4_8422_2325);
+    }
+}
@@ -0,0 +1,115 @@
+//! The `diagnostic` module converts whispers to and from a plain `Diagnostic`.
+//!
+//! `Diagnostic` holds the data of a compiler-style diagnostic without any styling: severity, code,
+//! message, notes, help and source snippet. Projects mixing murmur with a richer diagnostic
+//! renderer convert between the two through it, instead of reformatting the data by hand.
+//!
+//! ```
+//! use murmur::{Diagnostic, IconKind, Level, Whisper};
+//!
+//! let whisper = Whisper::new()
+//!     .icon(IconKind::NfFaTimes)
+//!     .message("expected value, found struct `Config`")
+//!     .code("E0423");
+//! let diagnostic = Diagnostic::from(whisper);
+//! assert_eq!(diagnostic.severity, Level::Error);
+//! assert_eq!(diagnostic.code.as_deref(), Some("E0423"));
+//! let whisper = Whisper::from(diagnostic);
+//! assert_eq!(whisper.icon_kind, Some(IconKind::NfFaTimes));
+//! ```
+//!
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Level, Snippet, Whisper};
+
+/// `Diagnostic` is the unstyled data of a diagnostic, converted from and to a `Whisper`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// The severity of the diagnostic.
+    pub severity: Level,
+    /// The error code, e.g. `E0423`.
+    pub code: Option<String>,
+    /// The main message.
+    pub message: String,
+    /// The notes following the main message.
+    pub notes: Vec<String>,
+    /// The help text.
+    pub help: Option<String>,
+    /// The source line the diagnostic points at.
+    pub snippet: Option<Snippet>,
+}
+
+impl Diagnostic {
+    /// Creates a `Diagnostic` of `severity` with `message`, without code, notes, help nor snippet.
+    #[must_use]
+    pub fn new(severity: Level, message: &str) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: String::from(message),
+            notes: Vec::new(),
+            help: None,
+            snippet: None,
+        }
+    }
+}
+
+/// The first message of the whisper is the message, the others are the notes.
+impl From<Whisper> for Diagnostic {
+    fn from(whisper: Whisper) -> Self {
+        let severity = whisper.severity();
+        let mut messages = whisper.messages.into_iter();
+        Self {
+            severity,
+            code: whisper.code,
+            message: messages.next().unwrap_or_default(),
+            notes: messages.collect(),
+            help: whisper.help,
+            snippet: whisper.snippet,
+        }
+    }
+}
+
+/// The whisper takes the icon of the severity, see `Level::icon()`, and the severity as its level.
+impl From<Diagnostic> for Whisper {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Self {
+            icon_kind: Some(diagnostic.severity.icon()),
+            level: Some(diagnostic.severity),
+            code: diagnostic.code,
+            help: diagnostic.help,
+            snippet: diagnostic.snippet,
+            ..Self::new()
+                .message(diagnostic.message)
+                .messages(diagnostic.notes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod diagnostic_tests {
+    use super::*;
+    use crate::IconKind;
+
+    #[test]
+    fn test_round_trip() {
+        let diagnostic = Diagnostic {
+            code: Some(String::from("W0612")),
+            notes: vec![String::from("src/main.rs:3")],
+            help: Some(String::from("prefix it with an underscore")),
+            snippet: Some(Snippet::new("let x = 1;", 1, 5..6)),
+            ..Diagnostic::new(Level::Warn, "unused variable `x`")
+        };
+        let whisper = Whisper::from(diagnostic.clone());
+        assert_eq!(whisper.icon_kind, Some(IconKind::NfFaWarning));
+        assert_eq!(whisper.messages, ["unused variable `x`", "src/main.rs:3"]);
+        assert_eq!(Diagnostic::from(whisper), diagnostic);
+    }
+
+    #[test]
+    fn test_from_whisper_without_messages() {
+        let diagnostic = Diagnostic::from(Whisper::new());
+        assert_eq!(diagnostic, Diagnostic::new(Level::Info, ""));
+    }
+}
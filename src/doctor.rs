@@ -0,0 +1,112 @@
+//! The `doctor` module whispers a self-diagnostic of the terminal murmur is writing to.
+//!
+//! It reports what was detected by the `term` module and prints every icon, so end users can
+//! verify their setup and attach the output when reporting rendering issues.
+//!
+use enum_iterator::all;
+
+use crate::term::{self, Stream};
+use crate::{Category, IconKind, Whisper, WhisperError};
+
+/// Whispers a diagnostic report: the detected color mode, whether `NerdFonts` seem available,
+/// the terminal size and background, and a gallery of all icons grouped by `Category`.
+///
+/// # Errors
+///
+/// Returns a `WhisperError` if any of the report whispers fails to print.
+///
+/// # Examples
+/// ```
+/// murmur::doctor().unwrap();
+/// ```
+pub fn doctor() -> Result<(), WhisperError> {
+    let capabilities = term::capabilities();
+    let decision = term::decision();
+    let header_icon = if term::nerd_fonts() {
+        IconKind::NfFaInfoCircle
+    } else {
+        IconKind::UnicodeInformationSource
+    };
+
+    let describe_tty = |stream| {
+        if capabilities.is_tty(stream) {
+            "terminal"
+        } else {
+            "not a terminal"
+        }
+    };
+    let size = match (capabilities.width, capabilities.height) {
+        (Some(width), Some(height)) => format!("{width}x{height}"),
+        _ => format!(
+            "unknown, assuming {}x{}",
+            term::DEFAULT_WIDTH,
+            term::DEFAULT_HEIGHT
+        ),
+    };
+    let nerd_fonts = if term::nerd_fonts() {
+        "likely installed"
+    } else {
+        "not detected"
+    };
+
+    Whisper::new()
+        .icon(header_icon)
+        .message("murmur doctor")
+        .message(format!(
+            "color mode: {:?} (colors enabled: {}, color depth: {:?})",
+            term::color_mode(),
+            term::color_enabled(),
+            capabilities.color_depth
+        ))
+        .message(format!(
+            "icon mode: {:?} (ASCII icons: {}, reason: {:?})",
+            term::icon_mode(),
+            decision.ascii_icons,
+            decision.reason
+        ))
+        .message(format!(
+            "stdout: {}, stderr: {}",
+            describe_tty(Stream::Stdout),
+            describe_tty(Stream::Stderr)
+        ))
+        .message(format!("terminal size: {size}"))
+        .message(format!(
+            "background: {}",
+            term::background().map_or_else(|| "unknown".to_string(), |bg| format!("{bg:?}"))
+        ))
+        .message(format!(
+            "hyperlinks: {}",
+            if capabilities.hyperlinks {
+                "supported"
+            } else {
+                "not supported"
+            }
+        ))
+        .message(format!(
+            "NerdFonts: {nerd_fonts} (set MURMUR_NERD_FONT=1 or 0 to override)"
+        ))
+        .whisper()?;
+
+    for category in all::<Category>() {
+        Whisper::new()
+            .message(format!("{category:?} icons"))
+            .whisper()?;
+        for icon_kind in IconKind::by_category(category) {
+            Whisper::new()
+                .icon(icon_kind.clone())
+                .message(format!("{icon_kind:?}"))
+                .whisper()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod doctor_tests {
+    use super::*;
+
+    #[test]
+    fn test_doctor() {
+        assert!(doctor().is_ok());
+    }
+}
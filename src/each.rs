@@ -0,0 +1,267 @@
+//! The `each` module whispers the items of an iterator as they are yielded.
+//!
+//! `whisper_each` instruments a pipeline without restructuring it: every item is whispered with
+//! its position, `[3/10]` when the length of the iterator is known and `[3]` otherwise, then
+//! yielded unchanged.
+//!
+//! ```
+//! use murmur::{IconKind, WhisperEach};
+//!
+//! let sizes = ["Cargo.toml", "src/lib.rs"]
+//!     .into_iter()
+//!     .whisper_each(IconKind::NfFaRefresh)
+//!     .map(str::len)
+//!     .collect::<Vec<_>>();
+//! assert_eq!(sizes, [10, 10]);
+//! ```
+//!
+//! `progress_iter` draws a progress bar while an iterator of known length is consumed, and
+//! whispers a completion line with the number of items and the elapsed time once it is exhausted.
+//!
+//! ```
+//! let files = ["a.rs", "b.rs", "c.rs"];
+//! for file in murmur::progress_iter(files.iter(), "processing files") {
+//!     assert!(file.ends_with(".rs"));
+//! }
+//! ```
+//!
+use core::fmt::Display;
+use core::iter::FusedIterator;
+use std::time::Duration;
+
+use crate::{clock, cursor, osc, strings, term, Clock, IconKind, Progress, Whisper};
+
+/// The number of cells of the progress bar drawn by `progress_iter`.
+const BAR_WIDTH: usize = 20;
+
+/// `WhisperEach` adds `whisper_each` to every iterator of displayable items.
+pub trait WhisperEach: Iterator + Sized
+where
+    Self::Item: Display,
+{
+    /// Whispers every item with `icon_kind` and its position as it is yielded.
+    ///
+    /// Whispers failing to print are ignored, the items are always yielded.
+    fn whisper_each(self, icon_kind: IconKind) -> WhisperEachIter<Self> {
+        let total = match self.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        };
+        WhisperEachIter {
+            iter: self,
+            icon_kind,
+            index: 0,
+            total,
+        }
+    }
+}
+
+impl<I> WhisperEach for I
+where
+    I: Iterator,
+    I::Item: Display,
+{
+}
+
+/// `WhisperEachIter` is the iterator returned by `whisper_each`.
+#[derive(Debug, Clone)]
+pub struct WhisperEachIter<I> {
+    /// The instrumented iterator.
+    iter: I,
+    /// The icon of every whisper.
+    icon_kind: IconKind,
+    /// The number of items yielded so far.
+    index: usize,
+    /// The number of items, if known when `whisper_each` was called.
+    total: Option<usize>,
+}
+
+impl<I> Iterator for WhisperEachIter<I>
+where
+    I: Iterator,
+    I::Item: Display,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        self.index += 1;
+        let _ = Whisper::new()
+            .icon(self.icon_kind.clone())
+            .message(progress(self.index, self.total, &item))
+            .whisper();
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> FusedIterator for WhisperEachIter<I>
+where
+    I: FusedIterator,
+    I::Item: Display,
+{
+}
+
+/// Wraps `iter`, drawing a progress bar labeled `label` as its items are yielded, and whispering a
+/// completion line with the number of items and the elapsed time when it is exhausted.
+///
+/// The bar is drawn in place on stdout and mirrored to the `OSC 9;4` progress indicator, only when
+/// stdout is a terminal, see `set_progress`. The completion line is always whispered.
+pub fn progress_iter<I: ExactSizeIterator>(iter: I, label: &str) -> ProgressIter<I> {
+    ProgressIter {
+        total: iter.len(),
+        iter,
+        label: String::from(label),
+        done: 0,
+        start: clock::clock().now(),
+        drawn: false,
+        finished: false,
+    }
+}
+
+/// `ProgressIter` is the iterator returned by `progress_iter`.
+#[derive(Debug)]
+pub struct ProgressIter<I> {
+    /// The wrapped iterator.
+    iter: I,
+    /// The label of the bar and of the completion line.
+    label: String,
+    /// The number of items when `progress_iter` was called.
+    total: usize,
+    /// The number of items yielded so far.
+    done: usize,
+    /// When `progress_iter` was called, on the `clock()`.
+    start: Duration,
+    /// Whether the bar was drawn, and must be cleared.
+    drawn: bool,
+    /// Whether the completion line was whispered.
+    finished: bool,
+}
+
+impl<I> ProgressIter<I> {
+    /// Redraws the bar, if stdout is a terminal.
+    fn draw(&mut self) {
+        if !osc::enabled() {
+            return;
+        }
+        let line = bar(&self.label, self.done, self.total);
+        self.drawn = osc::write(&format!("{}{line}", cursor::CLEAR_LINE)).is_ok();
+        let percent = (self.done * 100).checked_div(self.total).unwrap_or(100);
+        let _ = osc::set_progress(Progress::Normal(u8::try_from(percent).unwrap_or(100)));
+    }
+
+    /// Clears the bar and whispers the completion line, once.
+    fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        self.clear();
+        let elapsed = if term::deterministic() {
+            Duration::ZERO
+        } else {
+            clock::clock().now().saturating_sub(self.start)
+        };
+        let completed = (strings::strings().completed)(self.done, elapsed);
+        let _ = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message(format!("{}: {completed}", self.label))
+            .whisper();
+    }
+
+    /// Clears the bar, if it was drawn.
+    fn clear(&mut self) {
+        if self.drawn {
+            self.drawn = false;
+            let _ = osc::write(cursor::CLEAR_LINE);
+            let _ = osc::set_progress(Progress::Clear);
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for ProgressIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(item) = self.iter.next() else {
+            self.finish();
+            return None;
+        };
+        self.done += 1;
+        self.draw();
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for ProgressIter<I> {}
+
+impl<I> Drop for ProgressIter<I> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Returns the progress bar line of `done` items out of `total`.
+fn bar(label: &str, done: usize, total: usize) -> String {
+    let filled = (done * BAR_WIDTH).checked_div(total).unwrap_or(BAR_WIDTH);
+    format!(
+        "{label} [{}{}] {done}/{total}",
+        "=".repeat(filled.min(BAR_WIDTH)),
+        " ".repeat(BAR_WIDTH.saturating_sub(filled))
+    )
+}
+
+/// Returns the message of the item at `index`, counted from 1, of `total` items.
+fn progress<T: Display>(index: usize, total: Option<usize>, item: &T) -> String {
+    total.map_or_else(
+        || format!("[{index}] {item}"),
+        |total| format!("[{index}/{total}] {item}"),
+    )
+}
+
+#[cfg(test)]
+mod each_tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        assert_eq!(progress(3, Some(10), &"item"), "[3/10] item");
+        assert_eq!(progress(3, None, &42), "[3] 42");
+    }
+
+    #[test]
+    fn test_bar() {
+        assert_eq!(bar("files", 5, 10), "files [==========          ] 5/10");
+        assert_eq!(bar("files", 0, 0), "files [====================] 0/0");
+    }
+
+    #[test]
+    fn test_progress_iter_yields_items() {
+        let mut iter = progress_iter(["a", "b"].into_iter(), "progress_iter");
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), ["a", "b"]);
+        assert!(iter.finished);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_whisper_each_yields_items() {
+        let items = (1..=3)
+            .whisper_each(IconKind::NfFaRefresh)
+            .collect::<Vec<_>>();
+        assert_eq!(items, [1, 2, 3]);
+        let iter = [1, 2].iter().whisper_each(IconKind::NfFaRefresh);
+        assert_eq!(iter.total, Some(2));
+        let iter = (1..)
+            .filter(|n| n % 2 == 0)
+            .whisper_each(IconKind::NfFaRefresh);
+        assert_eq!(iter.total, None);
+    }
+}
@@ -0,0 +1,123 @@
+//! The `footer` module pins an updatable status line below the whispers.
+//!
+//! `footer` draws a line at the bottom of the output, e.g. the number of running jobs. Whispers
+//! printed by `StdoutSink` clear it, scroll above it and draw it again, so it stays last. It is
+//! cleared when the returned `Footer` is dropped.
+//!
+//! The footer is only drawn when stdout is a terminal, see `set_title`.
+//!
+//! ```no_run
+//! use murmur::Whisper;
+//!
+//! let footer = murmur::footer("3 jobs running\u{2026}");
+//! Whisper::new().message("job 1 done").whisper().unwrap();
+//! footer.set("2 jobs running\u{2026}");
+//! drop(footer);
+//! ```
+//!
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+use crate::{cursor, osc, render, sink};
+
+/// The text of the current footer, `None` without footer.
+static FOOTER: Mutex<Option<String>> = Mutex::new(None);
+
+/// `Footer` keeps the footer drawn until it is dropped, see `footer`.
+#[derive(Debug)]
+#[must_use = "the footer is cleared when dropped"]
+pub struct Footer {
+    /// Prevents creating a `Footer` without `footer`.
+    _private: (),
+}
+
+/// Draws `text` as the footer, replacing the current footer if any.
+///
+/// Only the first line of `text` is drawn, without escape sequences.
+pub fn footer(text: &str) -> Footer {
+    let footer = Footer { _private: () };
+    footer.set(text);
+    footer
+}
+
+impl Footer {
+    /// Replaces the text of the footer with `text`.
+    pub fn set(&self, text: &str) {
+        replace(Some(text));
+    }
+}
+
+impl Drop for Footer {
+    fn drop(&mut self) {
+        replace(None);
+    }
+}
+
+/// Replaces the footer with `text`, or clears it if `None`, while holding the output lock.
+fn replace(text: Option<&str>) {
+    if !osc::enabled() {
+        return;
+    }
+    let Ok(_output) = sink::lock_output() else {
+        return;
+    };
+    let Ok(mut footer) = FOOTER.lock() else {
+        return;
+    };
+    *footer = text.map(clean);
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(cursor::CLEAR_LINE.as_bytes());
+    let _ = draw(&mut stdout, footer.as_deref());
+}
+
+/// Returns the first line of `text` without escape sequences nor control characters.
+fn clean(text: &str) -> String {
+    render::strip_ansi(text.lines().next().unwrap_or_default())
+        .chars()
+        .filter(|character| !character.is_control())
+        .collect()
+}
+
+/// Writes `footer` without newline, and flushes `writer`.
+fn draw<W: Write>(writer: &mut W, footer: Option<&str>) -> io::Result<()> {
+    if let Some(footer) = footer {
+        writer.write_all(footer.as_bytes())?;
+    }
+    writer.flush()
+}
+
+/// Returns whether a footer is drawn, then whispers must clear it first, see `redraw`.
+pub fn active() -> bool {
+    FOOTER.lock().is_ok_and(|footer| footer.is_some())
+}
+
+/// Draws the footer again after a whisper was printed, called while holding the output lock.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+pub fn redraw<W: Write>(writer: &mut W) -> io::Result<()> {
+    let footer = FOOTER.lock().ok().and_then(|footer| footer.clone());
+    draw(writer, footer.as_deref())
+}
+
+#[cfg(test)]
+mod footer_tests {
+    use super::*;
+
+    #[test]
+    fn test_clean() {
+        assert_eq!(
+            clean("\u{1b}[1m3 jobs\u{1b}[0m\trunning\nsecond"),
+            "3 jobsrunning"
+        );
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut output = Vec::new();
+        draw(&mut output, Some("2 jobs running")).unwrap();
+        draw(&mut output, None).unwrap();
+        assert_eq!(output, b"2 jobs running");
+    }
+}
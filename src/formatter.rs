@@ -0,0 +1,143 @@
+//! The `formatter` module lets plugins add output formats that end users pick by name.
+//!
+//! A `Formatter` registered with `register_format` turns every whisper into the lines `StdoutSink`
+//! prints. The format is selected with `set_format`, or by end users with the `MURMUR_FORMAT`
+//! environment variable, so a plugin adds e.g. an audit format to a host tool without changing its
+//! code. Closures are formatters too.
+//!
+//! The built-in formats are `text`, the rendered lines and the default, and `json`, see `JsonSink`.
+//! An unknown format name prints the `text` format.
+//!
+//! ```
+//! use murmur::{RenderedWhisper, Whisper};
+//!
+//! murmur::register_format("audit", |rendered: &RenderedWhisper| {
+//!     vec![format!("AUDIT {} {}", rendered.level, rendered.messages.join(" | "))]
+//! });
+//! murmur::set_format("audit");
+//! Whisper::new().message("user deleted").whisper().unwrap();
+//! # murmur::set_format("text");
+//! ```
+//!
+use std::env;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::{JsonSink, RenderedWhisper};
+
+/// The name of the default format, printing the rendered lines.
+const TEXT: &str = "text";
+
+/// `Formatter` turns a whisper into the lines printed in its format, see `register_format`.
+pub trait Formatter: Send + Sync {
+    /// Returns the lines printed for `rendered`, without trailing newlines.
+    fn format(&self, rendered: &RenderedWhisper) -> Vec<String>;
+}
+
+impl<F: Fn(&RenderedWhisper) -> Vec<String> + Send + Sync> Formatter for F {
+    fn format(&self, rendered: &RenderedWhisper) -> Vec<String> {
+        self(rendered)
+    }
+}
+
+/// The registered formats by name, added to the built-in ones.
+static FORMATS: RwLock<Vec<(String, Arc<dyn Formatter>)>> = RwLock::new(Vec::new());
+
+/// The format selected with `set_format`, `None` to follow `MURMUR_FORMAT`.
+static SELECTED: RwLock<Option<String>> = RwLock::new(None);
+
+/// The value of `MURMUR_FORMAT`, cached on first use.
+static FORMAT_ENV: OnceLock<Option<String>> = OnceLock::new();
+
+/// Registers `formatter` as the format `name`, replacing a previous format of that name.
+///
+/// # Panics
+///
+/// Panics if the format lock is poisoned.
+pub fn register_format<F: Formatter + 'static>(name: &str, formatter: F) {
+    let mut formats = FORMATS.write().unwrap();
+    formats.retain(|(existing, _)| existing != name);
+    formats.push((String::from(name), Arc::new(formatter)));
+}
+
+/// Selects the format printed by `StdoutSink`, overriding `MURMUR_FORMAT`.
+///
+/// # Panics
+///
+/// Panics if the format lock is poisoned.
+pub fn set_format(name: &str) {
+    *SELECTED.write().unwrap() = Some(String::from(name));
+}
+
+/// Returns the name of the selected format: set by `set_format`, else `MURMUR_FORMAT`, else `text`.
+#[must_use]
+pub fn format() -> String {
+    selected().unwrap_or_else(|| String::from(TEXT))
+}
+
+/// Returns the name of the format set by `set_format`, else `MURMUR_FORMAT`.
+fn selected() -> Option<String> {
+    SELECTED.read().ok()?.clone().or_else(|| {
+        FORMAT_ENV
+            .get_or_init(|| env::var("MURMUR_FORMAT").ok())
+            .clone()
+    })
+}
+
+/// Returns whether whispers are printed in the `text` format, the selected format being `text` or unknown.
+pub fn is_text() -> bool {
+    selected().is_none_or(|name| {
+        name != "json"
+            && FORMATS
+                .read()
+                .is_ok_and(|formats| formats.iter().all(|(existing, _)| *existing != name))
+    })
+}
+
+/// Returns the lines of `rendered` in the selected format, `None` for the `text` format.
+pub fn apply(rendered: &RenderedWhisper) -> Option<Vec<String>> {
+    format_as(&selected()?, rendered)
+}
+
+/// Returns the lines of `rendered` in the format `name`, `None` for `text` and unknown formats.
+fn format_as(name: &str, rendered: &RenderedWhisper) -> Option<Vec<String>> {
+    let formatter = FORMATS.read().ok().and_then(|formats| {
+        formats
+            .iter()
+            .find_map(|(existing, formatter)| (existing == name).then(|| Arc::clone(formatter)))
+    });
+    match (formatter, name) {
+        (Some(formatter), _) => Some(formatter.format(rendered)),
+        (None, "json") => Some(vec![JsonSink::to_json(rendered)]),
+        (None, _) => None,
+    }
+}
+
+#[cfg(test)]
+mod formatter_tests {
+    use super::*;
+    use crate::{RenderOptions, Whisper};
+
+    #[test]
+    fn test_format_as() {
+        let rendered = RenderedWhisper::new(&Whisper::new().message("hi"), &RenderOptions::plain());
+        register_format("test-upper", |rendered: &RenderedWhisper| {
+            rendered
+                .plain_lines()
+                .iter()
+                .map(|line| line.to_uppercase())
+                .collect()
+        });
+        assert_eq!(
+            format_as("test-upper", &rendered),
+            Some(vec![String::from("HI")])
+        );
+        assert_eq!(
+            format_as("json", &rendered),
+            Some(vec![String::from(
+                "{\"level\":\"info\",\"messages\":[\"hi\"]}"
+            )])
+        );
+        assert_eq!(format_as(TEXT, &rendered), None);
+        assert_eq!(format_as("unknown", &rendered), None);
+    }
+}
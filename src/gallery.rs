@@ -0,0 +1,121 @@
+//! The `gallery` module lists every icon with what applications need to show it to their users.
+//!
+//! `gallery()` returns one `GalleryEntry` per `IconKind`, so a tool can back a `--list-icons` flag
+//! with the crate instead of keeping its own copy of the icon table. `whisper_gallery()` prints it.
+//!
+//! ```
+//! use murmur::IconKind;
+//!
+//! let check = murmur::gallery().into_iter().find(|entry| entry.icon_kind == IconKind::NfFaCheck).unwrap();
+//! assert_eq!(check.name, "NfFaCheck");
+//! assert_eq!(check.codepoints, "U+F00C");
+//! assert_eq!(check.unicode, IconKind::UnicodeCheckMark);
+//! ```
+//!
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use enum_iterator::all;
+
+use crate::{Category, Color, IconKind};
+#[cfg(feature = "std")]
+use crate::{Whisper, WhisperError};
+
+/// `GalleryEntry` describes an icon of the `gallery()`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct GalleryEntry {
+    /// The icon.
+    pub icon_kind: IconKind,
+    /// The stable name of the icon, see `IconKind::as_str()`.
+    pub name: String,
+    /// The glyph of the icon, without the space following it.
+    pub glyph: &'static str,
+    /// The code points of the glyph, e.g. `U+26A0 U+FE0F`.
+    pub codepoints: String,
+    /// The default color of the icon.
+    pub color: Color,
+    /// The `Unicode` counterpart of the icon, itself for `Unicode` icons.
+    pub unicode: IconKind,
+    /// The ASCII fallback of the icon.
+    pub ascii: &'static str,
+    /// The category of the icon.
+    pub category: Category,
+}
+
+impl GalleryEntry {
+    /// Returns the entry of `icon_kind`.
+    #[must_use]
+    pub fn new(icon_kind: IconKind) -> Self {
+        let glyph = icon_kind.glyph().trim_end_matches(' ');
+        let codepoints = glyph
+            .chars()
+            .map(|character| format!("U+{:04X}", u32::from(character)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            name: String::from(icon_kind.as_str()),
+            glyph,
+            codepoints,
+            color: icon_kind.default_color(),
+            unicode: icon_kind.to_unicode(),
+            ascii: icon_kind.ascii(),
+            category: icon_kind.category(),
+            icon_kind,
+        }
+    }
+}
+
+/// Returns an entry for every `IconKind`, in declaration order.
+#[must_use]
+pub fn gallery() -> Vec<GalleryEntry> {
+    all::<IconKind>().map(GalleryEntry::new).collect()
+}
+
+/// Whispers the `gallery()` grouped by `Category`, one line per icon with its name, code points,
+/// default color and `Unicode` counterpart.
+///
+/// # Errors
+///
+/// Returns the error of the first whisper that fails.
+///
+/// # Examples
+/// ```
+/// murmur::whisper_gallery().unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn whisper_gallery() -> Result<(), WhisperError> {
+    let entries = gallery();
+    for category in all::<Category>() {
+        let mut whisper = Whisper::new().message(format!("{category:?} icons"));
+        for entry in entries.iter().filter(|entry| entry.category == category) {
+            let color = entry.color.name().unwrap_or("custom");
+            let unicode = match &entry.unicode {
+                unicode if *unicode == entry.icon_kind => String::new(),
+                unicode => format!(", unicode {unicode:?}"),
+            };
+            whisper = whisper.message_with_icon(
+                entry.icon_kind.clone(),
+                format!("{} {} ({color}{unicode})", entry.name, entry.codepoints),
+            );
+        }
+        whisper.whisper()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod gallery_tests {
+    use super::*;
+
+    #[test]
+    fn test_gallery_covers_every_icon() {
+        let entries = gallery();
+        assert_eq!(entries.len(), all::<IconKind>().count());
+        let warning = GalleryEntry::new(IconKind::UnicodeWarningSign);
+        assert_eq!(warning.glyph, "\u{26a0}\u{fe0f}");
+        assert_eq!(warning.codepoints, "U+26A0 U+FE0F");
+        assert_eq!(warning.ascii, "!");
+        assert_eq!(warning.unicode, IconKind::UnicodeWarningSign);
+    }
+}
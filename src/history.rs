@@ -0,0 +1,251 @@
+//! The `history` module keeps the last rendered whispers in memory.
+//!
+//! The history is off by default. Once `keep_recent(n)` is called, the last `n` whispers are kept
+//! and `recent()` returns them, e.g. for a TUI front-end or a crash reporter, without capturing stdout.
+//!
+//! ```
+//! use murmur::Whisper;
+//!
+//! murmur::keep_recent(10);
+//! Whisper::new().message("kept in memory").whisper().unwrap();
+//! assert_eq!(murmur::recent().last().unwrap().messages, ["kept in memory"]);
+//! ```
+//!
+//! `history()` queries the kept whispers by level and time, and whispers a filtered subset again,
+//! e.g. for a "show recent errors" command:
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//! use murmur::{IconKind, Whisper};
+//!
+//! murmur::keep_recent(100);
+//! let start = Instant::now();
+//! Whisper::new().icon(IconKind::NfFaTimes).message("upload failed").whisper().unwrap();
+//! Whisper::new().message("retrying").whisper().unwrap();
+//!
+//! let errors = murmur::history().errors().since(start);
+//! assert_eq!(errors.len(), 1);
+//! errors.whisper().unwrap();
+//! ```
+//!
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::{sink, Level, RenderedWhisper, WhisperError};
+
+/// The number of whispers kept, 0 while the history is off.
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+/// The last rendered whispers, oldest first, with the instant they were whispered.
+static HISTORY: Mutex<VecDeque<(Instant, RenderedWhisper)>> = Mutex::new(VecDeque::new());
+
+/// Keeps the last `capacity` whispers in memory, 0 turns the history off and clears it.
+///
+/// # Panics
+///
+/// Panics if the history lock is poisoned.
+pub fn keep_recent(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    let mut history = HISTORY.lock().unwrap();
+    trim(&mut history, capacity);
+    history.shrink_to(capacity);
+}
+
+/// Returns whether whispers are kept in the history, see `keep_recent`.
+pub fn recording() -> bool {
+    CAPACITY.load(Ordering::Relaxed) > 0
+}
+
+/// Returns the last whispers, oldest first, empty while the history is off.
+///
+/// # Panics
+///
+/// Panics if the history lock is poisoned.
+#[must_use]
+pub fn recent() -> Vec<RenderedWhisper> {
+    HISTORY
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, rendered)| rendered.clone())
+        .collect()
+}
+
+/// Returns a `HistoryQuery` over the last whispers, empty while the history is off.
+///
+/// # Panics
+///
+/// Panics if the history lock is poisoned.
+#[must_use]
+pub fn history() -> HistoryQuery {
+    HistoryQuery {
+        entries: HISTORY.lock().unwrap().iter().cloned().collect(),
+    }
+}
+
+/// `HistoryQuery` is a snapshot of the history narrowed down by filters, see `history()`.
+///
+/// Whispers made after `history()` is called are not part of the query.
+#[derive(Debug, Clone)]
+pub struct HistoryQuery {
+    /// The whispers that passed the filters, oldest first, with the instant they were whispered.
+    entries: Vec<(Instant, RenderedWhisper)>,
+}
+
+impl HistoryQuery {
+    /// Keeps the whispers at or above `level`.
+    #[must_use]
+    pub fn at_least(self, level: Level) -> Self {
+        self.filter(|rendered| rendered.level >= level)
+    }
+
+    /// Keeps the whispers at the `Error` level.
+    #[must_use]
+    pub fn errors(self) -> Self {
+        self.at_least(Level::Error)
+    }
+
+    /// Keeps the whispers at or above the `Warn` level.
+    #[must_use]
+    pub fn warnings(self) -> Self {
+        self.at_least(Level::Warn)
+    }
+
+    /// Keeps the whispers made at or after `start`.
+    #[must_use]
+    pub fn since(mut self, start: Instant) -> Self {
+        self.entries.retain(|(instant, _)| *instant >= start);
+        self
+    }
+
+    /// Keeps the last `n` whispers.
+    #[must_use]
+    pub fn last(mut self, n: usize) -> Self {
+        self.entries.drain(..self.entries.len().saturating_sub(n));
+        self
+    }
+
+    /// Keeps the whispers for which `predicate` returns `true`.
+    #[must_use]
+    pub fn filter(mut self, mut predicate: impl FnMut(&RenderedWhisper) -> bool) -> Self {
+        self.entries.retain(|(_, rendered)| predicate(rendered));
+        self
+    }
+
+    /// Returns the number of whispers of the query.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether no whisper passed the filters.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the whispers of the query, oldest first.
+    #[must_use]
+    pub fn whispers(&self) -> Vec<RenderedWhisper> {
+        self.entries
+            .iter()
+            .map(|(_, rendered)| rendered.clone())
+            .collect()
+    }
+
+    /// Emits the whispers of the query again to every registered `Sink`, oldest first.
+    ///
+    /// Unlike `RenderedWhisper::whisper()`, they are not added to the history or counted in the
+    /// tally a second time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if a sink lock is poisoned, or the error of a failing sink.
+    pub fn whisper(&self) -> Result<(), WhisperError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        sink::emit_batch(&self.whispers())
+    }
+}
+
+/// Adds `rendered` to the history, if it is on.
+pub fn push(rendered: &RenderedWhisper) {
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    if capacity == 0 {
+        return;
+    }
+    if let Ok(mut history) = HISTORY.lock() {
+        trim(&mut history, capacity - 1);
+        history.push_back((Instant::now(), rendered.clone()));
+    }
+}
+
+/// Drops the oldest whispers of `history` until at most `len` remain.
+fn trim<T>(history: &mut VecDeque<T>, len: usize) {
+    while history.len() > len {
+        history.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+    use crate::{IconKind, RenderOptions, Whisper};
+    use std::time::Duration;
+
+    #[test]
+    fn test_trim_keeps_newest() {
+        let mut history = (0..5)
+            .map(|index| {
+                RenderedWhisper::new(&Whisper::new().message(index), &RenderOptions::plain())
+            })
+            .collect::<VecDeque<_>>();
+        trim(&mut history, 2);
+        let messages = history
+            .iter()
+            .map(|rendered| rendered.messages.concat())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["3", "4"]);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let start = Instant::now();
+        let entries = [
+            (IconKind::NfFaTimes, "failed"),
+            (IconKind::NfFaWarning, "slow"),
+            (IconKind::NfFaCheck, "done"),
+            (IconKind::NfFaTimes, "failed again"),
+        ]
+        .into_iter()
+        .map(|(icon_kind, message)| {
+            let whisper = Whisper::new().icon(icon_kind).message(message);
+            (
+                start,
+                RenderedWhisper::new(&whisper, &RenderOptions::plain()),
+            )
+        })
+        .collect();
+        let query = HistoryQuery { entries };
+        let messages = |query: HistoryQuery| {
+            query
+                .whispers()
+                .iter()
+                .map(|rendered| rendered.messages.concat())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(messages(query.clone().errors()), ["failed", "failed again"]);
+        assert_eq!(
+            messages(query.clone().warnings().last(2)),
+            ["slow", "failed again"]
+        );
+        assert!(query
+            .clone()
+            .since(start + Duration::from_secs(1))
+            .is_empty());
+        assert_eq!(query.since(start).len(), 4);
+    }
+}
@@ -1,18 +1,28 @@
 //! The `icon_map` module provides functionality for mapping `IconKind` enum variants to their corresponding icons and colors.
-//! It contains a `Lazy` static `ICON_MAP` which is a thread-safe `HashMap` that maps `IconKind` enum variants to a tuple of an icon and a color.
+//! It contains a `LazyLock` static `ICON_MAP` which is a thread-safe `HashMap` that maps `IconKind` enum variants to a tuple of an icon and a color.
 //! The `ICON_MAP` is used by the `Whisper` struct to look up the icon and color based on the `IconKind`.
-//! The `ICON_MAP` is lazily initialized and contains mappings for both `NerdFont` and Unicode icons.
+//! The `ICON_MAP` is lazily initialized from a constant table, usable without the standard library,
+//! and contains mappings for both `NerdFont` and Unicode icons.
 //!
 //! The `IconKind` enum represents different kinds of icons for formatting messages. It supports both Unicode or Nerd Font icons if you have a Nerd Font installed.
 //!
 #![allow(deprecated)]
 
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
-use std::fmt;
-use std::sync::RwLock;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicU8, Ordering};
+#[cfg(feature = "std")]
+use std::sync::{LazyLock, RwLock};
 
-use enum_iterator::Sequence;
-use once_cell::sync::Lazy;
+use enum_iterator::{all, Sequence};
+
+use crate::Color;
+#[cfg(feature = "std")]
+use crate::WhisperError;
 
 /// `IconKind` is an enum representing different kinds of icons for formatting messages.
 ///
@@ -30,6 +40,13 @@ use once_cell::sync::Lazy;
 /// You must have [NerdFonts](https://www.nerdfonts.com/) installed to use the `Nf` variants.
 /// - [NerdFonts github](https://github.com/ryanoasis/nerd-fonts?tab=readme-ov-files)
 /// - [NerdFonts cheat-sheet](https://www.nerdfonts.com/cheat-sheet)
+///
+/// Besides the status icons, each family is compiled in by its feature, e.g. `icons-oct` for the
+/// `NfOct` variants, see the `icons` features of the crate. The `Unicode` counterparts of the other
+/// icons are compiled in by `icons-unicode` or by the feature of their `Nf` family, e.g. `UnicodeTag`
+/// with `any(icons-unicode, icons-oct)`, so the variants `to_unicode()` returns depend on the
+/// enabled features, and `to_nerd_font()` returns a `Unicode` icon unchanged when its `Nf`
+/// counterpart is compiled out.
 #[non_exhaustive]
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Sequence)]
 pub enum IconKind {
@@ -44,18 +61,103 @@ pub enum IconKind {
     NfFaTerminal,
     NfFaTrash,
 
+    #[cfg(feature = "icons-fa")]
     NfFaAngleRight,
+    #[cfg(feature = "icons-fa")]
     NfFaAngleLeft,
+    #[cfg(feature = "icons-fa")]
     NfFaAngleUp,
+    #[cfg(feature = "icons-fa")]
     NfFaAngleDown,
+    #[cfg(feature = "icons-fa")]
     NfFaThumbsUp,
+    #[cfg(feature = "icons-fa")]
     NfFaThumbsDown,
+    #[cfg(feature = "icons-fa")]
     NfFaFolder,
+    #[cfg(feature = "icons-fa")]
     NfFaFolderOpen,
+    #[cfg(feature = "icons-fa")]
+    NfFaCodeFork,
+    #[cfg(feature = "icons-fa")]
+    NfFaGit,
+    #[cfg(feature = "icons-fa")]
+    NfFaGithub,
+    #[cfg(feature = "icons-fa")]
+    NfFaFile,
+    #[cfg(feature = "icons-fa")]
+    NfFaFileCode,
+    #[cfg(feature = "icons-fa")]
+    NfFaDownload,
+    #[cfg(feature = "icons-fa")]
+    NfFaUpload,
+    #[cfg(feature = "icons-fa")]
+    NfFaRocket,
+    #[cfg(feature = "icons-fa")]
+    NfFaWrench,
+    NfFaClock,
+    #[cfg(feature = "icons-fa")]
+    NfFaLock,
+    NfFaKey,
+    #[cfg(feature = "icons-fa")]
+    NfFaDatabase,
+    #[cfg(feature = "icons-fa")]
+    NfFaHourglass,
+    #[cfg(feature = "icons-fae")]
     NfFaeCcCc,
+    #[cfg(feature = "icons-fae")]
     NfFaeEqual,
 
+    #[cfg(feature = "icons-dev")]
+    NfDevGit,
+    #[cfg(feature = "icons-dev")]
+    NfDevGitBranch,
+    #[cfg(feature = "icons-dev")]
+    NfDevGitCommit,
+    #[cfg(feature = "icons-dev")]
+    NfDevGitMerge,
+    #[cfg(feature = "icons-dev")]
+    NfDevRust,
+    #[cfg(feature = "icons-dev")]
+    NfDevPython,
+    #[cfg(feature = "icons-dev")]
+    NfDevJavascript,
+    #[cfg(feature = "icons-dev")]
+    NfDevDocker,
+    #[cfg(feature = "icons-dev")]
+    NfDevLinux,
+    #[cfg(feature = "icons-dev")]
+    NfDevApple,
+    #[cfg(feature = "icons-dev")]
+    NfDevWindows,
+
+    #[cfg(feature = "icons-oct")]
     NfOctDotFill,
+    #[cfg(feature = "icons-oct")]
+    NfOctGitCommit,
+    #[cfg(feature = "icons-oct")]
+    NfOctGitPullRequest,
+    #[cfg(feature = "icons-oct")]
+    NfOctGitMerge,
+    #[cfg(feature = "icons-oct")]
+    NfOctGitCompare,
+    #[cfg(feature = "icons-oct")]
+    NfOctTag,
+    #[cfg(feature = "icons-oct")]
+    NfOctRepo,
+    #[cfg(feature = "icons-oct")]
+    NfOctDiff,
+    #[cfg(feature = "icons-oct")]
+    NfOctPackage,
+
+    #[cfg(feature = "icons-weather")]
+    NfWeatherDaySunny,
+    #[cfg(feature = "icons-weather")]
+    NfWeatherCloudy,
+    #[cfg(feature = "icons-weather")]
+    NfWeatherRain,
+    #[cfg(feature = "icons-weather")]
+    NfWeatherNightClear,
 
     UnicodeCrossMark,
     UnicodeCheckMark,
@@ -63,91 +165,1571 @@ pub enum IconKind {
     UnicodeGear,
     UnicodeWarningSign,
     UnicodeBug,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeFile,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeFileCode,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+    UnicodePackage,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeDownload,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeUpload,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeRocket,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeWrench,
+    UnicodeClock,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeLock,
+    UnicodeKey,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeDatabase,
+    UnicodeQuestion,
+    UnicodeQuestionCircle,
+    UnicodeTerminal,
+    UnicodeTrash,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeAngleRight,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeAngleLeft,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeAngleUp,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeAngleDown,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeThumbsUp,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeThumbsDown,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeFolder,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeFolderOpen,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+    UnicodeCcCc,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+    UnicodeEqual,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+    UnicodeDotFill,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeCodeFork,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa", feature = "icons-dev"))]
+    UnicodeGit,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeGithub,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+    UnicodeGitBranch,
+    #[cfg(any(
+        feature = "icons-unicode",
+        feature = "icons-dev",
+        feature = "icons-oct"
+    ))]
+    UnicodeGitCommit,
+    #[cfg(any(
+        feature = "icons-unicode",
+        feature = "icons-dev",
+        feature = "icons-oct"
+    ))]
+    UnicodeGitMerge,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+    UnicodeGitPullRequest,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+    UnicodeGitCompare,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+    UnicodeTag,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+    UnicodeRepo,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+    UnicodeDiff,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+    UnicodeHourglass,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+    UnicodeDaySunny,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+    UnicodeCloudy,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+    UnicodeRain,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+    UnicodeNightClear,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+    UnicodeRust,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+    UnicodePython,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+    UnicodeJavascript,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+    UnicodeDocker,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+    UnicodeLinux,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+    UnicodeApple,
+    #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+    UnicodeWindows,
 }
 
 impl fmt::Display for IconKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some((icon, _)) = ICON_MAP.read().unwrap().get(self) {
-            write!(f, "{icon}")
-        } else {
-            write!(f, "Icon not found")
+        let icon = self.glyph();
+        write!(f, "{icon}")
+    }
+}
+
+/// `Category` groups related `IconKind` variants, e.g. to present an organized icon picker.
+///
+/// # Examples
+/// ```
+/// use murmur::{Category, IconKind};
+///
+/// assert_eq!(IconKind::NfFaCheck.category(), Category::Status);
+/// # #[cfg(feature = "icons-oct")]
+/// assert!(IconKind::by_category(Category::Vcs).contains(&IconKind::NfOctTag));
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Sequence)]
+pub enum Category {
+    /// Outcome and state icons: check, times, warning, info, bug, question, ...
+    Status,
+    /// Angle and arrow icons.
+    Arrows,
+    /// File, folder, package and transfer icons.
+    Files,
+    /// Git and version control icons.
+    Vcs,
+    /// Weather and time of day icons: sun, cloud, rain, moon.
+    Weather,
+    /// Programming language, tool and platform logos: Rust, Python, Docker, Linux, ...
+    Tech,
+    /// Everything else.
+    Misc,
+}
+
+/// `SpinnerKind` selects a group of icons representing the frames of an animation.
+///
+/// The frames are returned by [`IconKind::frames`] and can be cycled through to draw a spinner.
+///
+/// # Examples
+/// ```
+/// use murmur::{IconKind, SpinnerKind};
+///
+/// let frames = IconKind::frames(SpinnerKind::Braille);
+/// for tick in 0..20 {
+///     let frame = frames[tick % frames.len()];
+///     assert!(!frame.is_empty());
+/// }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Sequence)]
+pub enum SpinnerKind {
+    /// Braille dots: `⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏`
+    Braille,
+    /// Clock faces: `🕐🕑🕒 ... 🕛`
+    Clock,
+    /// Unicode circle quarters: `◴◷◶◵`
+    CircleQuarters,
+    /// Nerd Font circle slices, you must have [NerdFonts](https://www.nerdfonts.com/) installed.
+    NfCircleQuarters,
+}
+
+/// Braille dots spinner frames: `⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏`
+#[rustfmt::skip]
+const BRAILLE_FRAMES: [&str; 10] = [
+    "\u{280B}", "\u{2819}", "\u{2839}", "\u{2838}", "\u{283C}",
+    "\u{2834}", "\u{2826}", "\u{2827}", "\u{2807}", "\u{280F}",
+];
+/// Clock face frames, one per hour: `🕐🕑🕒🕓🕔🕕🕖🕗🕘🕙🕚🕛`
+#[rustfmt::skip]
+const CLOCK_FRAMES: [&str; 12] = [
+    "\u{1F550}", "\u{1F551}", "\u{1F552}", "\u{1F553}", "\u{1F554}", "\u{1F555}",
+    "\u{1F556}", "\u{1F557}", "\u{1F558}", "\u{1F559}", "\u{1F55A}", "\u{1F55B}",
+];
+/// Rotating circle quarter frames: `◴◷◶◵`
+const CIRCLE_QUARTER_FRAMES: [&str; 4] = ["\u{25F4}", "\u{25F7}", "\u{25F6}", "\u{25F5}"];
+/// Nerd Font circle slices filling up a quarter at a time: `󰪟󰪡󰪣󰪥`
+const NF_CIRCLE_QUARTER_FRAMES: [&str; 4] = ["\u{F0A9F}", "\u{F0AA1}", "\u{F0AA3}", "\u{F0AA5}"];
+
+impl IconKind {
+    /// Returns the frames of the animation selected by `spinner_kind`.
+    ///
+    /// Unlike the glyphs in `ICON_MAP`, frames have no trailing space, so they can be redrawn in place.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{IconKind, SpinnerKind};
+    ///
+    /// assert_eq!(IconKind::frames(SpinnerKind::CircleQuarters).len(), 4);
+    /// ```
+    #[must_use]
+    pub const fn frames(spinner_kind: SpinnerKind) -> &'static [&'static str] {
+        match spinner_kind {
+            SpinnerKind::Braille => &BRAILLE_FRAMES,
+            SpinnerKind::Clock => &CLOCK_FRAMES,
+            SpinnerKind::CircleQuarters => &CIRCLE_QUARTER_FRAMES,
+            SpinnerKind::NfCircleQuarters => &NF_CIRCLE_QUARTER_FRAMES,
+        }
+    }
+
+    /// Returns the `Category` of the icon. `Unicode` variants share the category of their `Nf` counterpart.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{Category, IconKind};
+    ///
+    /// # #[cfg(feature = "icons-fa")]
+    /// assert_eq!(IconKind::NfFaAngleUp.category(), Category::Arrows);
+    /// # #[cfg(feature = "icons-fa")]
+    /// assert_eq!(IconKind::UnicodeAngleUp.category(), Category::Arrows);
+    /// ```
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn category(&self) -> Category {
+        // Every icon is listed, so a new icon is given a category, its `Unicode` counterpart too
+        #[allow(clippy::match_same_arms)]
+        match self {
+            Self::NfFaTimes
+            | Self::NfFaCheck
+            | Self::NfFaInfoCircle
+            | Self::NfFaRefresh
+            | Self::NfFaWarning
+            | Self::NfFaBug
+            | Self::NfFaQuestion
+            | Self::NfFaQuestionCircle
+            | Self::UnicodeCrossMark
+            | Self::UnicodeCheckMark
+            | Self::UnicodeInformationSource
+            | Self::UnicodeGear
+            | Self::UnicodeWarningSign
+            | Self::UnicodeBug
+            | Self::UnicodeQuestion
+            | Self::UnicodeQuestionCircle => Category::Status,
+            Self::NfFaTrash | Self::UnicodeTrash => Category::Files,
+            Self::NfFaTerminal
+            | Self::NfFaClock
+            | Self::NfFaKey
+            | Self::UnicodeTerminal
+            | Self::UnicodeClock
+            | Self::UnicodeKey => Category::Misc,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsUp | Self::NfFaThumbsDown => Category::Status,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleRight
+            | Self::NfFaAngleLeft
+            | Self::NfFaAngleUp
+            | Self::NfFaAngleDown => Category::Arrows,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolder
+            | Self::NfFaFolderOpen
+            | Self::NfFaFile
+            | Self::NfFaFileCode
+            | Self::NfFaDownload
+            | Self::NfFaUpload
+            | Self::NfFaDatabase => Category::Files,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaCodeFork | Self::NfFaGit | Self::NfFaGithub => Category::Vcs,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaRocket | Self::NfFaWrench | Self::NfFaLock | Self::NfFaHourglass => {
+                Category::Misc
+            }
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeCcCc | Self::NfFaeEqual => Category::Misc,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGit | Self::NfDevGitBranch | Self::NfDevGitCommit | Self::NfDevGitMerge => {
+                Category::Vcs
+            }
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevRust
+            | Self::NfDevPython
+            | Self::NfDevJavascript
+            | Self::NfDevDocker
+            | Self::NfDevLinux
+            | Self::NfDevApple
+            | Self::NfDevWindows => Category::Tech,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctPackage => Category::Files,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCommit
+            | Self::NfOctGitPullRequest
+            | Self::NfOctGitMerge
+            | Self::NfOctGitCompare
+            | Self::NfOctTag
+            | Self::NfOctRepo
+            | Self::NfOctDiff => Category::Vcs,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDotFill => Category::Misc,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherDaySunny
+            | Self::NfWeatherCloudy
+            | Self::NfWeatherRain
+            | Self::NfWeatherNightClear => Category::Weather,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeThumbsUp | Self::UnicodeThumbsDown => Category::Status,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleRight
+            | Self::UnicodeAngleLeft
+            | Self::UnicodeAngleUp
+            | Self::UnicodeAngleDown => Category::Arrows,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFolder
+            | Self::UnicodeFolderOpen
+            | Self::UnicodeFile
+            | Self::UnicodeFileCode
+            | Self::UnicodeDownload
+            | Self::UnicodeUpload
+            | Self::UnicodeDatabase => Category::Files,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeCodeFork | Self::UnicodeGithub => Category::Vcs,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa", feature = "icons-dev"))]
+            Self::UnicodeGit => Category::Vcs,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeRocket
+            | Self::UnicodeWrench
+            | Self::UnicodeLock
+            | Self::UnicodeHourglass => Category::Misc,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+            Self::UnicodeCcCc | Self::UnicodeEqual => Category::Misc,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeGitBranch => Category::Vcs,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeRust
+            | Self::UnicodePython
+            | Self::UnicodeJavascript
+            | Self::UnicodeDocker
+            | Self::UnicodeLinux
+            | Self::UnicodeApple
+            | Self::UnicodeWindows => Category::Tech,
+            #[cfg(any(
+                feature = "icons-unicode",
+                feature = "icons-dev",
+                feature = "icons-oct"
+            ))]
+            Self::UnicodeGitCommit | Self::UnicodeGitMerge => Category::Vcs,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodePackage => Category::Files,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeGitPullRequest
+            | Self::UnicodeGitCompare
+            | Self::UnicodeTag
+            | Self::UnicodeRepo
+            | Self::UnicodeDiff => Category::Vcs,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeDotFill => Category::Misc,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeDaySunny
+            | Self::UnicodeCloudy
+            | Self::UnicodeRain
+            | Self::UnicodeNightClear => Category::Weather,
+        }
+    }
+
+    /// Returns every `IconKind` belonging to `category`, in declaration order.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{Category, IconKind};
+    ///
+    /// for icon_kind in IconKind::by_category(Category::Arrows) {
+    ///     assert_eq!(icon_kind.category(), Category::Arrows);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn by_category(category: Category) -> Vec<Self> {
+        all::<Self>()
+            .filter(|icon_kind| icon_kind.category() == category)
+            .collect()
+    }
+
+    /// Returns the ASCII fallback of the icon, printed when glyphs cannot be displayed.
+    ///
+    /// Status and arrow icons have a distinct fallback, every other icon falls back to `*`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::IconKind;
+    ///
+    /// assert_eq!(IconKind::NfFaCheck.ascii(), "v");
+    /// assert_eq!(IconKind::UnicodeCrossMark.ascii(), "x");
+    /// ```
+    #[must_use]
+    pub fn ascii(&self) -> &'static str {
+        #[allow(clippy::match_same_arms)]
+        match self.to_nerd_font() {
+            Self::NfFaTimes => "x",
+            Self::NfFaCheck => "v",
+            Self::NfFaInfoCircle => "i",
+            Self::NfFaRefresh => "~",
+            Self::NfFaWarning => "!",
+            Self::NfFaBug => "#",
+            Self::NfFaQuestion | Self::NfFaQuestionCircle => "?",
+            Self::NfFaTerminal => "$",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsUp => "+",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsDown => "-",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleRight => ">",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleLeft => "<",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleUp => "^",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleDown => "v",
+            _ => "*",
+        }
+    }
+
+    /// Returns the icon named `name`, e.g. in the arguments of a command line tool.
+    ///
+    /// `name` is the name of a variant like `NfFaInfoCircle`, or its name without the `Nf...` or
+    /// `Unicode` prefix like `info-circle`, which selects the `Nf` variant first. Case, dashes and
+    /// underscores are ignored. The levels `error`, `warn`, `info`, `success` and `debug` name the
+    /// icon of their level.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::IconKind;
+    ///
+    /// assert_eq!(IconKind::from_name("check"), Some(IconKind::NfFaCheck));
+    /// assert_eq!(IconKind::from_name("info-circle"), Some(IconKind::NfFaInfoCircle));
+    /// assert_eq!(IconKind::from_name("UnicodeBug"), Some(IconKind::UnicodeBug));
+    /// assert_eq!(IconKind::from_name("warn"), Some(IconKind::NfFaWarning));
+    /// assert_eq!(IconKind::from_name("unknown"), None);
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let level_icon = match normalized(name).collect::<String>().as_str() {
+            "error" => Some(Self::NfFaTimes),
+            "warn" | "warning" => Some(Self::NfFaWarning),
+            "info" => Some(Self::NfFaInfoCircle),
+            "success" | "ok" => Some(Self::NfFaCheck),
+            "debug" => Some(Self::NfFaTerminal),
+            _ => None,
+        };
+        let variants = || all::<Self>().map(|icon_kind| (icon_kind.as_str(), icon_kind));
+        let named = |variant: &str| normalized(variant).eq(normalized(name));
+        level_icon
+            .or_else(|| {
+                variants()
+                    .find(|(variant, _)| named(variant))
+                    .map(|(_, icon)| icon)
+            })
+            .or_else(|| {
+                variants()
+                    .find(|(variant, _)| named(short_name(variant)))
+                    .map(|(_, icon)| icon)
+            })
+    }
+
+    /// Returns the stable numeric ID of the icon, e.g. to store it in a compact format.
+    ///
+    /// IDs never change across versions and are never reused, whatever the order of the variants.
+    /// Each family has its own range, so new icons join their family: `NfFa` from 100, `NfFae`
+    /// from 200, `NfDev` from 300, `NfOct` from 400, `NfWeather` from 500 and `Unicode` from 1000.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::IconKind;
+    ///
+    /// assert_eq!(IconKind::NfFaCheck.id(), 101);
+    /// assert_eq!(IconKind::from_id(101), Some(IconKind::NfFaCheck));
+    /// ```
+    #[must_use]
+    #[rustfmt::skip]
+    #[allow(clippy::too_many_lines)]
+    pub const fn id(&self) -> u16 {
+        match self {
+            Self::NfFaTimes => 100,
+            Self::NfFaCheck => 101,
+            Self::NfFaInfoCircle => 102,
+            Self::NfFaRefresh => 103,
+            Self::NfFaWarning => 104,
+            Self::NfFaBug => 105,
+            Self::NfFaQuestion => 106,
+            Self::NfFaQuestionCircle => 107,
+            Self::NfFaTerminal => 108,
+            Self::NfFaTrash => 109,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleRight => 110,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleLeft => 111,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleUp => 112,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleDown => 113,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsUp => 114,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsDown => 115,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolder => 116,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolderOpen => 117,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaCodeFork => 118,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaGit => 119,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaGithub => 120,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFile => 121,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFileCode => 122,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaDownload => 123,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaUpload => 124,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaRocket => 125,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaWrench => 126,
+            Self::NfFaClock => 127,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaLock => 128,
+            Self::NfFaKey => 129,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaDatabase => 130,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaHourglass => 131,
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeCcCc => 200,
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeEqual => 201,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGit => 300,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitBranch => 301,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitCommit => 302,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitMerge => 303,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevRust => 304,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevPython => 305,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevJavascript => 306,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevDocker => 307,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevLinux => 308,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevApple => 309,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevWindows => 310,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDotFill => 400,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCommit => 401,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitPullRequest => 402,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitMerge => 403,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCompare => 404,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctTag => 405,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctRepo => 406,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDiff => 407,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctPackage => 408,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherDaySunny => 500,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherCloudy => 501,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherRain => 502,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherNightClear => 503,
+            Self::UnicodeCrossMark => 1000,
+            Self::UnicodeCheckMark => 1001,
+            Self::UnicodeInformationSource => 1002,
+            Self::UnicodeGear => 1003,
+            Self::UnicodeWarningSign => 1004,
+            Self::UnicodeBug => 1005,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFile => 1006,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFileCode => 1007,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodePackage => 1008,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeDownload => 1009,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeUpload => 1010,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeRocket => 1011,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeWrench => 1012,
+            Self::UnicodeClock => 1013,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeLock => 1014,
+            Self::UnicodeKey => 1015,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeDatabase => 1016,
+            Self::UnicodeQuestion => 1017,
+            Self::UnicodeQuestionCircle => 1018,
+            Self::UnicodeTerminal => 1019,
+            Self::UnicodeTrash => 1020,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleRight => 1021,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleLeft => 1022,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleUp => 1023,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleDown => 1024,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeThumbsUp => 1025,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeThumbsDown => 1026,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFolder => 1027,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFolderOpen => 1028,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+            Self::UnicodeCcCc => 1029,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+            Self::UnicodeEqual => 1030,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeDotFill => 1031,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeCodeFork => 1032,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa", feature = "icons-dev"))]
+            Self::UnicodeGit => 1033,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeGithub => 1034,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeGitBranch => 1035,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev", feature = "icons-oct"))]
+            Self::UnicodeGitCommit => 1036,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev", feature = "icons-oct"))]
+            Self::UnicodeGitMerge => 1037,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeGitPullRequest => 1038,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeGitCompare => 1039,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeTag => 1040,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeRepo => 1041,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeDiff => 1042,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeHourglass => 1043,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeDaySunny => 1044,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeCloudy => 1045,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeRain => 1046,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeNightClear => 1047,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeRust => 1048,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodePython => 1049,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeJavascript => 1050,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeDocker => 1051,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeLinux => 1052,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeApple => 1053,
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeWindows => 1054,
+        }
+    }
+
+    /// Returns the icon of the stable numeric ID `id`, see `id()`, `None` for unknown IDs.
+    #[must_use]
+    pub fn from_id(id: u16) -> Option<Self> {
+        all::<Self>().find(|icon_kind| icon_kind.id() == id)
+    }
+
+    /// Returns the stable name of the icon, e.g. to store it in a configuration file.
+    ///
+    /// Names never change across versions, even if a variant is renamed, and are accepted by
+    /// `from_name()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::IconKind;
+    ///
+    /// assert_eq!(IconKind::UnicodeWarningSign.as_str(), "UnicodeWarningSign");
+    /// assert_eq!(IconKind::from_name("UnicodeWarningSign"), Some(IconKind::UnicodeWarningSign));
+    /// ```
+    #[must_use]
+    #[rustfmt::skip]
+    #[allow(clippy::too_many_lines)]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::NfFaTimes => "NfFaTimes",
+            Self::NfFaCheck => "NfFaCheck",
+            Self::NfFaInfoCircle => "NfFaInfoCircle",
+            Self::NfFaRefresh => "NfFaRefresh",
+            Self::NfFaWarning => "NfFaWarning",
+            Self::NfFaBug => "NfFaBug",
+            Self::NfFaQuestion => "NfFaQuestion",
+            Self::NfFaQuestionCircle => "NfFaQuestionCircle",
+            Self::NfFaTerminal => "NfFaTerminal",
+            Self::NfFaTrash => "NfFaTrash",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleRight => "NfFaAngleRight",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleLeft => "NfFaAngleLeft",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleUp => "NfFaAngleUp",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleDown => "NfFaAngleDown",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsUp => "NfFaThumbsUp",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsDown => "NfFaThumbsDown",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolder => "NfFaFolder",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolderOpen => "NfFaFolderOpen",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaCodeFork => "NfFaCodeFork",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaGit => "NfFaGit",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaGithub => "NfFaGithub",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFile => "NfFaFile",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFileCode => "NfFaFileCode",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaDownload => "NfFaDownload",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaUpload => "NfFaUpload",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaRocket => "NfFaRocket",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaWrench => "NfFaWrench",
+            Self::NfFaClock => "NfFaClock",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaLock => "NfFaLock",
+            Self::NfFaKey => "NfFaKey",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaDatabase => "NfFaDatabase",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaHourglass => "NfFaHourglass",
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeCcCc => "NfFaeCcCc",
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeEqual => "NfFaeEqual",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGit => "NfDevGit",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitBranch => "NfDevGitBranch",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitCommit => "NfDevGitCommit",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitMerge => "NfDevGitMerge",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevRust => "NfDevRust",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevPython => "NfDevPython",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevJavascript => "NfDevJavascript",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevDocker => "NfDevDocker",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevLinux => "NfDevLinux",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevApple => "NfDevApple",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevWindows => "NfDevWindows",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDotFill => "NfOctDotFill",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCommit => "NfOctGitCommit",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitPullRequest => "NfOctGitPullRequest",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitMerge => "NfOctGitMerge",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCompare => "NfOctGitCompare",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctTag => "NfOctTag",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctRepo => "NfOctRepo",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDiff => "NfOctDiff",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctPackage => "NfOctPackage",
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherDaySunny => "NfWeatherDaySunny",
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherCloudy => "NfWeatherCloudy",
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherRain => "NfWeatherRain",
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherNightClear => "NfWeatherNightClear",
+            Self::UnicodeCrossMark => "UnicodeCrossMark",
+            Self::UnicodeCheckMark => "UnicodeCheckMark",
+            Self::UnicodeInformationSource => "UnicodeInformationSource",
+            Self::UnicodeGear => "UnicodeGear",
+            Self::UnicodeWarningSign => "UnicodeWarningSign",
+            Self::UnicodeBug => "UnicodeBug",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFile => "UnicodeFile",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFileCode => "UnicodeFileCode",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodePackage => "UnicodePackage",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeDownload => "UnicodeDownload",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeUpload => "UnicodeUpload",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeRocket => "UnicodeRocket",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeWrench => "UnicodeWrench",
+            Self::UnicodeClock => "UnicodeClock",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeLock => "UnicodeLock",
+            Self::UnicodeKey => "UnicodeKey",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeDatabase => "UnicodeDatabase",
+            Self::UnicodeQuestion => "UnicodeQuestion",
+            Self::UnicodeQuestionCircle => "UnicodeQuestionCircle",
+            Self::UnicodeTerminal => "UnicodeTerminal",
+            Self::UnicodeTrash => "UnicodeTrash",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleRight => "UnicodeAngleRight",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleLeft => "UnicodeAngleLeft",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleUp => "UnicodeAngleUp",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleDown => "UnicodeAngleDown",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeThumbsUp => "UnicodeThumbsUp",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeThumbsDown => "UnicodeThumbsDown",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFolder => "UnicodeFolder",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFolderOpen => "UnicodeFolderOpen",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+            Self::UnicodeCcCc => "UnicodeCcCc",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+            Self::UnicodeEqual => "UnicodeEqual",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeDotFill => "UnicodeDotFill",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeCodeFork => "UnicodeCodeFork",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa", feature = "icons-dev"))]
+            Self::UnicodeGit => "UnicodeGit",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeGithub => "UnicodeGithub",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeGitBranch => "UnicodeGitBranch",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev", feature = "icons-oct"))]
+            Self::UnicodeGitCommit => "UnicodeGitCommit",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev", feature = "icons-oct"))]
+            Self::UnicodeGitMerge => "UnicodeGitMerge",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeGitPullRequest => "UnicodeGitPullRequest",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeGitCompare => "UnicodeGitCompare",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeTag => "UnicodeTag",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeRepo => "UnicodeRepo",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeDiff => "UnicodeDiff",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeHourglass => "UnicodeHourglass",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeDaySunny => "UnicodeDaySunny",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeCloudy => "UnicodeCloudy",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeRain => "UnicodeRain",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeNightClear => "UnicodeNightClear",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeRust => "UnicodeRust",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodePython => "UnicodePython",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeJavascript => "UnicodeJavascript",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeDocker => "UnicodeDocker",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeLinux => "UnicodeLinux",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeApple => "UnicodeApple",
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeWindows => "UnicodeWindows",
+        }
+    }
+
+    /// Returns the `Unicode` counterpart of a `NerdFont` icon.
+    ///
+    /// Every `Nf` variant has a `Unicode` variant with the same name stem, except for the original
+    /// status icons which keep their historical pairing (`NfFaTimes` maps to `UnicodeCrossMark`,
+    /// `NfFaRefresh` to `UnicodeGear`, ...). `Unicode` variants are returned unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::IconKind;
+    ///
+    /// # #[cfg(feature = "icons-fa")]
+    /// assert_eq!(IconKind::NfFaFolder.to_unicode(), IconKind::UnicodeFolder);
+    /// assert_eq!(IconKind::NfFaTimes.to_unicode(), IconKind::UnicodeCrossMark);
+    /// assert_eq!(IconKind::UnicodeBug.to_unicode(), IconKind::UnicodeBug);
+    /// ```
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn to_unicode(&self) -> Self {
+        #[allow(clippy::match_same_arms)]
+        match self {
+            Self::NfFaTimes => Self::UnicodeCrossMark,
+            Self::NfFaCheck => Self::UnicodeCheckMark,
+            Self::NfFaInfoCircle => Self::UnicodeInformationSource,
+            Self::NfFaRefresh => Self::UnicodeGear,
+            Self::NfFaWarning => Self::UnicodeWarningSign,
+            Self::NfFaBug => Self::UnicodeBug,
+            Self::NfFaQuestion => Self::UnicodeQuestion,
+            Self::NfFaQuestionCircle => Self::UnicodeQuestionCircle,
+            Self::NfFaTerminal => Self::UnicodeTerminal,
+            Self::NfFaTrash => Self::UnicodeTrash,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleRight => Self::UnicodeAngleRight,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleLeft => Self::UnicodeAngleLeft,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleUp => Self::UnicodeAngleUp,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleDown => Self::UnicodeAngleDown,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsUp => Self::UnicodeThumbsUp,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsDown => Self::UnicodeThumbsDown,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolder => Self::UnicodeFolder,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolderOpen => Self::UnicodeFolderOpen,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaCodeFork => Self::UnicodeCodeFork,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaGit => Self::UnicodeGit,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaGithub => Self::UnicodeGithub,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFile => Self::UnicodeFile,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFileCode => Self::UnicodeFileCode,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaDownload => Self::UnicodeDownload,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaUpload => Self::UnicodeUpload,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaRocket => Self::UnicodeRocket,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaWrench => Self::UnicodeWrench,
+            Self::NfFaClock => Self::UnicodeClock,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaLock => Self::UnicodeLock,
+            Self::NfFaKey => Self::UnicodeKey,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaDatabase => Self::UnicodeDatabase,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaHourglass => Self::UnicodeHourglass,
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeCcCc => Self::UnicodeCcCc,
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeEqual => Self::UnicodeEqual,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDotFill => Self::UnicodeDotFill,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCommit => Self::UnicodeGitCommit,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitPullRequest => Self::UnicodeGitPullRequest,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitMerge => Self::UnicodeGitMerge,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCompare => Self::UnicodeGitCompare,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctTag => Self::UnicodeTag,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctRepo => Self::UnicodeRepo,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDiff => Self::UnicodeDiff,
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctPackage => Self::UnicodePackage,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherDaySunny => Self::UnicodeDaySunny,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherCloudy => Self::UnicodeCloudy,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherRain => Self::UnicodeRain,
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherNightClear => Self::UnicodeNightClear,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGit => Self::UnicodeGit,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitBranch => Self::UnicodeGitBranch,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitCommit => Self::UnicodeGitCommit,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitMerge => Self::UnicodeGitMerge,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevRust => Self::UnicodeRust,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevPython => Self::UnicodePython,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevJavascript => Self::UnicodeJavascript,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevDocker => Self::UnicodeDocker,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevLinux => Self::UnicodeLinux,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevApple => Self::UnicodeApple,
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevWindows => Self::UnicodeWindows,
+            unicode => unicode.clone(),
+        }
+    }
+
+    /// Returns the `NerdFont` counterpart of a `Unicode` icon.
+    ///
+    /// When several `Nf` variants share a `Unicode` counterpart (e.g. `NfOctGitCommit` and
+    /// `NfDevGitCommit`), the `NfOct` or `NfFa` variant is returned. `Nf` variants are returned unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::IconKind;
+    ///
+    /// assert_eq!(IconKind::UnicodeCheckMark.to_nerd_font(), IconKind::NfFaCheck);
+    /// assert_eq!(IconKind::NfFaCheck.to_nerd_font(), IconKind::NfFaCheck);
+    /// ```
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn to_nerd_font(&self) -> Self {
+        match self {
+            Self::UnicodeCrossMark => Self::NfFaTimes,
+            Self::UnicodeCheckMark => Self::NfFaCheck,
+            Self::UnicodeInformationSource => Self::NfFaInfoCircle,
+            Self::UnicodeGear => Self::NfFaRefresh,
+            Self::UnicodeWarningSign => Self::NfFaWarning,
+            Self::UnicodeBug => Self::NfFaBug,
+            Self::UnicodeQuestion => Self::NfFaQuestion,
+            Self::UnicodeQuestionCircle => Self::NfFaQuestionCircle,
+            Self::UnicodeTerminal => Self::NfFaTerminal,
+            Self::UnicodeTrash => Self::NfFaTrash,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeAngleRight => Self::NfFaAngleRight,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeAngleLeft => Self::NfFaAngleLeft,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeAngleUp => Self::NfFaAngleUp,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeAngleDown => Self::NfFaAngleDown,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeThumbsUp => Self::NfFaThumbsUp,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeThumbsDown => Self::NfFaThumbsDown,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeFolder => Self::NfFaFolder,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeFolderOpen => Self::NfFaFolderOpen,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeCodeFork => Self::NfFaCodeFork,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeGit => Self::NfFaGit,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeGithub => Self::NfFaGithub,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeFile => Self::NfFaFile,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeFileCode => Self::NfFaFileCode,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeDownload => Self::NfFaDownload,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeUpload => Self::NfFaUpload,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeRocket => Self::NfFaRocket,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeWrench => Self::NfFaWrench,
+            Self::UnicodeClock => Self::NfFaClock,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeLock => Self::NfFaLock,
+            Self::UnicodeKey => Self::NfFaKey,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeDatabase => Self::NfFaDatabase,
+            #[cfg(feature = "icons-fa")]
+            Self::UnicodeHourglass => Self::NfFaHourglass,
+            #[cfg(feature = "icons-fae")]
+            Self::UnicodeCcCc => Self::NfFaeCcCc,
+            #[cfg(feature = "icons-fae")]
+            Self::UnicodeEqual => Self::NfFaeEqual,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodeDotFill => Self::NfOctDotFill,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodeGitCommit => Self::NfOctGitCommit,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodeGitPullRequest => Self::NfOctGitPullRequest,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodeGitMerge => Self::NfOctGitMerge,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodeGitCompare => Self::NfOctGitCompare,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodeTag => Self::NfOctTag,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodeRepo => Self::NfOctRepo,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodeDiff => Self::NfOctDiff,
+            #[cfg(feature = "icons-oct")]
+            Self::UnicodePackage => Self::NfOctPackage,
+            #[cfg(feature = "icons-weather")]
+            Self::UnicodeDaySunny => Self::NfWeatherDaySunny,
+            #[cfg(feature = "icons-weather")]
+            Self::UnicodeCloudy => Self::NfWeatherCloudy,
+            #[cfg(feature = "icons-weather")]
+            Self::UnicodeRain => Self::NfWeatherRain,
+            #[cfg(feature = "icons-weather")]
+            Self::UnicodeNightClear => Self::NfWeatherNightClear,
+            #[cfg(feature = "icons-dev")]
+            Self::UnicodeRust => Self::NfDevRust,
+            #[cfg(feature = "icons-dev")]
+            Self::UnicodePython => Self::NfDevPython,
+            #[cfg(feature = "icons-dev")]
+            Self::UnicodeJavascript => Self::NfDevJavascript,
+            #[cfg(feature = "icons-dev")]
+            Self::UnicodeDocker => Self::NfDevDocker,
+            #[cfg(feature = "icons-dev")]
+            Self::UnicodeLinux => Self::NfDevLinux,
+            #[cfg(feature = "icons-dev")]
+            Self::UnicodeApple => Self::NfDevApple,
+            #[cfg(feature = "icons-dev")]
+            Self::UnicodeWindows => Self::NfDevWindows,
+            #[cfg(feature = "icons-dev")]
+            Self::UnicodeGitBranch => Self::NfDevGitBranch,
+            nerd_font => nerd_font.clone(),
         }
     }
 }
 
 /// Red color.
-const RED: &str = "red";
+const RED: Color = Color::Red;
 /// Green color.
-const GREEN: &str = "green";
+const GREEN: Color = Color::Green;
 /// White color.
-const WHITE: &str = "white";
+const WHITE: Color = Color::White;
 /// Yellow color.
-const YELLOW: &str = "yellow";
+const YELLOW: Color = Color::Yellow;
 /// Cyan color.
-const CYAN: &str = "cyan";
+const CYAN: Color = Color::Cyan;
+
+impl IconKind {
+    /// Returns the glyph of the icon, followed by a single space.
+    ///
+    /// The glyphs are constant data, so they can be used in `const` contexts.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::IconKind;
+    ///
+    /// const DONE: &str = IconKind::UnicodeCheckMark.glyph();
+    /// assert_eq!(DONE, "\u{2714}\u{fe0f} ");
+    /// ```
+    #[must_use]
+    pub const fn glyph(&self) -> &'static str {
+        self.glyph_and_color().0
+    }
+
+    /// Returns the default color of the icon, e.g. `Color::Red` for `NfFaTimes`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{Color, IconKind};
+    ///
+    /// const ERROR_COLOR: Color = IconKind::NfFaTimes.default_color();
+    /// assert_eq!(ERROR_COLOR, Color::Red);
+    /// ```
+    #[must_use]
+    pub const fn default_color(&self) -> Color {
+        self.glyph_and_color().1
+    }
+
+    /// Returns the glyph and the default color of the icon, from the constant icon table.
+    ///
+    /// The glyph is followed by a single space.
+    #[rustfmt::skip]
+    #[allow(clippy::too_many_lines)]
+    pub(crate) const fn glyph_and_color(&self) -> (&'static str, Color) {
+        match self {
+            // Nerd Font Font Awesome icons
+            Self::NfFaTimes => ("\u{f00d} ", RED), // 
+            Self::NfFaCheck => ("\u{f00c} ", GREEN), // 
+            Self::NfFaInfoCircle => ("\u{f05a} ", WHITE), // 
+            Self::NfFaRefresh => ("\u{f021} ", CYAN), // 
+            Self::NfFaWarning => ("\u{f071} ", YELLOW), // 
+            Self::NfFaBug => ("\u{f188} ", RED), // 
+            Self::NfFaQuestion => ("\u{f128} ", RED), // 
+            Self::NfFaQuestionCircle => ("\u{f059} ", RED), // 
+            Self::NfFaTerminal => ("\u{f120} ", WHITE), // 
+            Self::NfFaTrash => ("\u{f1f8} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleRight => ("\u{f105} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleLeft => ("\u{f104} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleUp => ("\u{f106} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaAngleDown => ("\u{f107} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsUp => ("\u{f164} ", GREEN), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsDown => ("\u{f165} ", RED), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolder => ("\u{f07b} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolderOpen => ("\u{f07c} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaCodeFork => ("\u{f126} ", CYAN), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaGit => ("\u{f1d3} ", RED), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaGithub => ("\u{f09b} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFile => ("\u{f15b} ", WHITE), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFileCode => ("\u{f1c9} ", CYAN), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaDownload => ("\u{f019} ", CYAN), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaUpload => ("\u{f093} ", CYAN), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaRocket => ("\u{f135} ", GREEN), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaWrench => ("\u{f0ad} ", CYAN), // 
+            Self::NfFaClock => ("\u{f017} ", YELLOW), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaLock => ("\u{f023} ", YELLOW), // 
+            Self::NfFaKey => ("\u{f084} ", YELLOW), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaDatabase => ("\u{f1c0} ", CYAN), // 
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaHourglass => ("\u{f254} ", YELLOW), // 
+
+            // Nerd Font Font Awesome Extension icons
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeCcCc => ("\u{e291} ", WHITE), // 
+            #[cfg(feature = "icons-fae")]
+            Self::NfFaeEqual => ("\u{e279} ", WHITE), // 
+
+            // Nerd Font Devicons
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGit => ("\u{e702} ", RED), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitBranch => ("\u{e725} ", CYAN), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitCommit => ("\u{e729} ", YELLOW), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevGitMerge => ("\u{e727} ", CYAN), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevRust => ("\u{e7a8} ", RED), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevPython => ("\u{e73c} ", YELLOW), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevJavascript => ("\u{e74e} ", YELLOW), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevDocker => ("\u{e7b0} ", CYAN), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevLinux => ("\u{e712} ", WHITE), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevApple => ("\u{e711} ", WHITE), // 
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevWindows => ("\u{e70f} ", CYAN), // 
+
+            // Nerd Font Oct-icons
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDotFill => ("\u{f444} ", WHITE), // 
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCommit => ("\u{f417} ", YELLOW), // 
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitPullRequest => ("\u{f407} ", GREEN), // 
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitMerge => ("\u{f419} ", CYAN), // 
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctGitCompare => ("\u{f47f} ", CYAN), // 
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctTag => ("\u{f412} ", YELLOW), // 
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctRepo => ("\u{f401} ", WHITE), // 
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctDiff => ("\u{f440} ", YELLOW), // 
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctPackage => ("\u{f487} ", YELLOW), // 
 
-// pub static ICON_MAP: Lazy<Mutex<HashMap<IconKind, (&'static str, &'static str)>>>
+            // Nerd Font Weather icons
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherDaySunny => ("\u{e30d} ", YELLOW), // 
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherCloudy => ("\u{e312} ", WHITE), // 
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherRain => ("\u{e318} ", CYAN), // 
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherNightClear => ("\u{e32b} ", WHITE), // 
+
+            // Unicode icons
+            Self::UnicodeInformationSource => ("\u{2139}\u{fe0f} ", WHITE), // ℹ️
+            Self::UnicodeGear => ("\u{2699}\u{FE0F} ", CYAN), // ⚙️
+            Self::UnicodeWarningSign => ("\u{26A0}\u{FE0F} ", YELLOW), // ⚠️
+            Self::UnicodeBug => ("\u{1F41B} ", RED), // 🐛
+            Self::UnicodeCrossMark => ("\u{274C} ", RED), // ❌
+            Self::UnicodeCheckMark => ("\u{2714}\u{FE0F} ", GREEN), // ✔️
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFile => ("\u{1F4C4} ", WHITE), // 📄
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFileCode => ("\u{1F4DD} ", CYAN), // 📝
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodePackage => ("\u{1F4E6} ", YELLOW), // 📦
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeDownload => ("\u{1F4E5} ", CYAN), // 📥
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeUpload => ("\u{1F4E4} ", CYAN), // 📤
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeRocket => ("\u{1F680} ", GREEN), // 🚀
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeWrench => ("\u{1F527} ", CYAN), // 🔧
+            Self::UnicodeClock => ("\u{1F552} ", YELLOW), // 🕒
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeLock => ("\u{1F512} ", YELLOW), // 🔒
+            Self::UnicodeKey => ("\u{1F511} ", YELLOW), // 🔑
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeDatabase => ("\u{1F5C4}\u{FE0F} ", CYAN), // 🗄️
+            Self::UnicodeQuestion => ("\u{2753} ", RED), // ❓
+            Self::UnicodeQuestionCircle => ("\u{2754} ", RED), // ❔
+            Self::UnicodeTerminal => ("\u{2328}\u{FE0F} ", WHITE), // ⌨️
+            Self::UnicodeTrash => ("\u{1F5D1}\u{FE0F} ", WHITE), // 🗑️
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleRight => ("\u{2192} ", WHITE), // →
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleLeft => ("\u{2190} ", WHITE), // ←
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleUp => ("\u{2191} ", WHITE), // ↑
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeAngleDown => ("\u{2193} ", WHITE), // ↓
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeThumbsUp => ("\u{1F44D} ", GREEN), // 👍
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeThumbsDown => ("\u{1F44E} ", RED), // 👎
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFolder => ("\u{1F4C1} ", WHITE), // 📁
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeFolderOpen => ("\u{1F4C2} ", WHITE), // 📂
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+            Self::UnicodeCcCc => ("\u{1F16D} ", WHITE), // 🅭
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fae"))]
+            Self::UnicodeEqual => ("\u{229C} ", WHITE), // ⊜
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeDotFill => ("\u{25CF} ", WHITE), // ●
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeCodeFork => ("\u{2442} ", CYAN), // ⑂
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa", feature = "icons-dev"))]
+            Self::UnicodeGit => ("\u{2325} ", RED), // ⌥
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeGithub => ("\u{1F419} ", WHITE), // 🐙
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeGitBranch => ("\u{2387} ", CYAN), // ⎇
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev", feature = "icons-oct"))]
+            Self::UnicodeGitCommit => ("\u{2299} ", YELLOW), // ⊙
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev", feature = "icons-oct"))]
+            Self::UnicodeGitMerge => ("\u{1F500} ", CYAN), // 🔀
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeGitPullRequest => ("\u{21C4} ", GREEN), // ⇄
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeGitCompare => ("\u{21C6} ", CYAN), // ⇆
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeTag => ("\u{1F3F7}\u{FE0F} ", YELLOW), // 🏷️
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeRepo => ("\u{1F4DA} ", WHITE), // 📚
+            #[cfg(any(feature = "icons-unicode", feature = "icons-oct"))]
+            Self::UnicodeDiff => ("\u{00B1} ", YELLOW), // ±
+            #[cfg(any(feature = "icons-unicode", feature = "icons-fa"))]
+            Self::UnicodeHourglass => ("\u{23F3} ", YELLOW), // ⏳
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeDaySunny => ("\u{2600}\u{FE0F} ", YELLOW), // ☀️
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeCloudy => ("\u{2601}\u{FE0F} ", WHITE), // ☁️
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeRain => ("\u{1F327}\u{FE0F} ", CYAN), // 🌧️
+            #[cfg(any(feature = "icons-unicode", feature = "icons-weather"))]
+            Self::UnicodeNightClear => ("\u{1F319} ", WHITE), // 🌙
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeRust => ("\u{1F980} ", RED), // 🦀
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodePython => ("\u{1F40D} ", YELLOW), // 🐍
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeJavascript => ("\u{1F4DC} ", YELLOW), // 📜
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeDocker => ("\u{1F433} ", CYAN), // 🐳
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeLinux => ("\u{1F427} ", WHITE), // 🐧
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeApple => ("\u{1F34E} ", WHITE), // 🍎
+            #[cfg(any(feature = "icons-unicode", feature = "icons-dev"))]
+            Self::UnicodeWindows => ("\u{1FA9F} ", CYAN), // 🪟
+        }
+    }
+}
 
 /// A static `ICON_MAP` that maps `IconKind` to a tuple of icon and color.
 ///
-/// This map is lazily initialized and thread-safe. It contains mappings for both `NerdFont` and Unicode icons.
+/// This map is lazily initialized from the constant icon table by a `LazyLock` and thread-safe. It contains mappings for both `NerdFont` and Unicode icons.
 /// Each `IconKind` is mapped to a tuple, where the first element is the icon character and the second element is the color.
 ///
 /// The `ICON_MAP` is used by the `Whisper` struct to look up the icon and color based on the `IconKind`.
-///
-/// If the `tracing` feature is enabled, an informational message will be logged when the `ICON_MAP` is initialized.
-pub static ICON_MAP: Lazy<RwLock<HashMap<IconKind, (&'static str, &'static str)>>> =
-    Lazy::new(|| {
-        let mut i_map = HashMap::new();
-
-        // Nerd Font Font Awesome icons
-        i_map.insert(IconKind::NfFaTimes, ("\u{f00d} ", RED)); // 
-        i_map.insert(IconKind::NfFaCheck, ("\u{f00c} ", GREEN)); // 
-        i_map.insert(IconKind::NfFaInfoCircle, ("\u{f05a} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaRefresh, ("\u{f021} ", CYAN)); // 
-        i_map.insert(IconKind::NfFaWarning, ("\u{f071} ", YELLOW)); // 
-        i_map.insert(IconKind::NfFaBug, ("\u{f188} ", RED)); // 
-        i_map.insert(IconKind::NfFaQuestion, ("\u{f128} ", RED)); // 
-        i_map.insert(IconKind::NfFaQuestionCircle, ("\u{f059} ", RED)); // 
-        i_map.insert(IconKind::NfFaTerminal, ("\u{f120} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaTrash, ("\u{f1f8} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaAngleRight, ("\u{f105} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaAngleLeft, ("\u{f104} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaAngleUp, ("\u{f106} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaAngleDown, ("\u{f107} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaThumbsUp, ("\u{f164} ", GREEN)); // 
-        i_map.insert(IconKind::NfFaThumbsDown, ("\u{f165} ", RED)); // 
-        i_map.insert(IconKind::NfFaFolder, ("\u{f07b} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaFolderOpen, ("\u{f07c} ", WHITE)); // 
-
-        // Nerd Font Font Awesome Extension icons
-        i_map.insert(IconKind::NfFaeCcCc, ("\u{e291} ", WHITE)); // 
-        i_map.insert(IconKind::NfFaeEqual, ("\u{e279} ", WHITE)); // 
-
-        // Nerd Font Oct-icons
-        i_map.insert(IconKind::NfOctDotFill, ("\u{f444} ", WHITE)); // 
-
-        // Unicode icons
-        #[rustfmt::skip]
-        i_map.insert(IconKind::UnicodeInformationSource, ("\u{2139}\u{fe0f} ", WHITE)); // ℹ️
-        i_map.insert(IconKind::UnicodeGear, ("\u{2699}\u{FE0F} ", CYAN)); // ⚙️
-        i_map.insert(IconKind::UnicodeWarningSign, ("\u{26A0}\u{FE0F} ", YELLOW)); // ⚠️
-        i_map.insert(IconKind::UnicodeBug, ("\u{1F41B} ", RED)); // 🐛
-        i_map.insert(IconKind::UnicodeCrossMark, ("\u{274C} ", RED)); // ❌
-        i_map.insert(IconKind::UnicodeCheckMark, ("\u{2714}\u{FE0F} ", GREEN)); // ✔️
-
+/// It is only available with the `std` feature, without it icons are looked up in the constant table directly.
+#[cfg(feature = "std")]
+pub static ICON_MAP: LazyLock<RwLock<HashMap<IconKind, (&'static str, Color)>>> =
+    LazyLock::new(|| {
+        let i_map = all::<IconKind>()
+            .map(|icon_kind| {
+                let glyph = icon_kind.glyph_and_color();
+                (icon_kind, glyph)
+            })
+            .collect();
         RwLock::new(i_map)
     });
 
+/// `UnknownIconPolicy` decides how whispers with an icon missing from `ICON_MAP` are printed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum UnknownIconPolicy {
+    /// The icon is printed as a visible `?` placeholder.
+    #[default]
+    Placeholder,
+    /// `whisper()` returns `WhisperError::UnknownIcon`.
+    Error,
+}
+
+/// The glyph printed for icons missing from `ICON_MAP` with `UnknownIconPolicy::Placeholder`.
+#[cfg(feature = "std")]
+const PLACEHOLDER: &str = "? ";
+
+/// The global `UnknownIconPolicy`, stored as its discriminant.
+#[cfg(feature = "std")]
+static UNKNOWN_ICON_POLICY: AtomicU8 = AtomicU8::new(UnknownIconPolicy::Placeholder as u8);
+
+/// Sets the global `UnknownIconPolicy`, `UnknownIconPolicy::Placeholder` by default.
+///
+/// # Examples
+/// ```
+/// use murmur::UnknownIconPolicy;
+///
+/// murmur::set_unknown_icon_policy(UnknownIconPolicy::Error);
+/// ```
+#[cfg(feature = "std")]
+pub fn set_unknown_icon_policy(policy: UnknownIconPolicy) {
+    UNKNOWN_ICON_POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// Returns the global `UnknownIconPolicy`.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn unknown_icon_policy() -> UnknownIconPolicy {
+    match UNKNOWN_ICON_POLICY.load(Ordering::Relaxed) {
+        x if x == UnknownIconPolicy::Error as u8 => UnknownIconPolicy::Error,
+        _ => UnknownIconPolicy::Placeholder,
+    }
+}
+
+/// Returns the glyph and color of `icon_kind` in `ICON_MAP`, see `resolve`.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the icon map lock is poisoned, and `WhisperError::UnknownIcon`
+/// if the icon is missing with `UnknownIconPolicy::Error`.
+#[cfg(feature = "std")]
+pub fn lookup(icon_kind: &IconKind) -> Result<(&'static str, Option<Color>), WhisperError> {
+    let entry = ICON_MAP
+        .read()
+        .map_err(|_| WhisperError::Lock)?
+        .get(icon_kind)
+        .copied();
+    resolve(icon_kind, entry, unknown_icon_policy())
+}
+
+/// Returns the glyph and color of the `entry` of `icon_kind`, or the placeholder without color
+/// if it is missing, following `policy`.
+#[cfg(feature = "std")]
+fn resolve(
+    icon_kind: &IconKind,
+    entry: Option<(&'static str, Color)>,
+    policy: UnknownIconPolicy,
+) -> Result<(&'static str, Option<Color>), WhisperError> {
+    match (entry, policy) {
+        (Some((glyph, color)), _) => Ok((glyph, Some(color))),
+        (None, UnknownIconPolicy::Placeholder) => Ok((PLACEHOLDER, None)),
+        (None, UnknownIconPolicy::Error) => Err(WhisperError::UnknownIcon(icon_kind.clone())),
+    }
+}
+
+/// Returns the characters of `name` in lowercase, without dashes and underscores.
+fn normalized(name: &str) -> impl Iterator<Item = char> + '_ {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_'))
+        .map(|c| c.to_ascii_lowercase())
+}
+
+/// Returns the name of the `IconKind` variant `variant` without its `Nf...` or `Unicode` prefix.
+fn short_name(variant: &str) -> &str {
+    if let Some(short) = variant.strip_prefix("Unicode") {
+        return short;
+    }
+    // Skip the set of the Nerd Font, e.g. `Fa` or `Oct`, up to the next capital
+    variant
+        .strip_prefix("Nf")
+        .and_then(|rest| {
+            rest.char_indices()
+                .skip(1)
+                .find(|(_, c)| c.is_ascii_uppercase())
+        })
+        .map_or(variant, |(index, _)| &variant[2 + index..])
+}
+
 #[cfg(test)]
+#[cfg(feature = "std")]
 mod icon_map_tests {
     use color_eyre::Report;
-    use enum_iterator::all;
 
     use crate::Whisper;
 
     use super::*;
 
+    #[test]
+    fn test_short_name() {
+        assert_eq!(short_name("NfFaInfoCircle"), "InfoCircle");
+        assert_eq!(short_name("NfFaeCcCc"), "CcCc");
+        assert_eq!(short_name("NfOctGitMerge"), "GitMerge");
+        assert_eq!(short_name("UnicodeCheckMark"), "CheckMark");
+    }
+
+    #[test]
+    fn test_resolve_unknown_icon() {
+        let entry = Some(("\u{f00c} ", Color::Green));
+        let icon_kind = IconKind::NfFaCheck;
+        let policy = UnknownIconPolicy::Error;
+        assert_eq!(
+            resolve(&icon_kind, entry, policy).unwrap(),
+            ("\u{f00c} ", Some(Color::Green))
+        );
+        let placeholder = resolve(&icon_kind, None, UnknownIconPolicy::Placeholder);
+        assert_eq!(placeholder.unwrap(), ("? ", None));
+        assert!(matches!(
+            resolve(&icon_kind, None, policy),
+            Err(WhisperError::UnknownIcon(IconKind::NfFaCheck))
+        ));
+    }
+
     #[test]
     fn test_color_eyre_install_setup() -> Result<(), Report> {
         color_eyre::install()?;
@@ -231,4 +1813,120 @@ mod icon_map_tests {
             );
         }
     }
+
+    #[test]
+    fn test_every_nerd_font_icon_has_unicode_counterpart() {
+        for icon_kind in all::<IconKind>() {
+            let unicode = icon_kind.to_unicode();
+            assert!(
+                format!("{unicode:?}").starts_with("Unicode"),
+                "{icon_kind:?} has no Unicode counterpart"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "icons")]
+    fn test_unicode_nerd_font_round_trip() {
+        for icon_kind in all::<IconKind>().filter(|kind| format!("{kind:?}").starts_with("Unicode"))
+        {
+            let nerd_font = icon_kind.to_nerd_font();
+            assert!(format!("{nerd_font:?}").starts_with("Nf"));
+            assert_eq!(nerd_font.to_unicode(), icon_kind);
+        }
+    }
+
+    #[test]
+    fn test_spinner_frames_have_no_spacing() {
+        for spinner_kind in all::<SpinnerKind>() {
+            let frames = IconKind::frames(spinner_kind);
+            assert!(
+                frames.len() > 1,
+                "{spinner_kind:?} needs at least two frames"
+            );
+            for frame in frames {
+                assert!(!frame.is_empty() && !frame.contains(' '));
+            }
+        }
+    }
+
+    #[test]
+    fn test_unicode_icons_share_the_category_of_their_counterpart() {
+        for icon_kind in all::<IconKind>() {
+            assert_eq!(icon_kind.to_nerd_font().category(), icon_kind.category());
+        }
+        assert_eq!(IconKind::UnicodeCrossMark.category(), Category::Status);
+        assert_eq!(IconKind::UnicodeClock.category(), Category::Misc);
+        #[cfg(feature = "icons")]
+        {
+            assert_eq!(IconKind::NfDevGitBranch.category(), Category::Vcs);
+            assert_eq!(IconKind::NfFaHourglass.category(), Category::Misc);
+            assert_eq!(IconKind::UnicodeRocket.category(), Category::Misc);
+        }
+    }
+
+    #[test]
+    fn test_ids_and_names_are_stable() {
+        let mut ids = all::<IconKind>()
+            .map(|icon_kind| icon_kind.id())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), all::<IconKind>().count());
+        for icon_kind in all::<IconKind>() {
+            assert_eq!(IconKind::from_id(icon_kind.id()), Some(icon_kind.clone()));
+            assert_eq!(IconKind::from_name(icon_kind.as_str()), Some(icon_kind));
+        }
+        // Pinned, changing them breaks stored whispers
+        assert_eq!(IconKind::NfFaTimes.id(), 100);
+        #[cfg(feature = "icons")]
+        {
+            assert_eq!(IconKind::NfOctPackage.id(), 408);
+            assert_eq!(IconKind::UnicodeDiff.id(), 1042);
+        }
+        assert_eq!(IconKind::from_id(0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "icons")]
+    fn test_weather_icons() {
+        assert_eq!(
+            IconKind::from_name("day-sunny"),
+            Some(IconKind::NfWeatherDaySunny)
+        );
+        assert_eq!(IconKind::NfWeatherRain.to_unicode(), IconKind::UnicodeRain);
+        assert_eq!(IconKind::UnicodeNightClear.category(), Category::Weather);
+        assert_eq!(IconKind::NfFaHourglass.category(), Category::Misc);
+        assert_eq!(IconKind::NfWeatherCloudy.id(), 501);
+        assert_eq!(IconKind::UnicodeHourglass.id(), 1043);
+    }
+
+    #[test]
+    #[cfg(feature = "icons")]
+    fn test_devicons() {
+        assert_eq!(IconKind::from_name("rust"), Some(IconKind::NfDevRust));
+        assert_eq!(
+            IconKind::UnicodeDocker.to_nerd_font(),
+            IconKind::NfDevDocker
+        );
+        assert_eq!(
+            IconKind::by_category(Category::Tech).len(),
+            2 * 7,
+            "every logo and its Unicode counterpart"
+        );
+        assert_eq!(IconKind::NfDevRust.id(), 304);
+        assert_eq!(IconKind::UnicodeWindows.id(), 1054);
+    }
+
+    #[test]
+    fn test_ascii_fallbacks() {
+        for icon_kind in all::<IconKind>() {
+            let ascii = icon_kind.ascii();
+            assert!(
+                ascii.len() == 1 && ascii.is_ascii(),
+                "{icon_kind:?}: '{ascii}'"
+            );
+            assert_eq!(ascii, icon_kind.to_unicode().ascii());
+        }
+    }
 }
@@ -0,0 +1,109 @@
+//! The `indent` module nests the whispers emitted by helper functions under their caller.
+//!
+//! `indent_scope` returns a guard indenting every whisper of the current thread by 2 more spaces
+//! while it is alive. Scopes nest, so a helper opening its own scope is indented further when it is
+//! called from another scope, without passing an indent parameter around.
+//!
+//! ```
+//! use murmur::{IconKind, Whisper};
+//!
+//! fn build(step: &str) {
+//!     let _indent = murmur::indent_scope();
+//!     Whisper::new().icon(IconKind::NfFaCheck).message(step).whisper().unwrap();
+//! }
+//!
+//! Whisper::new().icon(IconKind::NfFaInfoCircle).message("release").whisper().unwrap();
+//! {
+//!     let _indent = murmur::indent_scope();
+//!     Whisper::new().message("compiling").whisper().unwrap();
+//!     // Indented by 4 spaces
+//!     build("murmur");
+//! }
+//! ```
+//!
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use crate::{context, RenderedWhisper, Segment, Style};
+
+/// The number of spaces added by each indent scope.
+pub const INDENT_WIDTH: usize = 2;
+
+thread_local! {
+    /// The number of indent scopes alive on the current thread.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// `IndentGuard` indents the whispers of the current thread while it is alive, see `indent_scope`.
+#[derive(Debug)]
+#[must_use = "the indent ends when the guard is dropped"]
+pub struct IndentGuard {
+    /// Keeps the guard on the thread whose whispers it indents.
+    thread: PhantomData<*const ()>,
+}
+
+impl Drop for IndentGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+/// Indents every whisper rendered on the current thread by `INDENT_WIDTH` more spaces until the
+/// returned guard is dropped.
+///
+/// Whispers are indented when they are rendered, a whisper frozen inside a scope keeps its indent.
+pub fn indent_scope() -> IndentGuard {
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+    IndentGuard {
+        thread: PhantomData,
+    }
+}
+
+/// Returns the width of the indent of the whispers rendered on the current thread, from its indent
+/// scopes and its contexts, see `Context::indent()`.
+pub fn width() -> usize {
+    (DEPTH.with(Cell::get) + context::indent()) * INDENT_WIDTH
+}
+
+/// Indents every line of `rendered` by `width` spaces.
+pub fn shift(rendered: &mut RenderedWhisper, width: usize) {
+    if width == 0 {
+        return;
+    }
+    let indent = " ".repeat(width);
+    for line in &mut rendered.segments {
+        line.insert(0, Segment::new(indent.clone(), Style::default()));
+    }
+}
+
+#[cfg(test)]
+mod indent_tests {
+    use super::*;
+    use crate::{RenderOptions, Whisper};
+
+    #[test]
+    fn test_scopes_nest() {
+        assert_eq!(width(), 0);
+        let outer = indent_scope();
+        {
+            let _inner = indent_scope();
+            assert_eq!(width(), 4);
+        }
+        assert_eq!(width(), 2);
+        drop(outer);
+        assert_eq!(width(), 0);
+    }
+
+    #[test]
+    fn test_frozen_whisper_indented() {
+        let _indent = indent_scope();
+        let rendered = Whisper::new()
+            .messages(["first", "second"])
+            .freeze()
+            .unwrap();
+        assert_eq!(rendered.plain_lines(), ["  first", "    second"]);
+        let rendered =
+            RenderedWhisper::new(&Whisper::new().message("kept"), &RenderOptions::plain());
+        assert_eq!(rendered.plain_lines(), ["kept"]);
+    }
+}
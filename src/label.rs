@@ -0,0 +1,101 @@
+//! The `label` module keeps the styling of whispers per label, to decide it in one place.
+//!
+//! Large applications whisper about the same subsystems from many places. `label_config` registers
+//! the icon and color of a label once, and every `Whisper::labeled` whisper of that label picks
+//! them up when it is whispered or frozen. Icons and colors set on the whisper itself win.
+//!
+//! ```
+//! use murmur::{Color, IconKind, LabelConfig, Whisper};
+//!
+//! murmur::label_config(
+//!     "net",
+//!     LabelConfig { icon: Some(IconKind::NfFaRefresh), color: Some(Color::Cyan) },
+//! );
+//! Whisper::labeled("net").message("reconnecting").whisper().unwrap();
+//! ```
+//!
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+use crate::{Color, IconKind, Whisper};
+
+/// `LabelConfig` is the styling of the whispers of a label, see `label_config`.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct LabelConfig {
+    /// The icon of the whispers without icon.
+    pub icon: Option<IconKind>,
+    /// The color of the whispers without color, see `Whisper::color()`.
+    pub color: Option<Color>,
+}
+
+/// The registered labels and their configuration.
+static LABELS: RwLock<Vec<(String, LabelConfig)>> = RwLock::new(Vec::new());
+
+/// Registers the configuration of `label`, replacing a previous configuration of the label.
+///
+/// # Panics
+///
+/// Panics if the label lock is poisoned.
+pub fn label_config(label: &str, config: LabelConfig) {
+    let mut labels = LABELS.write().unwrap();
+    labels.retain(|(existing, _)| existing != label);
+    labels.push((String::from(label), config));
+}
+
+/// Returns `whisper` with the icon and color of its label filled in, if it has a registered label.
+pub fn apply(whisper: &Whisper) -> Cow<'_, Whisper> {
+    let Some(label) = whisper.label.as_deref() else {
+        return Cow::Borrowed(whisper);
+    };
+    let config = LABELS.read().ok().and_then(|labels| {
+        labels
+            .iter()
+            .find_map(|(existing, config)| (existing == label).then(|| config.clone()))
+    });
+    config.map_or(Cow::Borrowed(whisper), |config| {
+        Cow::Owned(fill(whisper.clone(), config))
+    })
+}
+
+/// Returns `whisper` with the icon and color of `config` where it has none.
+fn fill(mut whisper: Whisper, config: LabelConfig) -> Whisper {
+    whisper.icon_kind = whisper.icon_kind.or(config.icon);
+    whisper.color = whisper.color.or(config.color);
+    whisper
+}
+
+#[cfg(test)]
+mod label_tests {
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        let config = LabelConfig {
+            icon: Some(IconKind::NfFaRefresh),
+            color: Some(Color::Cyan),
+        };
+        let filled = fill(Whisper::labeled("net"), config.clone());
+        assert_eq!(filled.icon_kind, Some(IconKind::NfFaRefresh));
+        assert_eq!(filled.color, Some(Color::Cyan));
+
+        let own = Whisper::labeled("net")
+            .icon(IconKind::NfFaBug)
+            .color(Color::Red);
+        assert_eq!(fill(own.clone(), config), own);
+    }
+
+    #[test]
+    fn test_apply() {
+        label_config(
+            "label_tests",
+            LabelConfig {
+                icon: Some(IconKind::NfFaBug),
+                color: None,
+            },
+        );
+        let whisper = Whisper::labeled("label_tests");
+        assert_eq!(apply(&whisper).icon_kind, Some(IconKind::NfFaBug));
+        let unknown = Whisper::labeled("label_tests_unknown");
+        assert!(matches!(apply(&unknown), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,239 @@
+//! The `level` module gives whispers a severity.
+//!
+//! A whisper without an explicit `Level` takes the level of its icon, e.g. `NfFaTimes` is an
+//! `Error` and `NfFaWarning` a `Warn`. Sinks use the level to decide which whispers they emit.
+//!
+//! ```
+//! use murmur::{IconKind, Level, Whisper};
+//!
+//! assert_eq!(Whisper::new().icon(IconKind::NfFaTimes).severity(), Level::Error);
+//! assert_eq!(Whisper::new().icon(IconKind::NfFaTimes).level(Level::Warn).severity(), Level::Warn);
+//! ```
+//!
+use core::fmt;
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use enum_iterator::{all, Sequence};
+
+use crate::IconKind;
+
+/// `Level` is the severity of a whisper, ordered from `Trace` to `Error`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Sequence)]
+pub enum Level {
+    /// Very detailed diagnostics.
+    Trace,
+    /// Diagnostics useful while debugging.
+    Debug,
+    /// Regular progress information, the level of whispers without icon.
+    Info,
+    /// An operation completed successfully.
+    Success,
+    /// Something unexpected that does not stop the program.
+    Warn,
+    /// An operation failed.
+    Error,
+}
+
+impl Level {
+    /// Returns the lowercase name of the level, e.g. `"warn"`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Success => "success",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+
+    /// Returns the icon of whispers at this level, whose own level is this one except for `Trace`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{IconKind, Level};
+    ///
+    /// assert_eq!(Level::Warn.icon(), IconKind::NfFaWarning);
+    /// assert_eq!(Level::Warn.icon().level(), Level::Warn);
+    /// ```
+    #[must_use]
+    pub const fn icon(self) -> IconKind {
+        match self {
+            Self::Trace | Self::Debug => IconKind::NfFaTerminal,
+            Self::Info => IconKind::NfFaInfoCircle,
+            Self::Success => IconKind::NfFaCheck,
+            Self::Warn => IconKind::NfFaWarning,
+            Self::Error => IconKind::NfFaTimes,
+        }
+    }
+
+    /// Returns the level named `name` by `as_str`, `warning` also naming `Warn`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::Level;
+    ///
+    /// assert_eq!(Level::from_name("warn"), Some(Level::Warn));
+    /// assert_eq!(Level::from_name("fatal"), None);
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "warning" => Some(Self::Warn),
+            name => all::<Self>().find(|level| level.as_str() == name),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The icon of whispers at the level, see `Level::icon()`.
+impl From<Level> for IconKind {
+    fn from(level: Level) -> Self {
+        level.icon()
+    }
+}
+
+/// Maps the levels of `tracing` to the level of the same name.
+///
+/// # Examples
+/// ```
+/// use murmur::{IconKind, Level};
+///
+/// assert_eq!(Level::from(tracing::Level::WARN), Level::Warn);
+/// assert_eq!(IconKind::from(tracing::Level::ERROR), IconKind::NfFaTimes);
+/// ```
+#[cfg(feature = "tracing")]
+impl From<tracing::Level> for Level {
+    fn from(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::TRACE => Self::Trace,
+            tracing::Level::DEBUG => Self::Debug,
+            tracing::Level::INFO => Self::Info,
+            tracing::Level::WARN => Self::Warn,
+            tracing::Level::ERROR => Self::Error,
+        }
+    }
+}
+
+/// The icon of the murmur level of the same name, see `Level::icon()`.
+#[cfg(feature = "tracing")]
+impl From<tracing::Level> for IconKind {
+    fn from(level: tracing::Level) -> Self {
+        Level::from(level).icon()
+    }
+}
+
+/// Maps the levels to the `tracing` level of the same name, `Success` to `INFO`.
+#[cfg(feature = "tracing")]
+impl From<Level> for tracing::Level {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Trace => Self::TRACE,
+            Level::Debug => Self::DEBUG,
+            Level::Info | Level::Success => Self::INFO,
+            Level::Warn => Self::WARN,
+            Level::Error => Self::ERROR,
+        }
+    }
+}
+
+impl IconKind {
+    /// Returns the default `Level` of a whisper with this icon.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{IconKind, Level};
+    ///
+    /// assert_eq!(IconKind::UnicodeCheckMark.level(), Level::Success);
+    /// assert_eq!(IconKind::NfFaInfoCircle.level(), Level::Info);
+    /// ```
+    #[must_use]
+    pub fn level(&self) -> Level {
+        #[allow(clippy::match_same_arms)]
+        match self.to_nerd_font() {
+            Self::NfFaTimes | Self::NfFaBug => Level::Error,
+            Self::NfFaWarning => Level::Warn,
+            Self::NfFaCheck => Level::Success,
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsUp => Level::Success,
+            Self::NfFaTerminal => Level::Debug,
+            _ => Level::Info,
+        }
+    }
+}
+
+/// The lowest `Level` whispered, see `set_verbosity`.
+#[cfg(feature = "std")]
+static VERBOSITY: AtomicU8 = AtomicU8::new(Level::Trace as u8);
+
+/// Skips the whispers below `level`, e.g. `Level::Warn` for a `--quiet` flag, `Level::Trace` by
+/// default. Skipped whispers are neither rendered nor emitted to any sink.
+///
+/// # Examples
+/// ```
+/// use murmur::{IconKind, Level, Whisper};
+///
+/// murmur::set_verbosity(Level::Warn);
+/// // Not printed
+/// Whisper::new().message("compiling").whisper().unwrap();
+/// Whisper::new().icon(IconKind::NfFaWarning).message("unused import").whisper().unwrap();
+/// murmur::set_verbosity(Level::Trace);
+/// ```
+#[cfg(feature = "std")]
+pub fn set_verbosity(level: Level) {
+    VERBOSITY.store(level as u8, Ordering::Relaxed);
+}
+
+/// Returns the lowest `Level` whispered, see `set_verbosity`.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn verbosity() -> Level {
+    let verbosity = VERBOSITY.load(Ordering::Relaxed);
+    all::<Level>()
+        .find(|level| *level as u8 == verbosity)
+        .unwrap_or(Level::Trace)
+}
+
+#[cfg(test)]
+mod level_tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_are_ordered() {
+        let levels = all::<Level>().collect::<Vec<_>>();
+        assert!(levels.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Level::Warn.to_string(), "warn");
+    }
+
+    #[test]
+    fn test_icon_levels() {
+        assert_eq!(IconKind::NfFaTimes.level(), Level::Error);
+        assert_eq!(IconKind::UnicodeCrossMark.level(), Level::Error);
+        assert_eq!(IconKind::UnicodeWarningSign.level(), Level::Warn);
+        assert_eq!(IconKind::NfFaCheck.level(), Level::Success);
+        assert_eq!(IconKind::NfFaInfoCircle.level(), Level::Info);
+    }
+
+    #[test]
+    #[cfg(feature = "tracing")]
+    fn test_tracing_levels() {
+        for level in all::<Level>().filter(|&level| level != Level::Success) {
+            assert_eq!(Level::from(tracing::Level::from(level)), level);
+        }
+        assert_eq!(tracing::Level::from(Level::Success), tracing::Level::INFO);
+    }
+
+    #[test]
+    fn test_level_icons() {
+        for level in all::<Level>().filter(|&level| level != Level::Trace) {
+            assert_eq!(level.icon().level(), level);
+        }
+    }
+}
@@ -49,6 +49,8 @@
 //!
 //!  For a full list of the currently supported icons, see the `IconKind` [enum](https://docs.rs/murmur/latest/murmur/enum.IconKind.html).
 //! ```rust
+//! # #[cfg(feature = "color")]
+//! # fn main() {
 //! use murmur::{Whisper, IconKind};
 //! use owo_colors::OwoColorize;
 //!
@@ -58,8 +60,9 @@
 //!     .message("message".red())
 //!     .whisper()
 //!     .unwrap();
-//!
-//!
+//! # }
+//! # #[cfg(not(feature = "color"))]
+//! # fn main() {}
 //! ```
 //!
 //! <div class="warning">You must have NerdFonts installed to use the `Nf` variants.</div>
@@ -153,23 +156,246 @@
 //!     Ok(())
 //! }
 //!```
+//!
+//! ### render
+//!
+//! Without the default `std` feature murmur is `no_std` and only needs `alloc`: `whisper` is not
+//! available, but `render` returns the lines it would print, to send them over any transport.
+//!
+//! ```toml
+//! murmur = { version = "2", default-features = false }
+//! ```
+//!
+//! ```rust
+//! use murmur::{IconKind, RenderOptions, Whisper};
+//!
+//! let lines = Whisper::new()
+//!     .icon(IconKind::NfFaCheck)
+//!     .message("rendered, not printed")
+//!     .render(&RenderOptions::plain());
+//! # #[cfg(not(feature = "plain"))]
+//! assert_eq!(lines, ["\u{f00c} rendered, not printed"]);
+//! ```
+//!
+//! ### plain
+//!
+//! The `plain` feature compiles out all styling: no colors and no glyphs, icons are rendered as
+//! their ASCII labels. Combined with `default-features = false` it also drops `owo-colors`, for
+//! minimal static binaries, containers and CI logs.
+//!
+//! ```toml
+//! murmur = { version = "2", default-features = false, features = ["std", "plain"] }
+//! ```
+//!
+//! ### icons
+//!
+//! The icon families are compiled in by the default `icons` feature, split into `icons-fa`,
+//! `icons-fae`, `icons-dev`, `icons-oct`, `icons-weather` and `icons-unicode`. The status icons,
+//! e.g. `NfFaCheck` and `UnicodeCheckMark`, are always available, and the `Unicode` counterparts of
+//! the enabled `Nf` icons come with them. Binaries using a handful of icons enable the families
+//! they need.
+//!
+//! ```toml
+//! murmur = { version = "2", default-features = false, features = ["std", "color", "icons-oct"] }
+//! ```
+//!
+//! ### cli
+//!
+//! The `cli` feature builds the `murmur` binary, to whisper from shell scripts. It whispers its
+//! arguments, or every line read from stdin, with the colors and icons decided by the environment
+//! like the library.
+//!
+//! ```sh
+//! cargo install murmur --features cli
+//! some-cmd | murmur --icon check --color green
+//! ```
 
 #![doc(html_root_url = "https://docs.rs/murmur/")]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod align;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
+mod bounded;
+mod clock;
 mod color_map;
+#[cfg(feature = "std")]
+mod context;
+#[cfg(feature = "std")]
+mod countdown;
+pub mod cursor;
+#[cfg(feature = "std")]
+mod delimiter;
+#[cfg(feature = "diagnostic")]
+mod diagnostic;
+#[cfg(feature = "std")]
+mod doctor;
+#[cfg(feature = "std")]
+mod each;
+#[cfg(feature = "std")]
+mod footer;
+#[cfg(feature = "std")]
+mod formatter;
+mod gallery;
+#[cfg(feature = "std")]
+mod history;
 mod icon_map;
+#[cfg(feature = "std")]
+mod indent;
+#[cfg(feature = "std")]
+mod label;
+mod level;
+#[cfg(feature = "std")]
+mod observer;
+#[cfg(feature = "std")]
+mod osc;
+#[cfg(feature = "std")]
+mod pager;
+#[cfg(feature = "std")]
+mod prompt;
+mod protocol;
+#[cfg(feature = "std")]
+mod queue;
+#[cfg(feature = "std")]
+mod rate_limit;
+mod render;
+#[cfg(feature = "std")]
+mod report;
+mod segment;
+mod session;
+#[cfg(feature = "std")]
+mod sink;
+mod snippet;
+#[cfg(feature = "std")]
+mod strings;
+mod svg;
+#[cfg(feature = "std")]
+mod sys;
+#[cfg(feature = "std")]
+mod tally;
+#[cfg(feature = "std")]
+mod tasks;
+#[cfg(feature = "std")]
+pub mod term;
+pub mod testing;
+mod theme;
+#[cfg(feature = "std")]
+mod throttle;
+mod timestamp;
+#[cfg(feature = "std")]
+mod timing;
+#[cfg(feature = "std")]
+mod transcript;
+#[cfg(feature = "webhook")]
+mod webhook;
+mod width;
+#[cfg(feature = "std")]
+mod writer;
 
 // Re-exports
-pub use icon_map::IconKind;
-
-use core::fmt::{Debug, Display};
-use std::fmt;
-use std::io::{self, BufWriter, Write};
+pub use align::Aligner;
+#[cfg(feature = "std")]
+pub use batch::{whisper_all, whisper_all_with, BatchPolicy};
+#[cfg(feature = "std")]
+pub use bounded::{BoundedSink, Overflow};
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use clock::{clock, set_clock, SystemClock};
+#[cfg(feature = "std")]
+pub use color_map::register_color;
+pub use color_map::{key_color, Color, ColorFn, KEY_PALETTE};
+#[cfg(feature = "std")]
+pub use context::{context, Context, ContextGuard, Instrumented};
+#[cfg(feature = "std")]
+pub use countdown::countdown;
+#[cfg(feature = "std")]
+pub use delimiter::{delimiters, sentinels, set_delimiters};
+#[cfg(feature = "diagnostic")]
+pub use diagnostic::Diagnostic;
+#[cfg(feature = "std")]
+pub use doctor::doctor;
+#[cfg(feature = "std")]
+pub use each::{progress_iter, ProgressIter, WhisperEach, WhisperEachIter};
+#[cfg(feature = "std")]
+pub use footer::{footer, Footer};
+#[cfg(feature = "std")]
+pub use formatter::{format, register_format, set_format, Formatter};
+#[cfg(feature = "std")]
+pub use gallery::whisper_gallery;
+pub use gallery::{gallery, GalleryEntry};
+#[cfg(feature = "std")]
+pub use history::{history, keep_recent, recent, HistoryQuery};
+#[cfg(feature = "std")]
+pub use icon_map::{set_unknown_icon_policy, unknown_icon_policy};
+pub use icon_map::{Category, IconKind, SpinnerKind, UnknownIconPolicy};
+#[cfg(feature = "std")]
+pub use indent::{indent_scope, IndentGuard, INDENT_WIDTH};
+#[cfg(feature = "std")]
+pub use label::{label_config, LabelConfig};
+pub use level::Level;
+#[cfg(feature = "std")]
+pub use level::{set_verbosity, verbosity};
+#[cfg(feature = "std")]
+pub use observer::{add_observer, clear_observers, Observer, WhisperEvent};
+#[cfg(feature = "std")]
+pub use osc::{set_progress, set_title, Progress};
+#[cfg(feature = "std")]
+pub use prompt::{pause, Prompt, Secret};
+pub use protocol::from_protocol_line;
+#[cfg(feature = "std")]
+pub use queue::{drain, queue_whispers};
+pub use render::{AnsiPolicy, Decorator, RenderOptions, RenderedWhisper, TextDirection};
+#[cfg(feature = "std")]
+pub use report::Report;
+pub use segment::{ansi_line, line_width, plain_line, serialize_line, Segment, Style};
+pub use session::Session;
+#[cfg(feature = "std")]
+pub use sink::{
+    add_sink, add_sink_with_level, clear_sinks, set_sinks, FileSink, JsonSink, Readiness, Rotation,
+    Sink, StdoutSink,
+};
+pub use snippet::Snippet;
+#[cfg(feature = "std")]
+pub use strings::{set_strings, Strings};
+pub use svg::SvgExport;
+#[cfg(feature = "std")]
+pub use tally::{exit_code, reset_tally, set_warning_exit_code, tally, Tally};
+#[cfg(feature = "std")]
+pub use tasks::{TaskList, TaskStatus};
+#[cfg(feature = "std")]
+pub use theme::set_theme;
+pub use theme::{Background, Theme};
+#[cfg(feature = "std")]
+pub use throttle::{flush_throttled, refresh_rate, set_refresh_rate};
+pub use timestamp::{Timestamp, TIMESTAMP_WIDTH};
+#[cfg(feature = "std")]
+pub use timing::{timings, trace_timings, WhisperTiming};
+#[cfg(feature = "std")]
+pub use transcript::{record, replay};
+#[cfg(feature = "webhook")]
+pub use webhook::{WebhookFormat, WebhookSink};
+pub use width::Presentation;
+#[cfg(feature = "std")]
+pub use writer::WhisperWriter;
+
+#[cfg(feature = "std")]
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display};
+use core::ops::Range;
+use core::time::Duration;
 
 /// The `WhisperError` enum represents different kinds of errors that can occur while printing messages.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum WhisperError {
-    /// Error acquiring lock on ICON_MAP
+    /// Error acquiring a lock poisoned by a panicking thread: on `ICON_MAP`, the `Theme`, the sink
+    /// registry, the output lock held while a whisper is emitted, the rate limit state or the queue
     Lock,
 
     /// Error printing message
@@ -181,23 +407,62 @@ pub enum WhisperError {
     /// Error flushing buffer
     Flush,
 
-    /// Error converting bytes to UTF-8 string
-    Utf8Conversion,
+    /// Error converting bytes to UTF-8 string, with the position of the invalid bytes
+    Utf8Conversion(core::str::Utf8Error),
+
+    /// Error emitting to a sink
+    Sink,
+
+    /// Error reading a transcript
+    Transcript,
+
+    /// Error reading input
+    Read,
+
+    /// An icon missing from `ICON_MAP`, with `UnknownIconPolicy::Error`
+    UnknownIcon(IconKind),
+
+    /// Error parsing a protocol line, with the reason, see `from_protocol_line`
+    Protocol(String),
+
+    /// A message rejected by `Whisper::try_message`, with the reason
+    InvalidMessage(String),
 }
 
 impl Display for WhisperError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Lock => write!(f, "Failed to acquire lock on ICON_MAP"),
+            Self::Lock => write!(
+                f,
+                "Failed to acquire a poisoned lock on the icon map, theme, sinks, output, rate limit or queue"
+            ),
             Self::Print => write!(f, "Failed to print message"),
             Self::Write => write!(f, "Error writing to buffer"),
             Self::Flush => write!(f, "Error flushing buffer"),
-            Self::Utf8Conversion => write!(f, "Failed to convert bytes to UTF-8 string"),
+            Self::Utf8Conversion(error) => {
+                write!(f, "Failed to convert bytes to UTF-8 string: {error}")
+            }
+            Self::Sink => write!(f, "Failed to emit whisper to a sink"),
+            Self::Transcript => write!(f, "Failed to read transcript"),
+            Self::Read => write!(f, "Failed to read input"),
+            Self::UnknownIcon(icon_kind) => write!(f, "Unknown icon {icon_kind:?}"),
+            Self::Protocol(reason) => write!(f, "Invalid protocol line: {reason}"),
+            Self::InvalidMessage(reason) => write!(f, "Invalid message: {reason}"),
+        }
+    }
+}
+
+impl core::error::Error for WhisperError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Utf8Conversion(error) => Some(error),
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for WhisperError {}
+/// The length in bytes of the longest message accepted by `Whisper::try_message`.
+pub const MAX_MESSAGE_LEN: usize = 64 * 1024;
 
 /// Represents a collection of messages with an optional icon and message
 ///
@@ -205,6 +470,24 @@ impl std::error::Error for WhisperError {}
 ///
 /// * `icon_kind` - An optional field that specifies the kind of icon to be displayed.
 /// * `messages` - A vector of messages to be displayed.
+/// * `level` - An optional severity, the level of the icon is used if it is `None`.
+/// * `rate_limit` - An optional interval, similar whispers are printed at most once per interval.
+/// * `fields` - Structured `key=value` fields, rendered after the messages.
+/// * `message_icons` - Icons overriding the whisper icon for single messages, by message index.
+/// * `stripe` - An optional color name, every other line is printed in it.
+/// * `tab_width` - An optional tab stop width, tabs are expanded to spaces if set.
+/// * `icon_gap` - An optional number of spaces between the icon and the messages, 1 if `None`.
+/// * `escape_controls` - Whether control characters are rendered as visible escapes.
+/// * `direction` - The direction of the messages, the icon follows right-to-left messages.
+/// * `page` - Whether the whisper is piped through a pager, automatic if `None`.
+/// * `set_title` - Whether the terminal title is set to the first message.
+/// * `plain` - Whether the whisper is printed without color, whatever the color mode.
+/// * `commands` - The messages that are commands, by message index, with an optional link.
+/// * `label` - An optional label, whose registered icon and color are used, see `label_config`.
+/// * `color` - An optional color name, overriding the color of the icon.
+/// * `code` - An optional error code, printed in a compiler-style header.
+/// * `help` - An optional help text, printed dimmed on a trailing `help:` line.
+/// * `snippet` - An optional source line, printed with the reported columns underlined.
 ///
 /// # Example
 ///
@@ -223,145 +506,1140 @@ pub struct Whisper {
     pub icon_kind: Option<IconKind>,
     /// A vector of messages to be displayed.
     pub messages: Vec<String>,
+    /// An optional severity, overriding the level of the icon.
+    pub level: Option<Level>,
+    /// An optional interval, similar whispers are printed at most once per interval.
+    pub rate_limit: Option<Duration>,
+    /// Structured fields, the keys and their formatted values.
+    pub fields: Vec<(String, String)>,
+    /// Icons overriding the whisper icon for single messages, by message index.
+    pub message_icons: Vec<(usize, IconKind)>,
+    /// An optional color name, every other line is printed in it.
+    pub stripe: Option<Color>,
+    /// An optional tab stop width, tabs are expanded to spaces if set.
+    pub tab_width: Option<usize>,
+    /// An optional number of spaces between the icon and the messages, the space of the glyph if `None`.
+    pub icon_gap: Option<usize>,
+    /// Whether control characters are rendered as visible escapes.
+    pub escape_controls: bool,
+    /// The direction of the messages, the icon follows right-to-left messages.
+    pub direction: TextDirection,
+    /// Whether the whisper is piped through a pager, `None` to page it when it exceeds the terminal height.
+    pub page: Option<bool>,
+    /// Whether the terminal title is set to the first message when the whisper is printed.
+    pub set_title: bool,
+    /// Whether the whisper is printed without color, whatever the color mode.
+    pub plain: bool,
+    /// The messages that are commands, by message index, with an optional link.
+    pub commands: Vec<(usize, Option<String>)>,
+    /// An optional label, whose registered icon and color are used, see `label_config`.
+    pub label: Option<String>,
+    /// An optional color name, overriding the color of the icon.
+    pub color: Option<Color>,
+    /// An optional timestamp, printed in a column before the icon.
+    pub timestamp: Option<Timestamp>,
+    /// An optional correlation ID, printed as a dimmed suffix and written by structured sinks.
+    pub correlation_id: Option<String>,
+    /// An optional error code, printed in a compiler-style `error[E0423]:` header.
+    pub code: Option<String>,
+    /// An optional help text, printed dimmed on a trailing `help:` line.
+    pub help: Option<String>,
+    /// An optional source line, printed after the messages with the reported columns underlined.
+    pub snippet: Option<Snippet>,
+    /// An optional hook rewriting each rendered line, see `Whisper::decorate`.
+    pub decorator: Option<Decorator>,
 }
 
-impl Whisper {
-    /// Creates a new `Whisper` instance.
+impl Whisper {
+    /// Creates a new `Whisper` instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{Whisper, IconKind};
+    ///
+    /// let whisper = Whisper::new()
+    ///     .icon(IconKind::NfFaBug)
+    ///     .message("message")
+    ///     .whisper()
+    ///     .ok();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            icon_kind: None,
+            messages: Vec::new(),
+            level: None,
+            rate_limit: None,
+            fields: Vec::new(),
+            message_icons: Vec::new(),
+            stripe: None,
+            tab_width: None,
+            icon_gap: None,
+            escape_controls: false,
+            direction: TextDirection::Ltr,
+            page: None,
+            set_title: false,
+            plain: false,
+            commands: Vec::new(),
+            label: None,
+            color: None,
+            timestamp: None,
+            correlation_id: None,
+            code: None,
+            help: None,
+            snippet: None,
+            decorator: None,
+        }
+    }
+
+    /// Creates a new `Whisper` with `label`, taking the icon and color registered for the label
+    /// with `label_config` when it is whispered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::Whisper;
+    ///
+    /// Whisper::labeled("db").message("migrated 3 tables").whisper().unwrap();
+    /// ```
+    #[must_use]
+    pub fn labeled(label: &str) -> Self {
+        Self {
+            label: Some(String::from(label)),
+            ..Self::new()
+        }
+    }
+
+    /// Prints the `Whisper` in the color `color` instead of the color of its icon.
+    ///
+    /// `color` is a `Color`, or the name of a color like `"cyan"`, see `Color::from_name()`.
+    /// Messages with their own icon keep its color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{Color, IconKind, Whisper};
+    ///
+    /// Whisper::new().icon(IconKind::NfFaCheck).message("cached").color(Color::Blue).whisper().unwrap();
+    /// Whisper::new().message("orange").color(Color::Rgb(255, 165, 0)).whisper().unwrap();
+    /// ```
+    #[must_use]
+    pub fn color<C: Into<Color>>(mut self, color: C) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Prints the `Whisper` in a color chosen from `KEY_PALETTE` by a hash of `key`, see `color()`.
+    ///
+    /// Whispers with the same key, e.g. a container or thread name, always get the same color, so
+    /// interleaved output of several sources can be told apart.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::Whisper;
+    ///
+    /// for (source, line) in [("web", "listening"), ("db", "ready"), ("web", "GET /")] {
+    ///     Whisper::new().message(format!("{source} | {line}")).color_by_key(source).whisper().unwrap();
+    /// }
+    /// ```
+    #[must_use]
+    pub fn color_by_key(self, key: &str) -> Self {
+        self.color(color_map::key_color(key))
+    }
+
+    /// Prints the current wall-clock time of `clock()`, in UTC, in a column before the icon.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::Whisper;
+    ///
+    /// Whisper::new().message("server started").timestamp().whisper().unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn timestamp(mut self) -> Self {
+        let since_epoch = if term::deterministic() {
+            Duration::ZERO
+        } else {
+            clock::clock().since_epoch().unwrap_or_default()
+        };
+        self.timestamp = Some(Timestamp::Wall(since_epoch));
+        self
+    }
+
+    /// Prints the wall-clock time of `clock`, in UTC, in a column before the icon, midnight if
+    /// `clock` does not know it, see `Clock::since_epoch()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use murmur::testing::FakeClock;
+    /// use murmur::Whisper;
+    ///
+    /// let clock = FakeClock::new().at(Duration::from_secs(9 * 3600));
+    /// murmur::assert_whisper_eq!(Whisper::new().message("backup").timestamp_from(&clock), ["09:00:00 backup"]);
+    /// ```
+    #[must_use]
+    pub fn timestamp_from(mut self, clock: &dyn Clock) -> Self {
+        let since_epoch = clock.since_epoch().unwrap_or_default();
+        self.timestamp = Some(Timestamp::Wall(since_epoch));
+        self
+    }
+
+    /// Prints the time elapsed since `start`, like `+12.3s`, in a column before the icon.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Instant;
+    /// use murmur::Whisper;
+    ///
+    /// let start = Instant::now();
+    /// Whisper::new().message("linked").timestamp_relative(start).whisper().unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn timestamp_relative(mut self, start: std::time::Instant) -> Self {
+        let elapsed = if term::deterministic() {
+            Duration::ZERO
+        } else {
+            start.elapsed()
+        };
+        self.timestamp = Some(Timestamp::Relative(elapsed));
+        self
+    }
+
+    /// Prints the time elapsed on `clock` since `start`, a time returned by `Clock::now()`, like
+    /// `+12.3s`, in a column before the icon, see the `clock` module.
+    #[must_use]
+    pub fn timestamp_relative_from(mut self, start: Duration, clock: &dyn Clock) -> Self {
+        let elapsed = clock.now().saturating_sub(start);
+        self.timestamp = Some(Timestamp::Relative(elapsed));
+        self
+    }
+
+    /// Prints the `Whisper` at most once per `interval`, similar whispers within it are suppressed.
+    ///
+    /// Whispers are similar when they have the same icon and messages, the next one printed ends
+    /// with a `suppressed N similar messages` line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use murmur::{IconKind, Whisper};
+    ///
+    /// for attempt in 0..10 {
+    ///     Whisper::new()
+    ///         .icon(IconKind::NfFaRefresh)
+    ///         .message("polling for results")
+    ///         .rate_limit(Duration::from_secs(1))
+    ///         .whisper()
+    ///         .unwrap();
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn rate_limit(mut self, interval: Duration) -> Self {
+        self.rate_limit = Some(interval);
+        self
+    }
+
+    /// Prints every other line in the color `stripe`, to ease scanning long lists and tables.
+    ///
+    /// `stripe` is a `Color`, or the name of a color like `"bright_black"` or `"dimmed"`. The first
+    /// line keeps the color of the icon, lines with their own icon keep its color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{Color, Whisper};
+    ///
+    /// Whisper::new()
+    ///     .message("3 dependencies outdated")
+    ///     .messages(["serde     1.0.150  1.0.190", "tokio     1.28.0   1.33.0", "regex     1.7.0    1.10.2"])
+    ///     .stripe(Color::BrightBlack)
+    ///     .whisper()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn stripe<C: Into<Color>>(mut self, stripe: C) -> Self {
+        self.stripe = Some(stripe.into());
+        self
+    }
+
+    /// Expands tabs to spaces, up to the next stop every `tab_width` columns of the message.
+    ///
+    /// Terminals expand tabs from the start of the line, so tabs after the icon misalign columns
+    /// of process output or tables.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .messages(["name\tsize", "log.txt\t12k"])
+    ///     .tab_width(8)
+    ///     .render(&RenderOptions::plain());
+    /// assert_eq!(lines, ["name    size", "  log.txt 12k"]);
+    /// ```
+    #[must_use]
+    pub const fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = Some(tab_width);
+        self
+    }
+
+    /// Prints `icon_gap` spaces between the icon and the messages, instead of the space following
+    /// the glyphs of `ICON_MAP`.
+    ///
+    /// Wide Nerd Font glyphs overlap the messages with a single space, and compact layouts drop
+    /// the gap altogether. The following lines are indented under the first message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .icon(IconKind::NfFaCheck)
+    ///     .messages(["built", "tested"])
+    ///     .icon_gap(2)
+    ///     .render(&RenderOptions::plain());
+    /// # #[cfg(not(feature = "plain"))]
+    /// assert_eq!(lines, ["\u{f00c}  built", "   tested"]);
+    /// ```
+    #[must_use]
+    pub const fn icon_gap(mut self, icon_gap: usize) -> Self {
+        self.icon_gap = Some(icon_gap);
+        self
+    }
+
+    /// Renders control characters as visible escapes, so they cannot corrupt the terminal layout.
+    ///
+    /// C0 control characters are escaped in caret notation, e.g. a carriage return as `^M` and the
+    /// escape of ANSI sequences as `^[`, C1 control characters are replaced by `\u{FFFD}`. Tabs are
+    /// escaped as `^I` unless they are expanded by `tab_width`, newlines are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .message("downloading 50%\rdownloading 100%")
+    ///     .escape_controls()
+    ///     .render(&RenderOptions::plain());
+    /// assert_eq!(lines, ["downloading 50%^Mdownloading 100%"]);
+    /// ```
+    #[must_use]
+    pub const fn escape_controls(mut self) -> Self {
+        self.escape_controls = true;
+        self
+    }
+
+    /// Sets the direction of the messages, `TextDirection::Ltr` by default.
+    ///
+    /// Right-to-left whispers print the icon after the messages, and wrap each line in Unicode
+    /// right-to-left isolation marks, so mixed Arabic or Hebrew and Latin text keeps its order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, TextDirection, Whisper};
+    ///
+    /// Whisper::new()
+    ///     .icon(IconKind::NfFaCheck)
+    ///     .message("\u{5d4}\u{5d4}\u{5ea}\u{5e7}\u{5e0}\u{5d4} \u{5d4}\u{5e1}\u{5ea}\u{5d9}\u{5d9}\u{5de}\u{5d4}")
+    ///     .direction(TextDirection::Rtl)
+    ///     .whisper()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub const fn direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Pipes the whisper through `$PAGER`, `less -R` by default, if `page` is true, never if it is false.
+    ///
+    /// By default whispers with more lines than the terminal has rows are paged, so long help or
+    /// diagnostics do not scroll the context away. Only whispers printed to a terminal are paged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::Whisper;
+    ///
+    /// Whisper::new()
+    ///     .message("warnings")
+    ///     .messages((1..=200).map(|index| format!("warning {index}")))
+    ///     .page(false)
+    ///     .whisper()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub const fn page(mut self, page: bool) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Sets the title of the terminal window or tab to the first message when the whisper is printed.
+    ///
+    /// The title is set by `StdoutSink` with an `OSC 2` sequence, only when stdout is a terminal,
+    /// see `murmur::set_title`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, Whisper};
+    ///
+    /// Whisper::new()
+    ///     .icon(IconKind::NfFaRefresh)
+    ///     .message("deploying to production")
+    ///     .set_title(true)
+    ///     .whisper()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub const fn set_title(mut self, set_title: bool) -> Self {
+        self.set_title = set_title;
+        self
+    }
+
+    /// Prints the whisper without any color if `plain` is true, whatever the color mode.
+    ///
+    /// Meant for lines copied and pasted, like commands or URLs, where escape sequences would be
+    /// pasted along. Colors of pre-colored messages are stripped as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, Whisper};
+    ///
+    /// Whisper::new()
+    ///     .icon(IconKind::NfFaInfoCircle)
+    ///     .message("https://github.com/andretcarpizo/murmur/issues/new")
+    ///     .plain(true)
+    ///     .whisper()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub const fn plain(mut self, plain: bool) -> Self {
+        self.plain = plain;
+        self
+    }
+
+    /// Sets the severity of the `Whisper`, overriding the level of its icon.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, Level, Whisper};
+    ///
+    /// let whisper = Whisper::new()
+    ///     .icon(IconKind::NfFaInfoCircle)
+    ///     .message("disk almost full")
+    ///     .level(Level::Warn);
+    /// assert_eq!(whisper.severity(), Level::Warn);
+    /// ```
+    #[must_use]
+    pub const fn level(mut self, level: Level) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Returns the severity of the `Whisper`: its level if set, otherwise the level of its icon,
+    /// `Level::Info` for whispers without icon.
+    #[must_use]
+    pub fn severity(&self) -> Level {
+        self.level
+            .unwrap_or_else(|| self.icon_kind.as_ref().map_or(Level::Info, IconKind::level))
+    }
+
+    /// Adds an icon to the `Whisper` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `icon_kind`: The kind of icon to be displayed. See the `IconKind` enum for a list of available icons.
+    ///
+    /// # Returns
+    ///
+    /// A `Whisper` instance with the specified icon.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use murmur::{Whisper, IconKind};
+    /// let whisper = Whisper::new()
+    ///     .icon(IconKind::NfFaCheck)
+    ///     .message("Nerd Font icons if you have them installed")
+    ///     .message("Another message")
+    ///     .whisper()
+    ///     .expect("Failed to print message");
+    ///
+    /// let whisper = Whisper::new()
+    ///     .icon(IconKind::UnicodeWarningSign)
+    ///     .message("Unicode icons")
+    ///     .whisper()
+    ///     .or_else(|err| Err(err));
+    #[must_use]
+    pub fn icon(mut self, icon_kind: IconKind) -> Self {
+        self.icon_kind = Some(icon_kind);
+        self
+    }
+
+    /// Adds a single message to the `Whisper` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: The message to be added.
+    ///
+    /// Messages already colored, e.g. with `owo_colors`, keep their own colors instead of the color
+    /// of the icon, and are stripped of their colors when the whisper is rendered without color.
+    ///
+    /// # Returns
+    ///
+    /// A `Whisper` instance with the added message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::Whisper;
+    /// let whisper = Whisper::new()
+    ///    .message("1 message without icon")
+    ///    .message("2 message")
+    ///    .message("3 message")
+    ///    .whisper()
+    ///    .ok();
+    /// ```
+    /// # Output
+    /// ```text
+    /// 1 message without icon
+    ///   2 message without icon indents by 2 spaces all messages after the first
+    ///   3 message
+    /// ```
+    #[must_use]
+    pub fn message<T: Display + Debug>(mut self, message: T) -> Self {
+        self.messages.push(message.to_string());
+        self
+    }
+
+    /// Adds the message `bytes` after checking it, so invalid input is reported at the call site
+    /// instead of garbling the terminal when it is whispered.
+    ///
+    /// The message must be valid UTF-8, at most [`MAX_MESSAGE_LEN`] bytes long, and without control
+    /// characters other than newlines and tabs, unless the whisper escapes them, see `escape_controls`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `Whisper` unchanged with `WhisperError::Utf8Conversion` if `bytes` is not valid
+    /// UTF-8, or `WhisperError::InvalidMessage` if it is too long or contains a control character.
+    ///
+    /// The message is checked when it is added, so control characters are only accepted if
+    /// `escape_controls` is called before `try_message`, not after.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{Whisper, WhisperError};
+    ///
+    /// let whisper = Whisper::new().try_message(b"uploaded").unwrap();
+    /// assert_eq!(whisper.messages, ["uploaded"]);
+    ///
+    /// let (whisper, error) = whisper.try_message(b"\x1b]0;pwned\x07").unwrap_err();
+    /// assert!(matches!(error, WhisperError::InvalidMessage(_)));
+    /// assert_eq!(whisper.messages, ["uploaded"]);
+    /// ```
+    #[allow(clippy::result_large_err)]
+    pub fn try_message(mut self, bytes: &[u8]) -> Result<Self, (Self, WhisperError)> {
+        if bytes.len() > MAX_MESSAGE_LEN {
+            let reason = format!(
+                "{} bytes exceed the limit of {MAX_MESSAGE_LEN} bytes",
+                bytes.len()
+            );
+            return Err((self, WhisperError::InvalidMessage(reason)));
+        }
+        let message = match core::str::from_utf8(bytes) {
+            Ok(message) => message,
+            Err(error) => return Err((self, WhisperError::Utf8Conversion(error))),
+        };
+        let control = message
+            .char_indices()
+            .find(|(_, character)| character.is_control() && !matches!(character, '\n' | '\t'));
+        if let Some((index, character)) = control.filter(|_| !self.escape_controls) {
+            let reason = format!(
+                "control character U+{:04X} at byte {index}",
+                u32::from(character)
+            );
+            return Err((self, WhisperError::InvalidMessage(reason)));
+        }
+        self.messages.push(String::from(message));
+        Ok(self)
+    }
+
+    /// Adds a message marked with its own icon, printed in the color of that icon.
+    ///
+    /// The whisper keeps its own icon for the header, so a batch report can mark each result.
+    /// Like `render`, the icon comes from the built-in icon table.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .icon(IconKind::NfFaInfoCircle)
+    ///     .message("3 jobs done")
+    ///     .message_with_icon(IconKind::NfFaCheck, "build")
+    ///     .message_with_icon(IconKind::NfFaWarning, "lint: 2 warnings")
+    ///     .message("test")
+    ///     .render(&RenderOptions::plain());
+    /// # #[cfg(not(feature = "plain"))]
+    /// assert_eq!(
+    ///     lines,
+    ///     ["\u{f05a} 3 jobs done", "  \u{f00c} build", "  \u{f071} lint: 2 warnings", "  test"]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn message_with_icon<T: Display + Debug>(
+        mut self,
+        icon_kind: IconKind,
+        message: T,
+    ) -> Self {
+        self.message_icons.push((self.messages.len(), icon_kind));
+        self.message(message)
+    }
+
+    /// Creates a `Whisper` reporting the outcome of a batch operation, one message per result.
+    ///
+    /// `Ok` items are marked with a check and `Err` items with a cross, indented under a header
+    /// counting them, see `Strings::results_summary`. The header has a cross if any item failed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let results = ["a.txt", "b.txt"].map(|file| match file {
+    ///     "a.txt" => Ok(format!("deleted {file}")),
+    ///     _ => Err(format!("{file}: permission denied")),
+    /// });
+    /// let lines = Whisper::from_results(results).render(&RenderOptions::plain());
+    /// # #[cfg(not(feature = "plain"))]
+    /// assert_eq!(
+    ///     lines,
+    ///     [
+    ///         "\u{f00d} 1 succeeded, 1 failed",
+    ///         "  \u{f00c} deleted a.txt",
+    ///         "  \u{f00d} b.txt: permission denied",
+    ///     ]
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn from_results<T, E, I>(results: I) -> Self
+    where
+        T: Display,
+        E: Display,
+        I: IntoIterator<Item = Result<T, E>>,
+    {
+        let items = results
+            .into_iter()
+            .map(|result| match result {
+                Ok(item) => (IconKind::NfFaCheck, item.to_string()),
+                Err(error) => (IconKind::NfFaTimes, error.to_string()),
+            })
+            .collect::<Vec<_>>();
+        let failed = items
+            .iter()
+            .filter(|(icon_kind, _)| *icon_kind == IconKind::NfFaTimes)
+            .count();
+        let icon_kind = if failed > 0 {
+            IconKind::NfFaTimes
+        } else {
+            IconKind::NfFaCheck
+        };
+        let header = (strings::strings().results_summary)(items.len() - failed, failed);
+        items.into_iter().fold(
+            Self::new().icon(icon_kind).message(header),
+            |whisper, (icon_kind, item)| whisper.message_with_icon(icon_kind, item),
+        )
+    }
+
+    /// Adds a command the user is expected to copy and paste, e.g. to fix a problem.
+    ///
+    /// Commands are printed on their own line, indented below the messages, without color or
+    /// escape sequences, and with control characters escaped, so they are copied exactly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .icon(IconKind::NfFaWarning)
+    ///     .message("the lock file is outdated, run")
+    ///     .command("cargo update --workspace")
+    ///     .render(&RenderOptions { ascii_icons: true, ..RenderOptions::plain() });
+    /// assert_eq!(lines, ["! the lock file is outdated, run", "    cargo update --workspace"]);
+    /// ```
+    #[must_use]
+    pub fn command(mut self, command: &str) -> Self {
+        self.commands.push((self.messages.len(), None));
+        self.messages.push(String::from(command));
+        self
+    }
+
+    /// Adds a command like `command`, linked to `url` with an `OSC 8` hyperlink on terminals
+    /// supporting them, e.g. to its documentation.
+    #[must_use]
+    pub fn command_link(mut self, command: &str, url: &str) -> Self {
+        self.commands
+            .push((self.messages.len(), Some(String::from(url))));
+        self.messages.push(String::from(command));
+        self
+    }
+
+    /// Returns the icon overriding the whisper icon for the message at `index`, if any.
+    #[must_use]
+    pub fn message_icon(&self, index: usize) -> Option<&IconKind> {
+        self.message_icons
+            .iter()
+            .rev()
+            .find_map(|(existing, icon_kind)| (*existing == index).then_some(icon_kind))
+    }
+
+    /// Adds multiple messages to the `Whisper` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages`: An iterable collection of items, each implementing `Display`, `Debug`, and `AsRef<str>`.
+    ///
+    /// Returns a `Whisper` instance with the added messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use murmur::Whisper;
+    ///
+    /// Whisper::new()
+    ///     .messages(["1 message", "2 message", "3 message"])
+    ///     .whisper()
+    ///     .ok();
+    ///
+    /// Whisper::new()
+    ///     .messages(vec!["1 message", "2 message", "3 message"])
+    ///     .whisper()
+    ///     .ok();
+    ///
+    /// ```
+    /// # Output
+    /// ```text
+    /// 1 message
+    ///   2 message
+    ///   3 message
+    /// ```
+    #[must_use]
+    pub fn messages<I, S>(mut self, messages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Display + Debug + AsRef<str>,
+    {
+        for message in messages {
+            self.messages.push(message.as_ref().to_string());
+        }
+        self
+    }
+
+    /// Sorts the messages, e.g. findings collected from parallel workers in no particular order.
+    ///
+    /// Messages are compared as strings and equal messages keep their order. Message icons and
+    /// commands move with their messages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new().messages(["b.rs", "a.rs", "b.rs"]).sort().dedup().render(&RenderOptions::plain());
+    /// assert_eq!(lines, ["a.rs", "  b.rs"]);
+    /// ```
+    #[must_use]
+    pub fn sort(self) -> Self {
+        let mut order = (0..self.messages.len()).collect::<Vec<_>>();
+        order.sort_by(|&first, &second| self.messages[first].cmp(&self.messages[second]));
+        self.reorder(&order)
+    }
+
+    /// Removes consecutive repeated messages, keeping the first one, like `Vec::dedup`.
+    ///
+    /// Call `sort()` first to remove every repeated message.
+    #[must_use]
+    pub fn dedup(self) -> Self {
+        let order = (0..self.messages.len())
+            .filter(|&index| index == 0 || self.messages[index] != self.messages[index - 1])
+            .collect::<Vec<_>>();
+        self.reorder(&order)
+    }
+
+    /// Keeps only the messages for which `keep` returns `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .messages(["src/lib.rs", "target/debug/build.rs", "src/main.rs"])
+    ///     .filter(|message| !message.starts_with("target/"))
+    ///     .render(&RenderOptions::plain());
+    /// assert_eq!(lines, ["src/lib.rs", "  src/main.rs"]);
+    /// ```
+    #[must_use]
+    pub fn filter<F: FnMut(&str) -> bool>(self, mut keep: F) -> Self {
+        let order = (0..self.messages.len())
+            .filter(|&index| keep(&self.messages[index]))
+            .collect::<Vec<_>>();
+        self.reorder(&order)
+    }
+
+    /// Groups the messages after the first one by the `Level` `category` returns for them, most
+    /// severe group first, each group under a line with the icon of its level and its size.
+    ///
+    /// The first message stays the header of the whisper. Messages keep their order within their
+    /// group, and their message icons and commands move with them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{Level, RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .message("lint: 3 findings")
+    ///     .messages(["unused import", "missing semicolon", "long line"])
+    ///     .group_messages_by(|message| if message.starts_with("missing") { Level::Error } else { Level::Warn })
+    ///     .render(&RenderOptions { ascii_icons: true, ..RenderOptions::plain() });
+    /// assert_eq!(
+    ///     lines,
+    ///     [
+    ///         "lint: 3 findings",
+    ///         "  x error (1)",
+    ///         "  missing semicolon",
+    ///         "  ! warning (2)",
+    ///         "  unused import",
+    ///         "  long line",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn group_messages_by<F: FnMut(&str) -> Level>(self, mut category: F) -> Self {
+        if self.messages.is_empty() {
+            return self;
+        }
+        let levels = self
+            .messages
+            .iter()
+            .map(|message| category(message))
+            .collect::<Vec<_>>();
+        let mut order = Vec::from([0]);
+        let mut groups = Vec::new();
+        for level in enum_iterator::reverse_all::<Level>() {
+            let members = (1..levels.len()).filter(|&index| levels[index] == level);
+            let start = order.len();
+            order.extend(members);
+            if order.len() > start {
+                groups.push((start, level, order.len() - start));
+            }
+        }
+        // Insert the group lines from the last one, so the positions of the others stay valid
+        groups
+            .into_iter()
+            .rev()
+            .fold(self.reorder(&order), |whisper, (index, level, count)| {
+                let name = match level {
+                    Level::Warn => "warning",
+                    level => level.as_str(),
+                };
+                whisper.insert_message(index, level.icon(), format!("{name} ({count})"))
+            })
+    }
+
+    /// Returns a copy of the whisper with its icon replaced by `icon_kind`, leaving `self` untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, Whisper};
+    ///
+    /// let failed = Whisper::new().icon(IconKind::NfFaTimes).message("build failed");
+    /// let warned = failed.with_icon(IconKind::NfFaWarning);
+    /// assert_eq!(failed.icon_kind, Some(IconKind::NfFaTimes));
+    /// assert_eq!(warned.icon_kind, Some(IconKind::NfFaWarning));
+    /// ```
+    #[must_use]
+    pub fn with_icon(&self, icon_kind: IconKind) -> Self {
+        self.clone().icon(icon_kind)
+    }
+
+    /// Returns a copy of the whisper with its messages replaced by `messages`, leaving `self`
+    /// untouched, e.g. to reuse a configured template for several reports.
+    ///
+    /// The message icons and commands of the replaced messages are dropped, everything else is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let template = Whisper::new().icon(IconKind::NfFaTimes).code("E0001");
+    /// let lines = template
+    ///     .with_messages(["missing `;`"])
+    ///     .render(&RenderOptions::plain());
+    /// # #[cfg(not(feature = "plain"))]
+    /// assert_eq!(lines, ["\u{f00d} error[E0001]: missing `;`"]);
+    /// assert!(template.messages.is_empty());
+    /// ```
+    #[must_use]
+    pub fn with_messages<I, S>(&self, messages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Display + Debug + AsRef<str>,
+    {
+        self.clone().reorder(&[]).messages(messages)
+    }
+
+    /// Keeps the messages at the indices of `order`, in that order, moving their message icons and
+    /// commands with them.
+    fn reorder(mut self, order: &[usize]) -> Self {
+        let new_index = |index| order.iter().position(|&existing| existing == index);
+        let mut messages = core::mem::take(&mut self.messages)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.messages = order
+            .iter()
+            .filter_map(|&index| messages[index].take())
+            .collect();
+        self.message_icons = core::mem::take(&mut self.message_icons)
+            .into_iter()
+            .filter_map(|(index, icon_kind)| Some((new_index(index)?, icon_kind)))
+            .collect();
+        self.commands = core::mem::take(&mut self.commands)
+            .into_iter()
+            .filter_map(|(index, link)| Some((new_index(index)?, link)))
+            .collect();
+        self
+    }
+
+    /// Inserts `message` marked with `icon_kind` at `index`, moving the message icons and commands
+    /// of the following messages with them.
+    fn insert_message(mut self, index: usize, icon_kind: IconKind, message: String) -> Self {
+        let shift = |existing: &mut usize| {
+            if *existing >= index {
+                *existing += 1;
+            }
+        };
+        self.message_icons
+            .iter_mut()
+            .for_each(|(existing, _)| shift(existing));
+        self.commands
+            .iter_mut()
+            .for_each(|(existing, _)| shift(existing));
+        self.messages.insert(index, message);
+        self.message_icons.push((index, icon_kind));
+        self
+    }
+
+    /// Adds a structured field, formatting `value` with `Debug` like `tracing::info!(key = ?value)`.
+    ///
+    /// Fields are rendered as `key=value` after the last message when they fit in
+    /// `RenderOptions::width`, otherwise each one on its own indented line.
     ///
     /// # Example
     ///
     /// ```
-    /// use murmur::{Whisper, IconKind};
-    ///
-    /// let whisper = Whisper::new()
-    ///     .icon(IconKind::NfFaBug)
-    ///     .message("message")
-    ///     .whisper()
-    ///     .ok();
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .message("connected")
+    ///     .field_debug("addr", &"db:5432")
+    ///     .field_debug("retries", &[1, 2])
+    ///     .render(&RenderOptions::plain());
+    /// assert_eq!(lines, ["connected addr=\"db:5432\" retries=[1, 2]"]);
     /// ```
     #[must_use]
-    pub fn new() -> Self {
-        Self {
-            icon_kind: None,
-            messages: Vec::new(),
-        }
+    pub fn field_debug<T: Debug + ?Sized>(mut self, key: &str, value: &T) -> Self {
+        self.fields.push((key.to_string(), format!("{value:?}")));
+        self
     }
 
-    /// Adds an icon to the `Whisper` instance.
+    /// Adds a structured field, formatting `value` with `Display` like `tracing::info!(key = %value)`.
     ///
-    /// # Arguments
+    /// See `field_debug` for how fields are rendered.
     ///
-    /// * `icon_kind`: The kind of icon to be displayed. See the `IconKind` enum for a list of available icons.
+    /// # Example
     ///
-    /// # Returns
+    /// ```
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .message("request served")
+    ///     .field_display("path", &"/health")
+    ///     .field_display("status", &200)
+    ///     .render(&RenderOptions { width: 20, ..RenderOptions::plain() });
+    /// assert_eq!(lines, ["request served", "  path=/health", "  status=200"]);
+    /// ```
+    #[must_use]
+    pub fn field_display<T: Display + ?Sized>(mut self, key: &str, value: &T) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets the correlation ID of the whisper, e.g. the request ID of a call to a service, so the
+    /// printed line can be matched with the traces of the service.
     ///
-    /// A `Whisper` instance with the specified icon.
+    /// The ID is printed dimmed in brackets after the last line, and written as `correlation_id` by
+    /// `JsonSink`.
     ///
-    /// # Examples
+    /// # Example
     ///
     /// ```
-    /// use murmur::{Whisper, IconKind};
-    /// let whisper = Whisper::new()
-    ///     .icon(IconKind::NfFaCheck)
-    ///     .message("Nerd Font icons if you have them installed")
-    ///     .message("Another message")
-    ///     .whisper()
-    ///     .expect("Failed to print message");
+    /// use murmur::{RenderOptions, Whisper};
     ///
-    /// let whisper = Whisper::new()
-    ///     .icon(IconKind::UnicodeWarningSign)
-    ///     .message("Unicode icons")
-    ///     .whisper()
-    ///     .or_else(|err| Err(err));
+    /// let lines = Whisper::new()
+    ///     .message("deploy requested")
+    ///     .correlation_id("5f0c9a1e")
+    ///     .render(&RenderOptions::plain());
+    /// assert_eq!(lines, ["deploy requested [5f0c9a1e]"]);
+    /// ```
     #[must_use]
-    pub fn icon(mut self, icon_kind: IconKind) -> Self {
-        self.icon_kind = Some(icon_kind);
+    pub fn correlation_id<T: Display + ?Sized>(mut self, id: &T) -> Self {
+        self.correlation_id = Some(id.to_string());
         self
     }
 
-    /// Adds a single message to the `Whisper` instance.
-    ///
-    /// # Arguments
-    ///
-    /// * `message`: The message to be added.
+    /// Sets the error code of the whisper, printed like a compiler diagnostic: the first message is
+    /// prefixed by the severity and the code, e.g. `error[E0423]: `.
     ///
-    /// # Returns
+    /// # Example
     ///
-    /// A `Whisper` instance with the added message.
+    /// ```
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .icon(IconKind::NfFaTimes)
+    ///     .message("expected value, found struct `Config`")
+    ///     .code("E0423")
+    ///     .render(&RenderOptions::plain());
+    /// # #[cfg(not(feature = "plain"))]
+    /// assert_eq!(lines, ["\u{f00d} error[E0423]: expected value, found struct `Config`"]);
+    /// ```
+    #[must_use]
+    pub fn code<T: Display + ?Sized>(mut self, code: &T) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    /// Sets the help text of the whisper, printed dimmed on a `help:` line after the messages.
     ///
     /// # Example
     ///
     /// ```
-    /// use murmur::Whisper;
-    /// let whisper = Whisper::new()
-    ///    .message("1 message without icon")
-    ///    .message("2 message")
-    ///    .message("3 message")
-    ///    .whisper()
-    ///    .ok();
-    /// ```
-    /// # Output
-    /// ```text
-    /// 1 message without icon
-    ///   2 message without icon indents by 2 spaces all messages after the first
-    ///   3 message
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .icon(IconKind::NfFaTimes)
+    ///     .message("configuration not found")
+    ///     .help("run `mytool doctor` for details")
+    ///     .render(&RenderOptions::plain());
+    /// # #[cfg(not(feature = "plain"))]
+    /// assert_eq!(
+    ///     lines,
+    ///     ["\u{f00d} configuration not found", "  help: run `mytool doctor` for details"]
+    /// );
     /// ```
     #[must_use]
-    pub fn message<T: Display + Debug>(mut self, message: T) -> Self {
-        self.messages.push(message.to_string());
+    pub fn help<T: Display + ?Sized>(mut self, help: &T) -> Self {
+        self.help = Some(help.to_string());
         self
     }
 
-    /// Adds multiple messages to the `Whisper` instance.
-    ///
-    /// # Arguments
+    /// Attaches the line numbered `line` of `source`, starting at 1, with `columns` underlined by
+    /// carets in the color of the whisper, see `Snippet`.
     ///
-    /// * `messages`: An iterable collection of items, each implementing `Display`, `Debug`, and `AsRef<str>`.
+    /// `columns` are in characters starting at 1, the end excluded. The snippet is printed after the
+    /// messages and before the help line.
     ///
-    /// Returns a `Whisper` instance with the added messages.
+    /// # Example
     ///
-    /// # Examples
+    /// ```
+    /// use murmur::{RenderOptions, Whisper};
     ///
+    /// let lines = Whisper::new()
+    ///     .message("unknown key `colour`")
+    ///     .snippet("[theme]\ncolour = \"dark\"\n", 2, 1..7)
+    ///     .render(&RenderOptions::plain());
+    /// assert_eq!(lines[1..], ["  2 | colour = \"dark\"", "    | ^^^^^^"]);
     /// ```
-    /// use murmur::Whisper;
+    #[must_use]
+    pub fn snippet(mut self, source: &str, line: usize, columns: Range<usize>) -> Self {
+        self.snippet = Some(Snippet::new(source, line, columns));
+        self
+    }
+
+    /// Passes each rendered line through `decorate`, called with the index of the line starting at
+    /// 0 and the line as printed, colors included only if the whisper is printed in color, to add
+    /// e.g. sequence numbers or checksums.
     ///
-    /// Whisper::new()
-    ///     .messages(["1 message", "2 message", "3 message"])
-    ///     .whisper()
-    ///     .ok();
+    /// The decorated lines are printed and handed to the sinks in place of the rendered ones.
     ///
-    /// Whisper::new()
-    ///     .messages(vec!["1 message", "2 message", "3 message"])
-    ///     .whisper()
-    ///     .ok();
+    /// # Example
     ///
     /// ```
-    /// # Output
-    /// ```text
-    /// 1 message
-    ///   2 message
-    ///   3 message
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .message("first")
+    ///     .message("second")
+    ///     .decorate(|index, line| format!("{:02} {line}", index + 1))
+    ///     .render(&RenderOptions::plain());
+    /// assert_eq!(lines, ["01 first", "02   second"]);
     /// ```
     #[must_use]
-    pub fn messages<I, S>(mut self, messages: I) -> Self
-    where
-        I: IntoIterator<Item = S>,
-        S: Display + Debug + AsRef<str>,
-    {
-        for message in messages {
-            self.messages.push(message.as_ref().to_string());
-        }
+    pub fn decorate(
+        mut self,
+        decorate: impl Fn(usize, &str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.decorator = Some(Decorator::new(decorate));
         self
     }
 
     /// The `whisper` function is responsible for building the `Whisper` instance and printing the messages.
     /// It performs several steps to ensure the messages are printed correctly:
     ///
-    /// 1. It first tries to lock the `ICON_MAP` to safely access the global variable in a concurrent environment.
-    /// 2. If the lock is successfully acquired, it checks the `icon_kind` field of the `Whisper` instance.
-    /// 3. If `icon_kind` is `Some`, it tries to get the corresponding icon and color from the `icon_map`.
-    /// 4. If `icon_kind` is `None` or if the `icon_kind` does not exist in the `icon_map`, it defaults to an empty string for both `icon` and `color`.
-    /// 5. Finally, it prints the messages with the specified color and an optional icon prefix.
+    /// 1. The whisper is skipped if it is below the verbosity, see `set_verbosity`, or if it has a rate
+    ///    limit and a similar whisper was printed within it.
+    /// 2. The context and label of the current thread are applied, and the glyph and color of its icon
+    ///    are looked up in `ICON_MAP` while holding its read lock, following the `UnknownIconPolicy`
+    ///    if the icon is missing.
+    /// 3. The whisper is rendered by `freeze()` with `RenderOptions::detect()`: the color is adapted by the global `Theme`,
+    ///    and the ASCII fallback of the icon is used if `term::decision()` calls for ASCII icons.
+    /// 4. It adds the `RenderedWhisper` to the history, if `keep_recent` turned it on, counts it in the
+    ///    `tally()` and notifies the observers.
+    /// 5. Finally, it takes the output lock and emits the `RenderedWhisper` to every `Sink` of the sink
+    ///    registry, by default only `StdoutSink` which prints the rendered lines to stdout.
+    ///
+    /// When `StdoutSink` is the only sink and stdout is not a terminal, steps 3 to 5 write the lines to
+    /// stdout while they are rendered, so huge whispers are not built in memory.
+    ///
+    /// Whispers are emitted atomically: the lines of concurrent whispers from different threads never
+    /// interleave, and every sink receives the whispers in the same order.
+    ///
+    /// This function is only available with the `std` feature, use `Whisper::render` without it.
     ///
     /// # Returns
     ///
@@ -369,8 +1647,11 @@ impl Whisper {
     ///
     /// # Errors
     ///
-    /// This function will return `WhisperError::Lock` if it fails to acquire a lock on the `ICON_MAP`.
+    /// This function will return `WhisperError::Lock` if a lock it takes is poisoned: the `ICON_MAP`, the
+    /// `Theme`, the rate limit state, the sink registry, the output lock or the queue of `queue_whispers`.
+    /// It returns `WhisperError::UnknownIcon` if the icon is missing from the `ICON_MAP` with `UnknownIconPolicy::Error`.
     /// It will return `WhisperError::Print` if there is an error while printing the messages.
+    /// It will return the error of any other sink failing to emit the whisper.
     ///
     /// # Example
     ///
@@ -387,25 +1668,162 @@ impl Whisper {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg(feature = "std")]
     pub fn whisper(&self) -> Result<(), WhisperError> {
-        // Try to lock the ICON_MAP for safe access in a concurrent environment
-        let icon_map = icon_map::ICON_MAP.read().map_err(|_| WhisperError::Lock)?;
+        self.whisper_with(0)
+    }
+
+    /// Whispers the `Whisper` like `whisper()`, its icon padded to `icon_width`, see `Aligner`.
+    #[cfg(feature = "std")]
+    pub(crate) fn whisper_with(&self, icon_width: usize) -> Result<(), WhisperError> {
+        if !timing::enabled() && !history::recording() && !observer::observed() && sink::streams() {
+            // Nothing keeps the rendered lines, write them as they are rendered
+            return self.stream(icon_width);
+        }
+        if !timing::enabled() {
+            // Render the whisper and emit it, unless it is rate limited
+            return self
+                .prepare(icon_width)?
+                .map_or(Ok(()), |rendered| rendered.whisper());
+        }
+        let start = std::time::Instant::now();
+        let Some(rendered) = self.prepare(icon_width)? else {
+            return Ok(());
+        };
+        let render = start.elapsed();
+        let result = rendered.whisper();
+        timing::record(&rendered, start, render);
+        result
+    }
 
-        // Check the icon_kind field of the Whisper instance
-        let (icon, color) = self.icon_kind.clone().map_or(("", ""), |icon_kind| {
-            icon_map.get(&icon_kind).map_or(("", ""), |value| *value)
-        });
+    /// Renders the `Whisper` for `whisper_with`, `None` if a similar one was printed within its rate limit.
+    #[cfg(feature = "std")]
+    pub(crate) fn prepare(
+        &self,
+        icon_width: usize,
+    ) -> Result<Option<RenderedWhisper>, WhisperError> {
+        self.admitted()?
+            .map(|whisper| whisper.freeze_with(icon_width))
+            .transpose()
+    }
 
-        // Print the messages with the specified color and an optional icon prefix
-        self.print_messages(icon, color)
-            .map_err(|_| WhisperError::Print)?;
+    /// Returns the `Whisper` to print, telling how many similar ones were suppressed since the
+    /// last one, `None` if it is below the verbosity, see `set_verbosity`, or if a similar one was
+    /// printed within its rate limit.
+    #[cfg(feature = "std")]
+    fn admitted(&self) -> Result<Option<Cow<'_, Self>>, WhisperError> {
+        if self.severity() < level::verbosity() {
+            return Ok(None);
+        }
+        let suppressed = match self.rate_limit {
+            Some(interval) => match rate_limit::admit(self, interval)? {
+                Some(suppressed) => suppressed,
+                None => return Ok(None),
+            },
+            None => 0,
+        };
+        Ok(Some(match suppressed {
+            0 => Cow::Borrowed(self),
+            _ => Cow::Owned(
+                self.clone()
+                    .message((strings::strings().suppressed)(suppressed)),
+            ),
+        }))
+    }
 
-        Ok(())
+    /// Writes the `Whisper` to stdout as it is rendered, like `whisper_with`, see `sink::streams`.
+    ///
+    /// Peak memory stays bounded by a line whatever the number of messages, e.g. for megabytes of
+    /// process output piped to a file.
+    #[cfg(feature = "std")]
+    fn stream(&self, icon_width: usize) -> Result<(), WhisperError> {
+        let Some(whisper) = self.admitted()? else {
+            return Ok(());
+        };
+        whisper.layout(icon_width, |whisper, icon, color, options, indent| {
+            sink::stream(|stdout| {
+                render::stream_lines(whisper, icon, color, options, indent, stdout)
+            })?;
+            tally::count(whisper.severity());
+            Ok(())
+        })
+    }
+
+    /// Renders the `Whisper` once, for printing it repeatedly with `RenderedWhisper::whisper()`.
+    ///
+    /// Whispering the same status line in a loop otherwise looks up the icon, applies the theme and
+    /// colors, and allocates the lines on every call. The frozen whisper keeps the icon, colors and
+    /// terminal decision of the moment it was frozen, and is not rate limited.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `WhisperError::Lock` if the read lock on the `ICON_MAP` or the `Theme` is poisoned.
+    /// It returns `WhisperError::UnknownIcon` if the icon is missing from the `ICON_MAP` with `UnknownIconPolicy::Error`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use murmur::{IconKind, Whisper};
+    ///
+    /// let waiting = Whisper::new()
+    ///     .icon(IconKind::NfFaClock)
+    ///     .message("waiting for the database")
+    ///     .freeze()
+    ///     .unwrap();
+    /// for _ in 0..3 {
+    ///     waiting.whisper().unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn freeze(&self) -> Result<RenderedWhisper, WhisperError> {
+        self.freeze_with(0)
+    }
+
+    /// Renders the `Whisper` like `freeze()`, its icon padded to `icon_width`, see `Aligner`.
+    #[cfg(feature = "std")]
+    pub(crate) fn freeze_with(&self, icon_width: usize) -> Result<RenderedWhisper, WhisperError> {
+        self.layout(icon_width, |whisper, icon, color, options, indent| {
+            let mut rendered = RenderedWhisper::with_glyph(whisper, icon, color, options);
+            indent::shift(&mut rendered, indent);
+            Ok(rendered)
+        })
+    }
+
+    /// Calls `render` with the `Whisper` in its context and label, its icon and icon color, the
+    /// options it is rendered with, its icon padded to `icon_width`, and the width of its indent.
+    #[cfg(feature = "std")]
+    fn layout<T>(
+        &self,
+        icon_width: usize,
+        render: impl FnOnce(
+            &Self,
+            &str,
+            Option<Color>,
+            &RenderOptions,
+            usize,
+        ) -> Result<T, WhisperError>,
+    ) -> Result<T, WhisperError> {
+        let whisper = context::apply(self);
+        let whisper = label::apply(&whisper);
+
+        // Look up the icon in the ICON_MAP, following the UnknownIconPolicy if it is missing
+        let (icon, color) = match whisper.icon_kind.as_ref() {
+            Some(icon_kind) => icon_map::lookup(icon_kind)?,
+            None => ("", None),
+        };
+
+        let indent = indent::width();
+        let options = RenderOptions::detect()?;
+        let options = RenderOptions {
+            icon_width,
+            width: options.width.saturating_sub(indent),
+            ..options
+        };
+        render(&whisper, icon, color, &options, indent)
     }
 
     /// Writes the output of a process as a whisper.
     ///
-    /// This function is only available when the `experimental` feature is enabled.
     /// It takes a reference to the output of a process and converts it to a UTF-8 string.
     /// Then, it creates a `Whisper` instance with the extracted message and invokes the
     /// `whisper` method to perform the whispering process.
@@ -422,25 +1840,27 @@ impl Whisper {
     ///
     /// The function may return a `WhisperError` if:
     ///
-    /// * The conversion from the output to a UTF-8 string fails.
+    /// * The conversion from the output to a UTF-8 string fails, see `WhisperError::Utf8Conversion`.
     /// * The whispering process fails.
     ///
     /// # Examples
     ///
     /// ```
+    /// use std::process::Command;
+    /// use murmur::{IconKind, Whisper};
     ///
+    /// let output = Command::new("cargo").arg("version").output().unwrap();
+    /// Whisper::new().icon(IconKind::NfFaCheck).whisper_out(&output).unwrap();
     /// ```
-    #[cfg(feature = "experimental")]
+    #[cfg(feature = "std")]
     pub fn whisper_out(self, output: &std::process::Output) -> Result<(), WhisperError> {
-        let message =
-            std::str::from_utf8(&output.stdout).map_err(|_| WhisperError::Utf8Conversion)?;
+        let message = std::str::from_utf8(&output.stdout).map_err(WhisperError::Utf8Conversion)?;
         let whisper = self.message(message);
         whisper.whisper()?;
         Ok(())
     }
 
     /// This function is used to handle error messages from a process output and send them as whispers.
-    /// It is only available when the "experimental" feature is enabled.
     ///
     /// # Arguments
     ///
@@ -455,109 +1875,34 @@ impl Whisper {
     /// # Example
     ///
     /// ```
+    /// use std::process::Command;
+    /// use murmur::{IconKind, Whisper};
     ///
+    /// let output = Command::new("cargo").arg("--unknown-flag").output().unwrap();
+    /// if !output.status.success() {
+    ///     Whisper::new().icon(IconKind::NfFaTimes).whisper_err(&output).unwrap();
+    /// }
     /// ```
     ///
     /// # Errors
     ///
-    #[cfg(feature = "experimental")]
+    /// Returns `WhisperError::Utf8Conversion` if `stderr` is not valid UTF-8, or the error of `whisper()`.
+    #[cfg(feature = "std")]
     pub fn whisper_err(self, output: &std::process::Output) -> Result<(), WhisperError> {
-        let message =
-            std::str::from_utf8(&output.stderr).map_err(|_| WhisperError::Utf8Conversion)?;
+        let message = std::str::from_utf8(&output.stderr).map_err(WhisperError::Utf8Conversion)?;
         let whisper = self.message(message);
         whisper.whisper()?;
         Ok(())
     }
-
-    /// Prints messages with a specific color and an optional icon prefix.
-    ///
-    /// This function is responsible for printing each message in the `Whisper` instance with a specific color and an optional icon prefix.
-    /// It first creates a new `ColorMap` instance to map colors to their corresponding formatting functions.
-    /// Then, it checks if the `messages` vector of the `Whisper` instance is empty. If it is, it creates a new vector with an empty string.
-    /// Otherwise, it clones the `messages` vector.
-    ///
-    /// For each message in the `messages` vector, it determines the prefix. If the message is the first in the vector, the prefix is the `icon`.
-    /// For all other messages, the prefix is two spaces.
-    ///
-    /// Finally, it calls the `murmur_message` function to print each message with the specified color and prefix.
-    /// If there is an error while printing the messages, it returns `WhisperError::Print`.
-    ///
-    /// # Arguments
-    ///
-    /// * `icon`: A string slice that represents the icon to be printed before each message.
-    /// * `color`: A string slice that represents the color of the messages and the icon.
-    ///
-    /// # Returns
-    ///
-    /// This function returns a `Result`. If the operation is successful, it returns `Ok(())`. If there is an error during the operation, it returns `WhisperError`.
-    ///
-    /// # Errors
-    ///
-    /// This function will return `WhisperError::Print` if there is an error while printing the messages.
-    fn print_messages(&self, icon: &str, color: &str) -> Result<(), WhisperError> {
-        let messages = if self.messages.is_empty() {
-            vec![String::new()]
-        } else {
-            self.messages.clone()
-        };
-
-        for (index, message) in messages.iter().enumerate() {
-            let prefix = if index == 0 { icon } else { "  " };
-            Self::print_message(color, prefix, message).map_err(|_| WhisperError::Print)?;
-        }
-        Ok(())
-    }
-
-    /// Prints a message to stdout with a specific color and prefix.
-    ///
-    /// This function is responsible for printing a message to stdout with a specific color and prefix.
-    /// It first creates a buffer writer with a specific buffer size for stdout.
-    /// Then, it checks if the color exists in the `COLOR_MAP`. If it does, it locks the `Mutex` to get a mutable reference to the function.
-    /// After that, it calls the function with the prefix and message as arguments and writes the result to the buffer writer.
-    /// If the color does not exist in the `COLOR_MAP`, it writes the prefix and message directly to the buffer writer.
-    /// Finally, it flushes the buffer writer to ensure that all data is written to stdout.
-    ///
-    /// # Arguments
-    ///
-    /// * `color`: A string slice that represents the color of the message.
-    /// * `prefix`: A string slice that represents the prefix to be printed before the message.
-    /// * `message`: A string slice that represents the message to be printed.
-    ///
-    /// # Returns
-    ///
-    /// This function returns a `Result`. If the operation is successful, it returns `Ok(())`. If there is an error during the operation, it returns `WhisperError`.
-    ///
-    /// # Errors
-    ///
-    /// This function will return `WhisperError::Lock` if it fails to acquire a lock on the `Mutex`.
-    /// It will return `WhisperError::Write` if there is an error while writing to the buffer.
-    /// It will return `WhisperError::Flush` if there is an error while flushing the buffer.
-    fn print_message(color: &str, prefix: &str, message: &str) -> Result<(), WhisperError> {
-        /// The buffer size for stdout, 8192 bytes.
-        const BUFFER_SIZE: usize = 8192;
-        let stdout = io::stdout();
-        let mut writer = BufWriter::with_capacity(BUFFER_SIZE, stdout.lock());
-
-        if let Some(color_fn) = color_map::COLOR_MAP.get(color) {
-            writeln!(writer, "{}{}", color_fn(prefix), color_fn(message))
-                .map_err(|_| WhisperError::Write)?;
-        } else {
-            writeln!(writer, "{prefix}{message}").map_err(|_| WhisperError::Write)?;
-        }
-
-        writer.flush().map_err(|_| WhisperError::Flush)?;
-        Ok(())
-    }
 }
 
 #[cfg(test)]
-#[cfg(feature = "experimental")]
-mod whisper_experimental {
+#[cfg(feature = "std")]
+mod whisper_process {
     use super::*;
     use std::error::Error;
-    use std::process::Command;
+    use std::process::{Command, Output};
 
-    //cargo test --features experimental --color=always --package murmur --lib whisper_experimental
     #[test]
     fn execute_cargo_version() -> Result<(), Box<dyn Error>> {
         // Declaration and initialization of whisper
@@ -597,6 +1942,24 @@ mod whisper_experimental {
         Ok(())
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn invalid_utf8_output_error() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let output = Output {
+            status: std::process::ExitStatus::from_raw(0),
+            stdout: b"ok \xff".to_vec(),
+            stderr: Vec::new(),
+        };
+        let error = Whisper::new().whisper_out(&output).unwrap_err();
+        let WhisperError::Utf8Conversion(utf8_error) = error else {
+            panic!("unexpected error {error:?}");
+        };
+        assert_eq!(utf8_error.valid_up_to(), 3);
+        assert!(error.source().is_some());
+    }
+
     #[test]
     #[allow(clippy::match_bool)]
     fn execute_cargo_version_box_dyn() -> Result<(), Box<dyn Error>> {
@@ -617,6 +1980,7 @@ mod whisper_experimental {
 }
 
 #[cfg(test)]
+#[cfg(all(feature = "std", feature = "color"))]
 mod whisper_color_override_tests {
     use super::*;
     use owo_colors::OwoColorize;
@@ -652,6 +2016,7 @@ mod whisper_color_override_tests {
 }
 
 #[cfg(test)]
+#[cfg(feature = "std")]
 mod whisper_functionality_tests {
     use super::*;
 
@@ -673,6 +2038,44 @@ mod whisper_functionality_tests {
             .ok();
     }
 
+    #[test]
+    fn test_whisper_sort_dedup_filter() {
+        let whisper = Whisper::new()
+            .message("c")
+            .message_with_icon(IconKind::NfFaBug, "a")
+            .messages(["b", "a"])
+            .sort();
+        assert_eq!(whisper.messages, ["a", "a", "b", "c"]);
+        assert_eq!(whisper.message_icon(0), Some(&IconKind::NfFaBug));
+        assert_eq!(whisper.message_icon(1), None);
+        let whisper = whisper.dedup();
+        assert_eq!(whisper.messages, ["a", "b", "c"]);
+        assert_eq!(whisper.message_icon(0), Some(&IconKind::NfFaBug));
+        let whisper = whisper.filter(|message| message != "a");
+        assert_eq!(whisper.messages, ["b", "c"]);
+        assert!(whisper.message_icons.is_empty());
+    }
+
+    #[test]
+    fn test_whisper_with_icon_with_messages() {
+        let template = Whisper::new()
+            .icon(IconKind::NfFaTimes)
+            .message_with_icon(IconKind::NfFaBug, "placeholder")
+            .command("cargo build")
+            .field_display("attempt", &1);
+        let copy = template.with_messages(["first", "second"]);
+        assert_eq!(copy.messages, ["first", "second"]);
+        assert!(copy.message_icons.is_empty());
+        assert!(copy.commands.is_empty());
+        assert_eq!(copy.fields, template.fields);
+        assert_eq!(copy.icon_kind, Some(IconKind::NfFaTimes));
+        assert_eq!(template.messages, ["placeholder", "cargo build"]);
+        let copy = template.with_icon(IconKind::NfFaWarning);
+        assert_eq!(copy.icon_kind, Some(IconKind::NfFaWarning));
+        assert_eq!(copy.messages, template.messages);
+        assert_eq!(template.icon_kind, Some(IconKind::NfFaTimes));
+    }
+
     #[test]
     fn test_whisper_no_icon_no_messages() {
         // Test creating a Whisper instance with no icon and no messages
@@ -876,6 +2279,87 @@ mod whisper_functionality_tests {
         assert_eq!(whisper.messages, vec![long_message]);
     }
 
+    #[test]
+    fn test_whisper_try_message() {
+        let whisper = Whisper::new().try_message(b"tab\tand\nnewline").unwrap();
+        assert_eq!(whisper.messages, ["tab\tand\nnewline"]);
+
+        let (whisper, error) = whisper.try_message(&[0x66, 0xff]).unwrap_err();
+        assert!(matches!(error, WhisperError::Utf8Conversion(_)));
+        let (whisper, error) = whisper
+            .try_message(&vec![b'a'; MAX_MESSAGE_LEN + 1])
+            .unwrap_err();
+        assert!(matches!(error, WhisperError::InvalidMessage(_)));
+        let (whisper, error) = whisper.try_message(b"50%\r100%").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid message: control character U+000D at byte 3"
+        );
+        assert_eq!(whisper.messages.len(), 1);
+
+        // Escaped control characters cannot corrupt the terminal
+        let whisper = whisper.escape_controls().try_message(b"50%\r100%").unwrap();
+        assert_eq!(whisper.messages.len(), 2);
+    }
+
+    #[test]
+    fn test_whisper_try_message_checked_when_added() {
+        let escaped = Whisper::new().escape_controls().try_message(b"a\rb");
+        assert!(escaped.is_ok());
+        let (whisper, error) = Whisper::new().try_message(b"a\rb").unwrap_err();
+        assert!(matches!(error, WhisperError::InvalidMessage(_)));
+        assert!(whisper.escape_controls().messages.is_empty());
+    }
+
+    #[test]
+    fn test_whisper_from_results() {
+        let whisper = Whisper::from_results([Ok::<_, &str>("a"), Ok("b")]);
+        assert_eq!(whisper.icon_kind, Some(IconKind::NfFaCheck));
+        assert_eq!(whisper.messages, ["2 succeeded, 0 failed", "a", "b"]);
+        assert_eq!(
+            whisper.message_icons,
+            [(1, IconKind::NfFaCheck), (2, IconKind::NfFaCheck)]
+        );
+
+        let whisper = Whisper::from_results(Vec::<Result<u8, u8>>::new());
+        assert_eq!(whisper.messages, ["0 succeeded, 0 failed"]);
+        assert_eq!(
+            Whisper::from_results([Err::<u8, _>(1)]).severity(),
+            Level::Error
+        );
+    }
+
+    #[test]
+    fn test_whisper_group_messages_by() {
+        let whisper = Whisper::new()
+            .message("header")
+            .message_with_icon(IconKind::NfFaBug, "b")
+            .message("a")
+            .group_messages_by(|message| {
+                if message == "a" {
+                    Level::Error
+                } else {
+                    Level::Info
+                }
+            });
+        assert_eq!(
+            whisper.messages,
+            ["header", "error (1)", "a", "info (1)", "b"]
+        );
+        assert_eq!(
+            whisper.message_icons,
+            [
+                (4, IconKind::NfFaBug),
+                (3, IconKind::NfFaInfoCircle),
+                (1, IconKind::NfFaTimes)
+            ]
+        );
+        assert_eq!(
+            Whisper::new().group_messages_by(|_| Level::Error).messages,
+            Vec::<String>::new()
+        );
+    }
+
     #[test]
     fn test_whisper_special_characters_in_message() {
         let special_message = "!@#$%^&*()";
@@ -897,6 +2381,22 @@ mod whisper_functionality_tests {
         assert!(result.is_ok());
         assert_eq!(whisper.messages, messages);
     }
+
+    #[test]
+    fn test_whisper_freeze() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaClock)
+            .message("frozen")
+            .message("second line");
+        let frozen = whisper.freeze().unwrap();
+        let options = RenderOptions {
+            color: false,
+            ..RenderOptions::detect().unwrap()
+        };
+        assert_eq!(frozen.plain_lines(), whisper.render(&options));
+        frozen.whisper().unwrap();
+        frozen.whisper().unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -906,7 +2406,10 @@ mod whisper_error_tests {
     #[test]
     fn whisper_error_lock_error() {
         let error = WhisperError::Lock;
-        assert_eq!(format!("{error}"), "Failed to acquire lock on ICON_MAP");
+        assert_eq!(
+            format!("{error}"),
+            "Failed to acquire a poisoned lock on the icon map, theme, sinks, output, rate limit or queue"
+        );
     }
 
     #[test]
@@ -926,4 +2429,28 @@ mod whisper_error_tests {
         let error = WhisperError::Flush;
         assert_eq!(format!("{error}"), "Error flushing buffer");
     }
+
+    #[test]
+    fn whisper_error_sink_error() {
+        let error = WhisperError::Sink;
+        assert_eq!(format!("{error}"), "Failed to emit whisper to a sink");
+    }
+
+    #[test]
+    fn whisper_error_transcript_error() {
+        let error = WhisperError::Transcript;
+        assert_eq!(format!("{error}"), "Failed to read transcript");
+    }
+
+    #[test]
+    fn whisper_error_read_error() {
+        let error = WhisperError::Read;
+        assert_eq!(format!("{error}"), "Failed to read input");
+    }
+
+    #[test]
+    fn whisper_error_unknown_icon_error() {
+        let error = WhisperError::UnknownIcon(IconKind::NfFaBug);
+        assert_eq!(format!("{error}"), "Unknown icon NfFaBug");
+    }
 }
@@ -0,0 +1,132 @@
+//! The `observer` module calls back the application on every whisper, e.g. to feed metrics.
+//!
+//! An `Observer` registered with `add_observer` sees a `WhisperEvent` for every whisper emitted by
+//! `whisper()` or `whisper_all`, with its level, label and size, so error and warning rates can be
+//! counted in Prometheus or statsd without wrapping every call. Closures are observers too.
+//!
+//! ```
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use murmur::{IconKind, Level, Whisper, WhisperEvent};
+//!
+//! static ERRORS: AtomicUsize = AtomicUsize::new(0);
+//!
+//! murmur::add_observer(|event: &WhisperEvent| {
+//!     if event.level == Level::Error {
+//!         ERRORS.fetch_add(1, Ordering::Relaxed);
+//!     }
+//! });
+//! Whisper::new().icon(IconKind::NfFaTimes).message("upload failed").whisper().unwrap();
+//! assert!(ERRORS.load(Ordering::Relaxed) > 0);
+//! ```
+//!
+use std::sync::RwLock;
+
+use crate::{Level, RenderedWhisper};
+
+/// `WhisperEvent` describes a whisper emitted, see `Observer`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct WhisperEvent<'a> {
+    /// The severity of the whisper.
+    pub level: Level,
+    /// The label of the whisper, see `Whisper::labeled()`.
+    pub label: Option<&'a str>,
+    /// The number of bytes printed to the terminal, escape sequences and newlines included.
+    pub bytes: usize,
+}
+
+impl<'a> WhisperEvent<'a> {
+    /// Returns the event of `rendered`.
+    fn new(rendered: &'a RenderedWhisper) -> Self {
+        Self {
+            level: rendered.level,
+            label: rendered.label.as_deref(),
+            bytes: rendered.lines().iter().map(|line| line.len() + 1).sum(),
+        }
+    }
+}
+
+/// `Observer` is called on every whisper emitted, see `add_observer`.
+pub trait Observer: Send + Sync {
+    /// Observes the emitted whisper described by `event`, it must not whisper itself.
+    fn observe(&self, event: &WhisperEvent<'_>);
+}
+
+impl<F: Fn(&WhisperEvent<'_>) + Send + Sync> Observer for F {
+    fn observe(&self, event: &WhisperEvent<'_>) {
+        self(event);
+    }
+}
+
+/// The registered observers.
+static OBSERVERS: RwLock<Vec<Box<dyn Observer>>> = RwLock::new(Vec::new());
+
+/// Registers `observer`, called on every following whisper.
+///
+/// # Panics
+///
+/// Panics if the observer lock is poisoned.
+pub fn add_observer<O: Observer + 'static>(observer: O) {
+    OBSERVERS.write().unwrap().push(Box::new(observer));
+}
+
+/// Removes every registered observer.
+///
+/// # Panics
+///
+/// Panics if the observer lock is poisoned.
+pub fn clear_observers() {
+    OBSERVERS.write().unwrap().clear();
+}
+
+/// Returns whether an observer is registered, see `add_observer`.
+pub fn observed() -> bool {
+    OBSERVERS
+        .read()
+        .is_ok_and(|observers| !observers.is_empty())
+}
+
+/// Calls every registered observer with the event of `rendered`.
+pub fn notify(rendered: &RenderedWhisper) {
+    let Ok(observers) = OBSERVERS.read() else {
+        return;
+    };
+    if observers.is_empty() {
+        return;
+    }
+    let event = WhisperEvent::new(rendered);
+    for observer in observers.iter() {
+        observer.observe(&event);
+    }
+}
+
+#[cfg(test)]
+mod observer_tests {
+    use super::*;
+    use crate::{RenderOptions, Whisper};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_event() {
+        let whisper = Whisper::labeled("observer_tests").messages(["first", "second"]);
+        let rendered = RenderedWhisper::new(&whisper, &RenderOptions::plain());
+        let event = WhisperEvent::new(&rendered);
+        assert_eq!(event.level, Level::Info);
+        assert_eq!(event.label, Some("observer_tests"));
+        assert_eq!(event.bytes, "first\n  second\n".len());
+    }
+
+    #[test]
+    fn test_notify() {
+        /// The number of events observed with the label of this test.
+        static OBSERVED: AtomicUsize = AtomicUsize::new(0);
+        add_observer(|event: &WhisperEvent| {
+            if event.label == Some("observer_tests_notify") {
+                OBSERVED.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        let whisper = Whisper::labeled("observer_tests_notify");
+        notify(&RenderedWhisper::new(&whisper, &RenderOptions::plain()));
+        assert_eq!(OBSERVED.load(Ordering::SeqCst), 1);
+    }
+}
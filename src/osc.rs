@@ -0,0 +1,134 @@
+//! The `osc` module integrates whispers with the terminal window, through `OSC` escape sequences.
+//!
+//! `set_title` sets the title of the terminal window or tab, `Whisper::set_title(true)` sets it to
+//! the first message of the whisper. `set_progress` reports the progress of a long operation with
+//! `OSC 9;4`, shown in the taskbar by Windows Terminal and in the tab by `ConEmu`, `konsole` and
+//! others, terminals without support ignore it.
+//!
+//! Sequences are only written when stdout is a terminal, not `TERM=dumb`, and the output is not
+//! deterministic.
+//!
+//! ```no_run
+//! use murmur::{IconKind, Progress, Whisper};
+//!
+//! Whisper::new().icon(IconKind::NfFaRefresh).message("installing").set_title(true).whisper().unwrap();
+//! for percent in (0..=100).step_by(10) {
+//!     murmur::set_progress(Progress::Normal(percent)).unwrap();
+//! }
+//! murmur::set_progress(Progress::Clear).unwrap();
+//! ```
+//!
+use std::io;
+
+use crate::{render, sink, term, WhisperError};
+
+/// `Progress` is the state of the progress indicator set with `set_progress`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Progress {
+    /// Removes the progress indicator.
+    Clear,
+    /// A progress in percent, values above 100 are clamped.
+    Normal(u8),
+    /// A failed operation, in the error color, with its progress in percent.
+    Error(u8),
+    /// An operation of unknown length.
+    Indeterminate,
+    /// A paused operation, in the warning color, with its progress in percent.
+    Paused(u8),
+}
+
+impl Progress {
+    /// Returns the `OSC 9;4` sequence of the progress.
+    fn sequence(self) -> String {
+        let (state, percent) = match self {
+            Self::Clear => (0, 0),
+            Self::Normal(percent) => (1, percent),
+            Self::Error(percent) => (2, percent),
+            Self::Indeterminate => (3, 0),
+            Self::Paused(percent) => (4, percent),
+        };
+        format!("\x1b]9;4;{state};{}\x07", percent.min(100))
+    }
+}
+
+/// Sets the title of the terminal window or tab to `title`.
+///
+/// Control characters and ANSI escape sequences are removed from the title, and only its first
+/// line is kept.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the output lock is poisoned, and `WhisperError::Print` if
+/// writing to stdout fails.
+pub fn set_title(title: &str) -> Result<(), WhisperError> {
+    write(&title_sequence(title))
+}
+
+/// Sets the progress indicator of the terminal, see `Progress`.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the output lock is poisoned, and `WhisperError::Print` if
+/// writing to stdout fails.
+pub fn set_progress(progress: Progress) -> Result<(), WhisperError> {
+    write(&progress.sequence())
+}
+
+/// Returns whether `OSC` sequences are written to stdout.
+pub fn enabled() -> bool {
+    let capabilities = term::capabilities();
+    capabilities.stdout_tty && !capabilities.dumb && !term::deterministic()
+}
+
+/// Writes `sequence` to stdout while holding the output lock, if sequences are enabled.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the output lock is poisoned, and `WhisperError::Print` if
+/// writing to stdout fails.
+pub fn write(sequence: &str) -> Result<(), WhisperError> {
+    if !enabled() {
+        return Ok(());
+    }
+    let _output = sink::lock_output()?;
+    let mut stdout = io::stdout().lock();
+    io::Write::write_all(&mut stdout, sequence.as_bytes())
+        .and_then(|()| io::Write::flush(&mut stdout))
+        .map_err(|_| WhisperError::Print)
+}
+
+/// Returns the `OSC 2` sequence setting the title to the first line of `title`, without escapes.
+pub fn title_sequence(title: &str) -> String {
+    let mut sequence = String::from("\x1b]2;");
+    let title = title.lines().next().unwrap_or_default();
+    sequence.extend(
+        render::strip_ansi(title)
+            .chars()
+            .filter(|character| !character.is_control()),
+    );
+    sequence.push('\x07');
+    sequence
+}
+
+#[cfg(test)]
+mod osc_tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_sequence() {
+        assert_eq!(Progress::Normal(42).sequence(), "\x1b]9;4;1;42\x07");
+        assert_eq!(Progress::Error(150).sequence(), "\x1b]9;4;2;100\x07");
+        assert_eq!(Progress::Indeterminate.sequence(), "\x1b]9;4;3;0\x07");
+        assert_eq!(Progress::Clear.sequence(), "\x1b]9;4;0;0\x07");
+    }
+
+    #[test]
+    fn test_title_sequence() {
+        assert_eq!(title_sequence("building"), "\x1b]2;building\x07");
+        assert_eq!(
+            title_sequence("\x1b[31mfailed\x1b[0m\x07\nsecond line"),
+            "\x1b]2;failed\x07"
+        );
+        assert_eq!(title_sequence(""), "\x1b]2;\x07");
+    }
+}
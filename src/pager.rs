@@ -0,0 +1,115 @@
+//! The `pager` module pipes long whispers through a pager instead of scrolling the terminal.
+//!
+//! A whisper is paged when it asks for it with `Whisper::page(true)`, or automatically when it has
+//! more lines than the terminal has rows. Whispers are only paged when stdout is a terminal and the
+//! output is not deterministic, piped output is never paged.
+//!
+//! The pager is `$PAGER`, `less -R` by default. `LESS` is set to `R` if unset, so `less` keeps the
+//! colors. `PAGER=cat` or an empty `PAGER` disables paging.
+//!
+//! ```no_run
+//! use murmur::{IconKind, Whisper};
+//!
+//! Whisper::new()
+//!     .icon(IconKind::NfFaInfoCircle)
+//!     .message("usage: tool [OPTIONS] <COMMAND>")
+//!     .messages((1..=100).map(|index| format!("command-{index}")))
+//!     .page(true)
+//!     .whisper()
+//!     .unwrap();
+//! ```
+//!
+use std::env;
+use std::io;
+use std::process::{Command, Stdio};
+
+use crate::{sink, term, RenderedWhisper};
+
+/// The pager used when `PAGER` is not set.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Returns whether `rendered` should be paged, see the module documentation.
+pub fn should_page(rendered: &RenderedWhisper) -> bool {
+    let capabilities = term::capabilities();
+    !term::deterministic()
+        && wants_pager(
+            rendered.page,
+            rendered.segments.len(),
+            capabilities.stdout_tty,
+            term::size().1,
+        )
+}
+
+/// Returns whether a whisper of `lines` lines asking for `page` is paged on a terminal of `height` rows.
+const fn wants_pager(page: Option<bool>, lines: usize, stdout_tty: bool, height: usize) -> bool {
+    stdout_tty
+        && match page {
+            Some(page) => page,
+            // Keep a row for the prompt following the whisper
+            None => lines >= height,
+        }
+}
+
+/// Pipes `lines` through the pager and waits until the user quits it.
+///
+/// # Errors
+///
+/// Returns an error if paging is disabled or the pager could not be started, the lines are then
+/// not printed. A pager quit before reading every line is not an error.
+pub fn page(lines: &[String]) -> io::Result<()> {
+    let (program, args) = pager_command(env::var("PAGER").ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "paging is disabled"))?;
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "R");
+    }
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        match sink::write_lines(&mut stdin, lines) {
+            Err(error) if error.kind() != io::ErrorKind::BrokenPipe => {
+                let _ = child.wait();
+                return Err(error);
+            }
+            _ => {}
+        }
+    }
+    child.wait().map(drop)
+}
+
+/// Returns the program and arguments of the pager given the value of `PAGER`, `None` if disabled.
+fn pager_command(pager: Option<String>) -> Option<(String, Vec<String>)> {
+    let pager = pager.unwrap_or_else(|| String::from(DEFAULT_PAGER));
+    let mut words = pager.split_whitespace().map(String::from);
+    let program = words.next().filter(|program| program != "cat")?;
+    Some((program, words.collect()))
+}
+
+#[cfg(test)]
+mod pager_tests {
+    use super::*;
+
+    #[test]
+    fn test_wants_pager() {
+        assert!(!wants_pager(None, 10, true, 24));
+        assert!(wants_pager(None, 24, true, 24));
+        assert!(!wants_pager(None, 100, false, 24));
+        assert!(wants_pager(Some(true), 1, true, 24));
+        assert!(!wants_pager(Some(true), 1, false, 24));
+        assert!(!wants_pager(Some(false), 100, true, 24));
+    }
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(
+            pager_command(None),
+            Some((String::from("less"), vec![String::from("-R")]))
+        );
+        assert_eq!(
+            pager_command(Some(String::from("most"))),
+            Some((String::from("most"), Vec::new()))
+        );
+        assert_eq!(pager_command(Some(String::from("cat"))), None);
+        assert_eq!(pager_command(Some(String::from("  "))), None);
+    }
+}
@@ -0,0 +1,273 @@
+//! The `prompt` module asks the user for input, styled like the other whispers.
+//!
+//! `Whisper::prompt` reads a line of text, with an optional default and validation, asking again
+//! with a warning while the input is invalid.
+//!
+//! ```no_run
+//! use murmur::Whisper;
+//!
+//! let name = Whisper::prompt("Project name")
+//!     .default("demo")
+//!     .validate(|name| {
+//!         if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+//!             Ok(())
+//!         } else {
+//!             Err(String::from("use letters, digits and dashes"))
+//!         }
+//!     })
+//!     .read()
+//!     .unwrap();
+//! ```
+//!
+//! `pause` waits for a key press, e.g. between the steps of a demo script.
+//!
+//! ```no_run
+//! murmur::pause("Press any key to continue\u{2026}").unwrap();
+//! ```
+//!
+//! `Whisper::prompt_hidden` reads a secret such as a password or a token without echoing it, with
+//! termios on Unix and the console API on Windows. The input is returned as a `Secret`, which does
+//! not print its value and overwrites it when dropped.
+//!
+//! ```no_run
+//! use murmur::Whisper;
+//!
+//! let token = Whisper::prompt_hidden("API token").unwrap();
+//! assert!(!format!("{token:?}").contains(token.expose()));
+//! ```
+//!
+use core::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::{cursor, osc, sink, sys, IconKind, Whisper, WhisperError};
+
+/// `Secret` is a string that is not printed by `Debug` and is overwritten when dropped.
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Wraps `secret`.
+    #[must_use]
+    pub const fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    /// Returns the secret value.
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        let mut bytes = core::mem::take(&mut self.0).into_bytes();
+        bytes.fill(0);
+        // Keep the compiler from removing the writes to memory about to be freed
+        core::hint::black_box(&bytes);
+    }
+}
+
+/// A validation function of a `Prompt`, returning the warning to show for invalid input.
+type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+
+/// `Prompt` asks for a line of text, see `Whisper::prompt`.
+pub struct Prompt {
+    /// The question asked.
+    question: String,
+    /// The answer used when the input is empty.
+    default: Option<String>,
+    /// The validation of the answer.
+    validator: Option<Validator>,
+}
+
+impl fmt::Debug for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Prompt")
+            .field("question", &self.question)
+            .field("default", &self.default)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Prompt {
+    /// Sets the answer used when the input is empty, shown in brackets after the question.
+    #[must_use]
+    pub fn default(mut self, default: &str) -> Self {
+        self.default = Some(String::from(default));
+        self
+    }
+
+    /// Sets the validation of the answer, returning the warning to show if it is invalid.
+    #[must_use]
+    pub fn validate<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Asks the question and reads the answer from stdin, until a valid answer is entered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Print` if the question could not be printed, and `WhisperError::Read`
+    /// if stdin could not be read or ended before a valid answer.
+    pub fn read(&self) -> Result<String, WhisperError> {
+        self.read_from(&mut io::stdin().lock())
+    }
+
+    /// Asks the question and reads the answer from `input`, see `read`.
+    fn read_from<R: BufRead>(&self, input: &mut R) -> Result<String, WhisperError> {
+        let question = self.default.as_ref().map_or_else(
+            || format!("{}: ", self.question),
+            |default| format!("{} [{default}]: ", self.question),
+        );
+        loop {
+            print_prompt(IconKind::NfFaQuestionCircle, &question)?;
+            let mut line = String::new();
+            if input.read_line(&mut line).map_err(|_| WhisperError::Read)? == 0 {
+                return Err(WhisperError::Read);
+            }
+            let answer = match line.trim() {
+                "" => self.default.clone().unwrap_or_default(),
+                answer => String::from(answer),
+            };
+            match self
+                .validator
+                .as_ref()
+                .map_or(Ok(()), |validator| validator(&answer))
+            {
+                Ok(()) => return Ok(answer),
+                Err(warning) => Whisper::new()
+                    .icon(IconKind::NfFaWarning)
+                    .message(warning)
+                    .whisper()?,
+            }
+        }
+    }
+}
+
+impl Whisper {
+    /// Returns a `Prompt` asking `prompt`, read with `Prompt::read`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use murmur::Whisper;
+    ///
+    /// let name = Whisper::prompt("Project name").default("demo").read().unwrap();
+    /// ```
+    #[must_use]
+    pub fn prompt(prompt: &str) -> Prompt {
+        Prompt {
+            question: String::from(prompt),
+            default: None,
+            validator: None,
+        }
+    }
+
+    /// Prints `prompt` with a key icon and reads a line without echoing it, e.g. a password.
+    ///
+    /// The input is read from the controlling terminal, or from stdin when there is none. Echo
+    /// cannot be disabled without the `windows-vt` feature on Windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Print` if the prompt could not be printed, and `WhisperError::Read`
+    /// if the input could not be read or ended before a line.
+    pub fn prompt_hidden(prompt: &str) -> Result<Secret, WhisperError> {
+        print_prompt(IconKind::NfFaKey, &format!("{prompt}: "))?;
+        let line = sys::read_hidden_line();
+        // The enter key was not echoed, end the prompt line
+        print("\n")?;
+        line.map(Secret::new).ok_or(WhisperError::Read)
+    }
+}
+
+/// Prints `prompt` and waits for a key press, then clears the prompt.
+///
+/// On terminals any key continues. On dumb terminals, or when there is no terminal, the enter key
+/// must be pressed and the prompt is left in the output.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Print` if the prompt could not be printed, and `WhisperError::Read` if
+/// stdin could not be read.
+pub fn pause(prompt: &str) -> Result<(), WhisperError> {
+    print_prompt(IconKind::NfFaInfoCircle, prompt)?;
+    if osc::enabled() && sys::read_key().is_some() {
+        return osc::write(cursor::CLEAR_LINE);
+    }
+    let mut line = String::new();
+    if io::stdin()
+        .read_line(&mut line)
+        .map_err(|_| WhisperError::Read)?
+        == 0
+    {
+        // No enter key was echoed at the end of input, end the prompt line
+        print("\n")?;
+    }
+    Ok(())
+}
+
+/// Prints `question` with `icon_kind`, without newline so the answer follows it.
+fn print_prompt(icon_kind: IconKind, question: &str) -> Result<(), WhisperError> {
+    let lines = Whisper::new()
+        .icon(icon_kind)
+        .message(question)
+        .freeze()?
+        .lines();
+    print(&lines.join("\n"))
+}
+
+/// Prints `text` to stdout while holding the output lock.
+fn print(text: &str) -> Result<(), WhisperError> {
+    let _output = sink::lock_output()?;
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(text.as_bytes())
+        .and_then(|()| stdout.flush())
+        .map_err(|_| WhisperError::Print)
+}
+
+#[cfg(test)]
+mod prompt_tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_debug() {
+        let secret = Secret::new(String::from("hunter2"));
+        assert_eq!(format!("{secret:?}"), "Secret(***)");
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn test_prompt_default_and_validation() {
+        let prompt = Whisper::prompt("Project name")
+            .default("demo")
+            .validate(|name| {
+                if name.contains(' ') {
+                    Err(String::from("prompt_tests: no spaces"))
+                } else {
+                    Ok(())
+                }
+            });
+        assert_eq!(prompt.read_from(&mut &b"\n"[..]).unwrap(), "demo");
+        assert_eq!(
+            prompt.read_from(&mut &b"my app\nmy-app\n"[..]).unwrap(),
+            "my-app"
+        );
+        assert!(matches!(
+            prompt.read_from(&mut &b"my app\n"[..]),
+            Err(WhisperError::Read)
+        ));
+    }
+}
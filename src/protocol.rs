@@ -0,0 +1,121 @@
+//! The `protocol` module parses whispers written as text lines, e.g. by shell scripts.
+//!
+//! A protocol line starts with directives between `::`, separated by commas, followed by the
+//! message: `::icon=warn,color=yellow:: disk almost full`. Makefiles and shell scripts only need
+//! `echo` to emit structured whispers, which a murmur-based supervisor, or the `murmur` binary,
+//! re-renders like its own. Lines without directives are plain messages.
+//!
+//! The directives are:
+//! - `icon`: an icon name, see `IconKind::from_name`
+//! - `color`: a built-in color name, see `Color::builtin`
+//! - `level`: a level name, see `Level::from_name`
+//! - `label`: a label, see `Whisper::labeled`
+//! - `id`: a correlation ID, see `Whisper::correlation_id`
+//!
+//! ```
+//! use murmur::{IconKind, Level};
+//!
+//! let whisper = murmur::from_protocol_line("::icon=warn,level=error:: disk almost full").unwrap();
+//! assert_eq!(whisper.icon_kind, Some(IconKind::NfFaWarning));
+//! assert_eq!(whisper.level, Some(Level::Error));
+//! assert_eq!(whisper.messages, ["disk almost full"]);
+//! ```
+//!
+use alloc::format;
+use alloc::string::String;
+
+use crate::{Color, IconKind, Level, Whisper, WhisperError};
+
+/// The marker around the directives of a protocol line.
+const MARKER: &str = "::";
+
+/// Returns the whisper written as the protocol line `line`, see the module documentation.
+///
+/// The single space after the directives is not part of the message. A trailing newline is
+/// ignored, so lines can be passed as read.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Protocol` if the directives are not closed, or a directive is unknown or
+/// has an invalid value.
+pub fn from_protocol_line(line: &str) -> Result<Whisper, WhisperError> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let Some(rest) = line.strip_prefix(MARKER) else {
+        return Ok(Whisper::new().message(line));
+    };
+    let (directives, message) = rest
+        .split_once(MARKER)
+        .ok_or_else(|| WhisperError::Protocol(format!("unclosed directives in {line:?}")))?;
+    let message = message.strip_prefix(' ').unwrap_or(message);
+    let mut whisper = Whisper::new().message(message);
+    for directive in directives
+        .split(',')
+        .filter(|directive| !directive.is_empty())
+    {
+        whisper = apply(whisper, directive)?;
+    }
+    Ok(whisper)
+}
+
+/// Returns `whisper` with the `key=value` directive `directive` applied.
+fn apply(whisper: Whisper, directive: &str) -> Result<Whisper, WhisperError> {
+    let (key, value) = directive
+        .split_once('=')
+        .ok_or_else(|| WhisperError::Protocol(format!("directive {directive:?} has no value")))?;
+    let unknown = |kind: &str| WhisperError::Protocol(format!("unknown {kind} {value:?}"));
+    Ok(match key.trim() {
+        "icon" => whisper.icon(IconKind::from_name(value).ok_or_else(|| unknown("icon"))?),
+        "color" => whisper.color(Color::builtin(value).ok_or_else(|| unknown("color"))?),
+        "level" => whisper.level(Level::from_name(value).ok_or_else(|| unknown("level"))?),
+        "label" => Whisper {
+            label: Some(String::from(value)),
+            ..whisper
+        },
+        "id" => whisper.correlation_id(value),
+        key => return Err(WhisperError::Protocol(format!("unknown directive {key:?}"))),
+    })
+}
+
+#[cfg(test)]
+mod protocol_tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_line() {
+        let whisper = from_protocol_line("just text: a::b\n").unwrap();
+        assert_eq!(whisper, Whisper::new().message("just text: a::b"));
+    }
+
+    #[test]
+    fn test_directives() {
+        let whisper =
+            from_protocol_line("::icon=check,color=green,label=build,id=42:: done").unwrap();
+        assert_eq!(whisper.icon_kind, Some(IconKind::NfFaCheck));
+        assert_eq!(whisper.color, Some(Color::Green));
+        assert_eq!(whisper.label.as_deref(), Some("build"));
+        assert_eq!(whisper.correlation_id.as_deref(), Some("42"));
+        assert_eq!(whisper.messages, ["done"]);
+        assert_eq!(
+            from_protocol_line("::::  indented").unwrap().messages,
+            [" indented"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_lines() {
+        let reason = |line| match from_protocol_line(line) {
+            Err(WhisperError::Protocol(reason)) => reason,
+            result => panic!("{line:?} parsed as {result:?}"),
+        };
+        assert_eq!(
+            reason("::icon=warn message"),
+            r#"unclosed directives in "::icon=warn message""#
+        );
+        assert_eq!(
+            reason("::icon:: message"),
+            r#"directive "icon" has no value"#
+        );
+        assert_eq!(reason("::icon=nope:: message"), r#"unknown icon "nope""#);
+        assert_eq!(reason("::size=2:: message"), r#"unknown directive "size""#);
+    }
+}
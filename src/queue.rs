@@ -0,0 +1,100 @@
+//! The `queue` module holds whispers back, to emit them by priority at a chosen point.
+//!
+//! After `queue_whispers`, whispers are queued instead of being emitted to the sinks. `drain`
+//! emits the queued whispers by severity, errors first and informational notes last, whatever
+//! order they were whispered in, and stops queueing. Whispers of the same severity keep their
+//! order. This lets a tool print a final report sorted by importance while its checks run in any
+//! order.
+//!
+//! ```
+//! use murmur::{IconKind, Whisper};
+//!
+//! murmur::queue_whispers();
+//! Whisper::new().message("scanned 12 files").whisper().unwrap();
+//! Whisper::new().icon(IconKind::NfFaTimes).message("config.toml: invalid key").whisper().unwrap();
+//! // Prints the error first
+//! murmur::drain().unwrap();
+//! ```
+//!
+use std::sync::Mutex;
+
+use crate::{sink, RenderedWhisper, WhisperError};
+
+/// The queued whispers, `None` when whispers are not queued.
+static QUEUE: Mutex<Option<Vec<RenderedWhisper>>> = Mutex::new(None);
+
+/// Starts queueing whispers until `drain` is called, see the module documentation.
+///
+/// # Panics
+///
+/// Panics if the queue lock is poisoned.
+pub fn queue_whispers() {
+    QUEUE.lock().unwrap().get_or_insert_with(Vec::new);
+}
+
+/// Emits the queued whispers to the sinks as a single batch, by decreasing severity, and stops
+/// queueing.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the queue lock is poisoned, or the error of `emit_batch`.
+pub fn drain() -> Result<(), WhisperError> {
+    let queued = QUEUE.lock().map_err(|_| WhisperError::Lock)?.take();
+    let Some(mut queued) = queued.filter(|queued| !queued.is_empty()) else {
+        return Ok(());
+    };
+    sort(&mut queued);
+    sink::emit_batch(&queued)
+}
+
+/// Returns whether whispers are queued, see `queue_whispers`.
+pub fn queueing() -> bool {
+    QUEUE.lock().is_ok_and(|queue| queue.is_some())
+}
+
+/// Queues `batch` if whispers are queued, and returns whether it was.
+pub fn push(batch: &[RenderedWhisper]) -> bool {
+    let Ok(mut queue) = QUEUE.lock() else {
+        return false;
+    };
+    queue.as_mut().is_some_and(|queued| {
+        queued.extend_from_slice(batch);
+        true
+    })
+}
+
+/// Sorts `queued` by decreasing severity, keeping the order of whispers of the same severity.
+fn sort(queued: &mut [RenderedWhisper]) {
+    queued.sort_by_key(|rendered| core::cmp::Reverse(rendered.level));
+}
+
+#[cfg(test)]
+mod queue_tests {
+    use super::*;
+    use crate::{IconKind, Level, RenderOptions, Whisper};
+
+    #[test]
+    fn test_sort() {
+        let rendered = |whisper: Whisper| RenderedWhisper::new(&whisper, &RenderOptions::plain());
+        let mut queued = [
+            rendered(Whisper::new().message("first note")),
+            rendered(
+                Whisper::new()
+                    .icon(IconKind::NfFaWarning)
+                    .message("warning"),
+            ),
+            rendered(Whisper::new().message("second note")),
+            rendered(Whisper::new().icon(IconKind::NfFaTimes).message("error")),
+        ];
+        sort(&mut queued);
+        let levels = queued
+            .iter()
+            .map(|rendered| rendered.level)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            levels,
+            [Level::Error, Level::Warn, Level::Info, Level::Info]
+        );
+        assert_eq!(queued[2].messages, ["first note"]);
+    }
+}
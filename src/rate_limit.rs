@@ -0,0 +1,148 @@
+//! The `rate_limit` module keeps noisy whispers from flooding the terminal.
+//!
+//! A whisper with `Whisper::rate_limit(interval)` is printed at most once per `interval`. Whispers
+//! are similar when they have the same icon and messages. The next whisper printed after some were
+//! suppressed ends with a `suppressed N similar messages` line.
+//!
+//! ```
+//! use std::time::Duration;
+//! use murmur::{IconKind, Whisper};
+//!
+//! for _ in 0..100 {
+//!     // Printed once, the other 99 are suppressed
+//!     Whisper::new()
+//!         .icon(IconKind::NfFaRefresh)
+//!         .message("retrying connection")
+//!         .rate_limit(Duration::from_secs(1))
+//!         .whisper()
+//!         .unwrap();
+//! }
+//! ```
+//!
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Whisper, WhisperError};
+
+/// The number of tracked whispers above which expired entries are dropped.
+const MAX_ENTRIES: usize = 1024;
+
+/// The rate limiting state of similar whispers.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    /// When a whisper was last printed.
+    printed_at: Instant,
+    /// The interval of the last printed whisper.
+    interval: Duration,
+    /// The number of whispers suppressed since.
+    suppressed: usize,
+}
+
+/// The rate limiting state, keyed by the hash of the icon and messages.
+static ENTRIES: LazyLock<Mutex<HashMap<u64, Entry>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Decides whether `whisper` is printed now, given its rate limit `interval`.
+///
+/// Returns `None` if the whisper is suppressed, otherwise the number of similar whispers
+/// suppressed since the last one was printed.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the rate limiting lock is poisoned.
+pub fn admit(whisper: &Whisper, interval: Duration) -> Result<Option<usize>, WhisperError> {
+    let mut entries = ENTRIES.lock().map_err(|_| WhisperError::Lock)?;
+    Ok(admit_at(
+        &mut entries,
+        key(whisper),
+        interval,
+        Instant::now(),
+    ))
+}
+
+/// Decides whether the whisper with `key` is printed at `now`, see `admit`.
+fn admit_at(
+    entries: &mut HashMap<u64, Entry>,
+    key: u64,
+    interval: Duration,
+    now: Instant,
+) -> Option<usize> {
+    if let Some(entry) = entries.get_mut(&key) {
+        if now.duration_since(entry.printed_at) < entry.interval {
+            entry.suppressed += 1;
+            return None;
+        }
+    }
+    if entries.len() >= MAX_ENTRIES {
+        entries.retain(|_, entry| now.duration_since(entry.printed_at) < entry.interval);
+    }
+    let suppressed = entries.get(&key).map_or(0, |entry| entry.suppressed);
+    entries.insert(
+        key,
+        Entry {
+            printed_at: now,
+            interval,
+            suppressed: 0,
+        },
+    );
+    Some(suppressed)
+}
+
+/// Returns the key of similar whispers: the hash of the icon and messages.
+fn key(whisper: &Whisper) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    whisper.icon_kind.hash(&mut hasher);
+    whisper.messages.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::*;
+    use crate::IconKind;
+
+    #[test]
+    fn test_admit_suppresses_within_interval() {
+        let mut entries = HashMap::new();
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        assert_eq!(admit_at(&mut entries, 1, second, start), Some(0));
+        assert_eq!(admit_at(&mut entries, 1, second, start + second / 2), None);
+        assert_eq!(admit_at(&mut entries, 1, second, start + second / 2), None);
+        assert_eq!(
+            admit_at(&mut entries, 2, second, start + second / 2),
+            Some(0)
+        );
+        assert_eq!(admit_at(&mut entries, 1, second, start + second), Some(2));
+        assert_eq!(
+            admit_at(&mut entries, 1, second, start + second * 3),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_similar_whispers_share_a_key() {
+        let whisper = Whisper::new().icon(IconKind::NfFaRefresh).message("retry");
+        assert_eq!(
+            key(&whisper),
+            key(&whisper.clone().rate_limit(Duration::ZERO))
+        );
+        assert_ne!(key(&whisper), key(&whisper.clone().message("again")));
+    }
+
+    #[test]
+    fn test_whisper_rate_limit() {
+        for _ in 0..3 {
+            Whisper::new()
+                .message("test_whisper_rate_limit")
+                .rate_limit(Duration::from_mins(1))
+                .whisper()
+                .unwrap();
+        }
+        let whisper = Whisper::new().message("test_whisper_rate_limit");
+        let suppressed = ENTRIES.lock().unwrap()[&key(&whisper)].suppressed;
+        assert_eq!(suppressed, 2);
+    }
+}
@@ -0,0 +1,1683 @@
+//! The `render` module turns a `Whisper` into the lines it prints, without touching any output.
+//!
+//! It only needs `alloc`, so projects without the standard library, e.g. embedded targets, can
+//! format whispers with murmur and send the lines over their own transport (UART, RTT, a socket...).
+//!
+//! ```
+//! use murmur::{IconKind, RenderOptions, Whisper};
+//!
+//! let lines = Whisper::new()
+//!     .icon(IconKind::UnicodeCheckMark)
+//!     .message("flashed")
+//!     .render(&RenderOptions::plain());
+//! # #[cfg(not(feature = "plain"))]
+//! assert_eq!(lines, ["\u{2714}\u{fe0f} flashed"]);
+//! ```
+//!
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::segment::{self, Segment, Style};
+use crate::width;
+#[cfg(feature = "std")]
+use crate::{history, observer, sink, tally, term, theme, WhisperError};
+use crate::{Color, IconKind, Level, Presentation, Theme, Whisper};
+
+/// The width assumed by `RenderOptions::default()` and `RenderOptions::plain()`.
+const DEFAULT_WIDTH: usize = 80;
+
+/// `RenderOptions` decides how a `Whisper` is rendered to strings.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RenderOptions {
+    /// Whether the lines are wrapped in ANSI color escape sequences.
+    pub color: bool,
+    /// Whether icons are replaced by their ASCII fallback, see `IconKind::ascii`.
+    pub ascii_icons: bool,
+    /// The theme that adapts the color of the icons.
+    pub theme: Theme,
+    /// The width of the output in columns, fields that do not fit inline go on their own lines.
+    pub width: usize,
+    /// The minimum width of icons including their space, see `Aligner`, 0 to not pad them.
+    pub icon_width: usize,
+    /// Whether commands with a link are wrapped in an `OSC 8` hyperlink, only when rendering with color.
+    pub hyperlinks: bool,
+    /// How the variation selectors of icon glyphs are printed, see `Presentation`.
+    pub presentation: Presentation,
+    /// Whether `Nf` icons are printed as glyphs, their `Unicode` counterpart is printed otherwise.
+    pub nerd_fonts: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            color: true,
+            ascii_icons: false,
+            theme: Theme::dark(),
+            width: DEFAULT_WIDTH,
+            icon_width: 0,
+            hyperlinks: false,
+            presentation: Presentation::Emoji,
+            nerd_fonts: true,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Options rendering icon glyphs without any color, useful when the transport is not a terminal.
+    #[must_use]
+    pub const fn plain() -> Self {
+        Self {
+            color: false,
+            ascii_icons: false,
+            theme: Theme::dark(),
+            width: DEFAULT_WIDTH,
+            icon_width: 0,
+            hyperlinks: false,
+            presentation: Presentation::Emoji,
+            nerd_fonts: true,
+        }
+    }
+
+    /// The options `whisper()` uses: the `term::decision()` for this process and the global `Theme`.
+    ///
+    /// The width is the current terminal width, see `term::size()`, or 80 columns if
+    /// `term::deterministic()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if the theme lock is poisoned.
+    #[cfg(feature = "std")]
+    pub fn detect() -> Result<Self, WhisperError> {
+        let decision = term::decision();
+        let theme = theme::THEME.read().map_err(|_| WhisperError::Lock)?.clone();
+        Ok(Self {
+            color: decision.color,
+            ascii_icons: decision.ascii_icons,
+            theme,
+            width: if term::deterministic() {
+                DEFAULT_WIDTH
+            } else {
+                term::size().0
+            },
+            icon_width: 0,
+            hyperlinks: term::capabilities().hyperlinks && !term::deterministic(),
+            presentation: term::presentation(),
+            nerd_fonts: decision.nerd_fonts,
+        })
+    }
+}
+
+impl Whisper {
+    /// Renders the `Whisper` to the lines `whisper()` would print, without the trailing newlines.
+    ///
+    /// The icon and its color come from the built-in icon table, so runtime changes made to
+    /// `ICON_MAP` only affect `whisper()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let lines = Whisper::new()
+    ///     .icon(IconKind::NfFaCheck)
+    ///     .messages(["first", "second"])
+    ///     .render(&RenderOptions { ascii_icons: true, ..RenderOptions::plain() });
+    /// assert_eq!(lines, ["v first", "  second"]);
+    /// ```
+    #[must_use]
+    pub fn render(&self, options: &RenderOptions) -> Vec<String> {
+        let (glyph, color) = self.icon_kind.as_ref().map_or(("", None), icon_glyph);
+        render_lines(self, glyph, color, options)
+    }
+
+    /// Renders the `Whisper` to the lines `render` returns, as styled segments instead of strings,
+    /// see `Segment`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let whisper = Whisper::new().icon(IconKind::NfFaCheck).messages(["first", "second"]);
+    /// let options = RenderOptions::default();
+    /// let lines = whisper.segments(&options);
+    /// assert_eq!(lines[1][1].text, "second");
+    /// assert_eq!(lines.iter().map(|line| murmur::ansi_line(line)).collect::<Vec<_>>(), whisper.render(&options));
+    /// ```
+    #[must_use]
+    pub fn segments(&self, options: &RenderOptions) -> Vec<Vec<Segment<'static>>> {
+        let (glyph, color) = self.icon_kind.as_ref().map_or(("", None), icon_glyph);
+        render_segments(self, glyph, color, options, options.color)
+    }
+
+    /// Appends the lines `render` returns to `out`, each followed by a newline.
+    ///
+    /// The lines are written straight into `out` instead of a `String` each, so high-throughput
+    /// callers, e.g. log shippers, reuse a single buffer across whispers by clearing it.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{IconKind, RenderOptions, Whisper};
+    ///
+    /// let options = RenderOptions { ascii_icons: true, ..RenderOptions::plain() };
+    /// let mut buffer = String::with_capacity(4096);
+    /// for step in ["fetch", "build"] {
+    ///     buffer.clear();
+    ///     Whisper::new().icon(IconKind::NfFaCheck).message(step).render_into(&options, &mut buffer);
+    ///     assert_eq!(buffer, format!("v {step}\n"));
+    /// }
+    /// ```
+    pub fn render_into(&self, options: &RenderOptions, out: &mut String) {
+        let (glyph, color) = self.icon_kind.as_ref().map_or(("", None), icon_glyph);
+        // Writing to a `String` cannot fail
+        let _ = write_lines(self, glyph, color, options, out, |out| {
+            out.push('\n');
+            Ok(())
+        });
+    }
+}
+
+#[cfg(feature = "std")]
+impl Whisper {
+    /// Writes the lines `render` returns to `writer`, each followed by a newline.
+    ///
+    /// The lines are written segment by segment instead of being built in memory first, so peak
+    /// memory stays bounded when whispering megabytes of process output. Wrap `writer` in a
+    /// `BufWriter` to avoid many small writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `writer`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::io::{self, BufWriter, Write};
+    /// use murmur::{RenderOptions, Whisper};
+    ///
+    /// let output = "line\n".repeat(100_000);
+    /// let whisper = Whisper::new().message("build log").messages(output.lines());
+    /// let mut stdout = BufWriter::new(io::stdout().lock());
+    /// whisper.write_to(&mut stdout, &RenderOptions::detect().unwrap()).unwrap();
+    /// stdout.flush().unwrap();
+    /// ```
+    pub fn write_to<W: io::Write>(
+        &self,
+        writer: &mut W,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let (glyph, color) = self.icon_kind.as_ref().map_or(("", None), icon_glyph);
+        let mut adapter = IoWriter {
+            writer,
+            error: None,
+        };
+        let result = write_lines(self, glyph, color, options, &mut adapter, |out| {
+            fmt::Write::write_str(out, "\n")
+        });
+        adapter.finish(result)
+    }
+}
+
+/// Writes the lines of `whisper` to `writer` as `RenderedWhisper::with_glyph` renders them, each
+/// indented by `indent` spaces and followed by a newline, without building them in memory.
+///
+/// # Errors
+///
+/// Returns the error of `writer`.
+#[cfg(feature = "std")]
+pub fn stream_lines<W: io::Write + ?Sized>(
+    whisper: &Whisper,
+    glyph: &str,
+    color: Option<Color>,
+    options: &RenderOptions,
+    indent: usize,
+    writer: &mut W,
+) -> io::Result<()> {
+    let indent = " ".repeat(indent);
+    let mut adapter = IoWriter {
+        writer,
+        error: None,
+    };
+    let result = segment_lines(whisper, glyph, color, options, options.color, |segments| {
+        fmt::Write::write_str(&mut adapter, &indent)?;
+        segment::write_ansi(&mut adapter, &segments)?;
+        fmt::Write::write_str(&mut adapter, "\n")
+    });
+    adapter.finish(result)
+}
+
+/// `IoWriter` writes formatted text to an `io::Write`, keeping the error `fmt::Write` cannot carry.
+#[cfg(feature = "std")]
+struct IoWriter<'a, W: ?Sized> {
+    /// The writer the text is written to.
+    writer: &'a mut W,
+    /// The error of the last failing write.
+    error: Option<io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: ?Sized> IoWriter<'_, W> {
+    /// Returns the error of the writer if `result` failed because of it.
+    fn finish(self, result: fmt::Result) -> io::Result<()> {
+        match (result, self.error) {
+            (Ok(()), _) => Ok(()),
+            (Err(_), Some(error)) => Err(error),
+            (Err(_), None) => Err(io::Error::other("failed to format whisper")),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write + ?Sized> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.writer.write_all(text.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// `AnsiPolicy` decides whether a sink writes the ANSI escape sequences of colors, see
+/// `RenderedWhisper::lines_for()`.
+///
+/// Every whisper is rendered once for all sinks to styled segments, so each sink serializes them
+/// with its own policy: the terminal follows the color decision, a log file stays plain unless asked otherwise.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum AnsiPolicy {
+    /// Follow the color decision of the process, see `term::decision()`.
+    #[default]
+    Auto,
+    /// Always write colors, e.g. for logs read with `less -R`.
+    Always,
+    /// Never write colors.
+    Never,
+}
+
+/// `TextDirection` is the direction of the messages of a `Whisper`, see `Whisper::direction`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum TextDirection {
+    /// Left to right, the icon is printed before the messages.
+    #[default]
+    Ltr,
+    /// Right to left, e.g. for Arabic or Hebrew, the icon is printed after the messages.
+    Rtl,
+}
+
+/// `Decorator` rewrites each rendered line of a `Whisper`, see `Whisper::decorate`.
+///
+/// It is called with the index of the line, starting at 0, and the line as printed, colors
+/// included only if the whisper is printed in color, and returns the line to print instead. Two decorators are equal only if they are
+/// clones of the same one.
+#[derive(Clone)]
+pub struct Decorator(Arc<DecorateFn>);
+
+/// The function wrapped by a `Decorator`.
+type DecorateFn = dyn Fn(usize, &str) -> String + Send + Sync;
+
+impl Decorator {
+    /// Creates a `Decorator` calling `decorate` for each line.
+    #[must_use]
+    pub fn new(decorate: impl Fn(usize, &str) -> String + Send + Sync + 'static) -> Self {
+        Self(Arc::new(decorate))
+    }
+
+    /// Returns the decorated version of the line numbered `index`, starting at 0.
+    #[must_use]
+    pub fn apply(&self, index: usize, line: &str) -> String {
+        (self.0)(index, line)
+    }
+}
+
+impl fmt::Debug for Decorator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Decorator(..)")
+    }
+}
+
+impl PartialEq for Decorator {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Decorator {}
+
+/// `RenderedWhisper` is a `Whisper` rendered once, handed to every `Sink`.
+///
+/// It carries the styled segments of its lines, so every sink serializes them for its output, with
+/// or without colors, without rendering the whisper again.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RenderedWhisper {
+    /// The icon of the whisper.
+    pub icon_kind: Option<IconKind>,
+    /// The severity of the whisper, see `Whisper::severity()`.
+    pub level: Level,
+    /// The messages of the whisper.
+    pub messages: Vec<String>,
+    /// The structured fields of the whisper, see `Whisper::field_debug()`.
+    pub fields: Vec<(String, String)>,
+    /// The rendered lines as styled segments, colored whatever the options, each sink serializes
+    /// them for its output, see `lines_for()`.
+    pub segments: Vec<Vec<Segment<'static>>>,
+    /// Whether the options of the whisper call for color, followed by `AnsiPolicy::Auto`.
+    pub color: bool,
+    /// Whether `StdoutSink` pipes the lines through a pager, see `Whisper::page()`.
+    pub page: Option<bool>,
+    /// Whether `StdoutSink` sets the terminal title to the first message, see `Whisper::set_title()`.
+    pub set_title: bool,
+    /// The correlation ID of the whisper, see `Whisper::correlation_id()`.
+    pub correlation_id: Option<String>,
+    /// The label of the whisper, see `Whisper::labeled()`.
+    pub label: Option<String>,
+}
+
+impl RenderedWhisper {
+    /// Renders `whisper` with `options`, the icon comes from the built-in icon table.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{IconKind, Level, RenderOptions, RenderedWhisper, Whisper};
+    ///
+    /// let whisper = Whisper::new().icon(IconKind::NfFaWarning).message("slow");
+    /// let rendered = RenderedWhisper::new(&whisper, &RenderOptions::default());
+    /// assert_eq!(rendered.level, Level::Warn);
+    /// # #[cfg(not(feature = "plain"))]
+    /// assert_eq!(rendered.plain_lines(), ["\u{f071} slow"]);
+    /// ```
+    #[must_use]
+    pub fn new(whisper: &Whisper, options: &RenderOptions) -> Self {
+        let (glyph, color) = whisper.icon_kind.as_ref().map_or(("", None), icon_glyph);
+        Self::with_glyph(whisper, glyph, color, options)
+    }
+
+    /// Renders `whisper` with `options`, using `glyph` and `color` for its icon.
+    pub(crate) fn with_glyph(
+        whisper: &Whisper,
+        glyph: &str,
+        color: Option<Color>,
+        options: &RenderOptions,
+    ) -> Self {
+        // The whisper is rendered once with color whatever the options, the uncolored lines are
+        // the text of the segments. Links and the decorator follow the color of the options.
+        let colored = RenderOptions {
+            color: true,
+            hyperlinks: options.hyperlinks && options.color,
+            ..options.clone()
+        };
+        Self {
+            icon_kind: whisper.icon_kind.clone(),
+            level: whisper.severity(),
+            messages: whisper.messages.clone(),
+            fields: whisper.fields.clone(),
+            segments: render_segments(whisper, glyph, color, &colored, options.color),
+            color: options.color,
+            page: whisper.page,
+            set_title: whisper.set_title,
+            correlation_id: whisper.correlation_id.clone(),
+            label: whisper.label.clone(),
+        }
+    }
+}
+
+impl RenderedWhisper {
+    /// Returns whether a sink with the ANSI policy `ansi` writes colors, see `AnsiPolicy`.
+    #[must_use]
+    pub const fn ansi(&self, ansi: AnsiPolicy) -> bool {
+        match ansi {
+            AnsiPolicy::Auto => self.color,
+            AnsiPolicy::Always => true,
+            AnsiPolicy::Never => false,
+        }
+    }
+
+    /// Returns the lines a sink with the ANSI policy `ansi` writes, serialized from the segments.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{AnsiPolicy, RenderOptions, RenderedWhisper, Whisper};
+    ///
+    /// let rendered = RenderedWhisper::new(&Whisper::new().message("done"), &RenderOptions::plain());
+    /// assert_eq!(rendered.lines_for(AnsiPolicy::Never), ["done"]);
+    /// assert_eq!(rendered.lines_for(AnsiPolicy::Auto), rendered.lines());
+    /// ```
+    #[must_use]
+    pub fn lines_for(&self, ansi: AnsiPolicy) -> Vec<String> {
+        let ansi = self.ansi(ansi);
+        self.segments
+            .iter()
+            .map(|line| segment::serialize_line(line, ansi))
+            .collect()
+    }
+
+    /// Returns the lines printed to the terminal, colored if the options call for it.
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        self.lines_for(AnsiPolicy::Auto)
+    }
+
+    /// Returns the lines without color, e.g. for a log file.
+    #[must_use]
+    pub fn plain_lines(&self) -> Vec<String> {
+        self.lines_for(AnsiPolicy::Never)
+    }
+}
+
+#[cfg(feature = "std")]
+impl RenderedWhisper {
+    /// Emits the rendered whisper again without rendering it, see `Whisper::freeze()`.
+    ///
+    /// The whisper is added to the history, counted in the tally and emitted to every registered `Sink`,
+    /// like `Whisper::whisper()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if a sink lock is poisoned, or the error of a failing sink.
+    pub fn whisper(&self) -> Result<(), WhisperError> {
+        history::push(self);
+        tally::count(self.level);
+        observer::notify(self);
+        sink::emit(self)
+    }
+}
+
+/// Renders the messages of `whisper` to lines of segments, see `segment_lines`.
+fn render_segments(
+    whisper: &Whisper,
+    glyph: &str,
+    color: Option<Color>,
+    options: &RenderOptions,
+    ansi: bool,
+) -> Vec<Vec<Segment<'static>>> {
+    let mut lines = Vec::new();
+    // Collecting the lines cannot fail
+    let _ = segment_lines(whisper, glyph, color, options, ansi, |segments| {
+        lines.push(segments.into_iter().map(Segment::into_owned).collect());
+        Ok(())
+    });
+    lines
+}
+
+/// Renders the messages of `whisper` to lines, see `write_lines`.
+fn render_lines(
+    whisper: &Whisper,
+    glyph: &str,
+    color: Option<Color>,
+    options: &RenderOptions,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    // Writing to a `String` cannot fail
+    let _ = write_lines(whisper, glyph, color, options, &mut line, |line| {
+        lines.push(core::mem::take(line));
+        Ok(())
+    });
+    lines
+}
+
+/// Writes the messages of `whisper` to `out`, see `segment_lines`, serialized with the escape
+/// sequences of their styles.
+///
+/// * `end_line`: called after each line, the lines are not followed by a newline.
+///
+/// # Errors
+///
+/// Returns the error of `out` or `end_line`.
+fn write_lines<W: fmt::Write>(
+    whisper: &Whisper,
+    glyph: &str,
+    color: Option<Color>,
+    options: &RenderOptions,
+    out: &mut W,
+    mut end_line: impl FnMut(&mut W) -> fmt::Result,
+) -> fmt::Result {
+    segment_lines(whisper, glyph, color, options, options.color, |segments| {
+        segment::write_ansi(out, &segments)?;
+        end_line(out)
+    })
+}
+
+/// Renders the messages of `whisper` to lines of segments passed to `line`, the first one prefixed
+/// by `glyph` and the others indented by 2 spaces, or `RenderOptions::icon_width` if set, followed
+/// by its fields.
+///
+/// * `glyph`: the icon followed by its space, empty for no icon.
+/// * `color`: the default color of the icon, a name of the color map.
+///
+/// A whisper without messages renders as a single line with only the icon. The messages are
+/// segments as they are, without copying them, unless they are normalized or followed by fields.
+///
+/// The lines are passed through the decorator of the whisper, if any, see `Whisper::decorate`.
+/// The decorator sees the line as printed, serialized with escape sequences only if `ansi` is set,
+/// the text it adds around it is kept as unstyled segments.
+///
+/// # Errors
+///
+/// Returns the error of `line`.
+fn segment_lines(
+    whisper: &Whisper,
+    glyph: &str,
+    color: Option<Color>,
+    options: &RenderOptions,
+    ansi: bool,
+    mut line: impl FnMut(Vec<Segment<'_>>) -> fmt::Result,
+) -> fmt::Result {
+    let Some(decorator) = &whisper.decorator else {
+        return write_segments(whisper, glyph, color, options, line);
+    };
+    let mut index = 0;
+    let mut undecorated = String::new();
+    write_segments(whisper, glyph, color, options, |mut segments| {
+        undecorated.clear();
+        if ansi {
+            segment::write_ansi(&mut undecorated, &segments)?;
+        } else {
+            undecorated.extend(segments.iter().map(Segment::plain));
+        }
+        let decorated = decorator.apply(index, &undecorated);
+        index += 1;
+        let Some(start) = decorated.find(undecorated.as_str()) else {
+            return line(vec![Segment::new(decorated, Style::default())]);
+        };
+        let end = start + undecorated.len();
+        if end < decorated.len() {
+            segments.push(Segment::new(
+                String::from(&decorated[end..]),
+                Style::default(),
+            ));
+        }
+        if start > 0 {
+            segments.insert(
+                0,
+                Segment::new(String::from(&decorated[..start]), Style::default()),
+            );
+        }
+        line(segments)
+    })
+}
+
+/// Renders the lines of `whisper` to segments without decorating them, see `segment_lines`.
+///
+/// # Errors
+///
+/// Returns the error of `line`.
+fn write_segments(
+    whisper: &Whisper,
+    glyph: &str,
+    color: Option<Color>,
+    options: &RenderOptions,
+    mut line: impl FnMut(Vec<Segment<'_>>) -> fmt::Result,
+) -> fmt::Result {
+    let plain;
+    let options = if whisper.plain && options.color {
+        plain = RenderOptions {
+            color: false,
+            ..options.clone()
+        };
+        &plain
+    } else {
+        options
+    };
+    let icon = icon_prefix(whisper.icon_kind.as_ref(), glyph, whisper.icon_gap, options);
+    let timestamp = whisper
+        .timestamp
+        .map(|timestamp| format!("{} ", timestamp.column()));
+    let whisper_color = whisper.color.map_or_else(
+        || line_color(whisper.icon_kind.as_ref(), color, options),
+        |color| printable(color, options),
+    );
+
+    let normalize = |text| clean(text, whisper, options);
+    let mut texts = message_texts(whisper, options);
+    let fields = whisper
+        .fields
+        .iter()
+        .map(|(key, value)| (normalize(key), normalize(value)))
+        .collect::<Vec<_>>();
+    let timestamp_width = timestamp.as_deref().map_or(0, width::display_width);
+    push_fields(
+        &mut texts,
+        &fields,
+        timestamp_width + width::display_width(&icon),
+        options.width,
+    );
+
+    // A single column glyph followed by its gap
+    let icon_columns = whisper.icon_gap.map_or(2, |gap| gap + 1);
+    let continuation = pad(String::new(), options.icon_width.max(icon_columns));
+    let stripe_color = whisper.stripe.and_then(|stripe| printable(stripe, options));
+
+    let command_indent = format!("{continuation}  ");
+
+    for (index, text) in texts.iter().enumerate() {
+        let command = command(whisper, index);
+        let indent = match (index, command) {
+            (0, _) => "",
+            (_, Some(_)) => command_indent.as_str(),
+            (_, None) => continuation.as_str(),
+        };
+        let (prefix, prefix_color) = match (whisper.message_icon(index), index) {
+            (None, 0) => (Cow::Borrowed(icon.as_str()), whisper_color),
+            (line_icon, _) => {
+                let colors = (whisper_color, stripe_color);
+                line_prefix(whisper, index, line_icon, indent, colors, options)
+            }
+        };
+        // Texts colored by the caller keep their colors, wrapping them would be cut by their resets
+        let text_color = if has_ansi(text) || command.is_some() {
+            None
+        } else {
+            prefix_color
+        };
+        let mut text = Segment::new(text.as_ref(), Style::color(text_color));
+        if options.hyperlinks && options.color {
+            text.link = command.flatten().map(Cow::Borrowed);
+        }
+        let mut segments = Vec::new();
+        if let Some(timestamp) = &timestamp {
+            segments.push(timestamp_segment(timestamp, index == 0, options));
+        }
+        push_text(
+            &mut segments,
+            whisper.direction,
+            (prefix, prefix_color),
+            text,
+        );
+        if let Some(id) = whisper.correlation_id.as_deref() {
+            if index + 1 == texts.len() {
+                segments.push(Segment::new(
+                    format!(" [{}]", normalize(id)),
+                    Style::color(dim_color(options)),
+                ));
+            }
+        }
+        line(segments)?;
+    }
+    for parts in trailer_lines(whisper, whisper_color, options) {
+        let mut segments = Vec::new();
+        if let Some(timestamp) = &timestamp {
+            segments.push(timestamp_segment(timestamp, false, options));
+        }
+        segments.push(Segment::new(continuation.as_str(), Style::default()));
+        segments.extend(
+            parts
+                .into_iter()
+                .map(|(color, part)| Segment::new(part, Style::color(color))),
+        );
+        line(segments)?;
+    }
+    Ok(())
+}
+
+/// Pushes the segments of `text` and of its `prefix` in `prefix_color` to `segments`, in the order
+/// of `direction`.
+fn push_text<'a>(
+    segments: &mut Vec<Segment<'a>>,
+    direction: TextDirection,
+    (prefix, prefix_color): (Cow<'a, str>, Option<Color>),
+    mut text: Segment<'a>,
+) {
+    match direction {
+        TextDirection::Ltr => {
+            segments.push(Segment::new(prefix, Style::color(prefix_color)));
+            segments.push(text);
+        }
+        TextDirection::Rtl => {
+            // Isolate the text with a right-to-left base direction, the icon follows it
+            text.text = Cow::Owned(format!("\u{2067}{}\u{2069}", text.text));
+            segments.push(text);
+            let icon = prefix.trim();
+            if !icon.is_empty() {
+                segments.push(Segment::new(format!(" {icon}"), Style::color(prefix_color)));
+            }
+        }
+    }
+}
+
+/// Returns the lines printed after the messages of `whisper`, its snippet and its help, as the
+/// parts of each line with their color.
+fn trailer_lines<'a>(
+    whisper: &'a Whisper,
+    whisper_color: Option<Color>,
+    options: &RenderOptions,
+) -> Vec<Vec<(Option<Color>, Cow<'a, str>)>> {
+    let dim = dim_color(options);
+    let mut lines = Vec::new();
+    if let Some(snippet) = &whisper.snippet {
+        // The line number in a gutter, then the carets under the columns
+        let number = format!("{}", snippet.line);
+        let (before, underlined) = snippet.split();
+        let offset = width::display_width(&clean(before, whisper, options));
+        let carets = width::display_width(&clean(underlined, whisper, options)).max(1);
+        lines.push(vec![
+            (dim, Cow::Owned(format!("{number} | "))),
+            (None, clean(&snippet.text, whisper, options)),
+        ]);
+        lines.push(vec![
+            (
+                dim,
+                Cow::Owned(format!("{} | ", pad(String::new(), number.len()))),
+            ),
+            (None, Cow::Owned(pad(String::new(), offset))),
+            (whisper_color, Cow::Owned("^".repeat(carets))),
+        ]);
+    }
+    if let Some(help) = whisper.help.as_deref() {
+        lines.push(vec![
+            (dim, Cow::Borrowed("help: ")),
+            (dim, clean(help, whisper, options)),
+        ]);
+    }
+    lines
+}
+
+/// Returns the prefix of the line of the message at `index`, after the first line, and its color.
+///
+/// * `line_icon`: the icon of the message, see `Whisper::message_icon`.
+/// * `indent`: the indent of the line, before its icon if any.
+/// * `colors`: the color of the whisper and its stripe color.
+fn line_prefix<'a>(
+    whisper: &Whisper,
+    index: usize,
+    line_icon: Option<&IconKind>,
+    indent: &'a str,
+    (whisper_color, stripe_color): (Option<Color>, Option<Color>),
+    options: &RenderOptions,
+) -> (Cow<'a, str>, Option<Color>) {
+    match line_icon {
+        Some(line_icon) => {
+            let (glyph, color) = line_icon.glyph_and_color();
+            (
+                Cow::Owned(format!(
+                    "{indent}{}",
+                    icon_prefix(Some(line_icon), glyph, whisper.icon_gap, options)
+                )),
+                line_color(Some(line_icon), Some(color), options),
+            )
+        }
+        // The indent of commands is not colored, so copying them copies no escape sequence
+        None if command(whisper, index).is_some() => (Cow::Borrowed(indent), None),
+        // Every other line takes the stripe color
+        None if index % 2 == 1 && stripe_color.is_some() => (Cow::Borrowed(indent), stripe_color),
+        None => (Cow::Borrowed(indent), whisper_color),
+    }
+}
+
+/// Returns the messages of `whisper` ready to be rendered, a single empty text without messages.
+///
+/// With an error code, the first text is prefixed by the compiler-style header, e.g. `error[E0423]: `.
+fn message_texts<'a>(whisper: &'a Whisper, options: &RenderOptions) -> Vec<Cow<'a, str>> {
+    let mut texts = if whisper.messages.is_empty() {
+        vec![Cow::Borrowed("")]
+    } else {
+        whisper
+            .messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| match command(whisper, index) {
+                Some(_) => clean_command(message, whisper.tab_width),
+                None => clean(message, whisper, options),
+            })
+            .collect()
+    };
+    if let Some(code) = whisper.code.as_deref() {
+        let severity = match whisper.severity() {
+            Level::Warn => "warning",
+            level => level.as_str(),
+        };
+        let code = clean(code, whisper, options);
+        texts[0] = Cow::Owned(format!("{severity}[{code}]: {}", texts[0]));
+    }
+    texts
+}
+
+/// Returns the segment of the `timestamp` column, dimmed on the `first` line, and spaces as wide on
+/// the others so the icons and messages stay aligned.
+fn timestamp_segment<'a>(timestamp: &'a str, first: bool, options: &RenderOptions) -> Segment<'a> {
+    if !first {
+        return Segment::new(
+            pad(String::new(), width::display_width(timestamp)),
+            Style::default(),
+        );
+    }
+    Segment::new(timestamp, Style::color(dim_color(options)))
+}
+
+/// Returns the color of the timestamp and the correlation ID, `None` without color.
+fn dim_color(options: &RenderOptions) -> Option<Color> {
+    printable(Color::BrightBlack, options)
+}
+
+/// Returns `color` if the options print colors and it can be printed, see `Color::paint()`.
+fn printable(color: Color, options: &RenderOptions) -> Option<Color> {
+    options
+        .color
+        .then_some(color)
+        .filter(|color| color.paint("").is_some())
+}
+
+/// Returns the prefix of a line marked with `icon_kind`: its `glyph`, the glyph of its `Unicode`
+/// counterpart without `NerdFonts`, or its ASCII fallback when glyphs cannot be displayed or are
+/// compiled out.
+///
+/// The variation selectors of the glyph follow `RenderOptions::presentation`, the space following
+/// it is replaced by `gap` spaces if set, and the prefix is padded to `RenderOptions::icon_width`.
+fn icon_prefix(
+    icon_kind: Option<&IconKind>,
+    glyph: &str,
+    gap: Option<usize>,
+    options: &RenderOptions,
+) -> String {
+    let mut prefix = match icon_kind {
+        Some(icon_kind)
+            if cfg!(feature = "plain") || (!glyph.is_empty() && options.ascii_icons) =>
+        {
+            format!("{} ", icon_kind.ascii())
+        }
+        // Without NerdFonts, the icon is printed as its Unicode counterpart
+        Some(icon_kind) if !options.nerd_fonts && !glyph.is_empty() => {
+            let unicode = icon_kind.to_unicode();
+            let glyph = if unicode == *icon_kind {
+                glyph
+            } else {
+                unicode.glyph()
+            };
+            options.presentation.apply(glyph).into_owned()
+        }
+        _ => options.presentation.apply(glyph).into_owned(),
+    };
+    if let Some(gap) = gap.filter(|_| !prefix.is_empty()) {
+        prefix.truncate(prefix.trim_end_matches(' ').len());
+        prefix.extend(core::iter::repeat_n(' ', gap));
+    }
+    pad(prefix, options.icon_width)
+}
+
+/// Pads `text` with spaces to `width` columns.
+fn pad(mut text: String, width: usize) -> String {
+    let text_width = width::display_width(&text);
+    text.extend(core::iter::repeat_n(' ', width.saturating_sub(text_width)));
+    text
+}
+
+/// Returns the color of a line marked with `icon_kind`, given its default `color` and adapted by
+/// the theme, `None` if the line is not colored.
+fn line_color(
+    icon_kind: Option<&IconKind>,
+    color: Option<Color>,
+    options: &RenderOptions,
+) -> Option<Color> {
+    let color = match icon_kind {
+        Some(icon_kind) => options.theme.color_for(icon_kind, color?),
+        None => color?,
+    };
+    printable(color, options)
+}
+
+/// Returns the glyph and the default color of `icon_kind`, see `IconKind::glyph_and_color()`.
+fn icon_glyph(icon_kind: &IconKind) -> (&'static str, Option<Color>) {
+    let (glyph, color) = icon_kind.glyph_and_color();
+    (glyph, Some(color))
+}
+
+/// Returns `text` ready to be rendered: its ANSI escape sequences stripped when rendering without
+/// color, then normalized, see `normalize`.
+fn clean<'a>(text: &'a str, whisper: &Whisper, options: &RenderOptions) -> Cow<'a, str> {
+    let (tab_width, escape_controls) = (whisper.tab_width, whisper.escape_controls);
+    if options.color {
+        return normalize(text, tab_width, escape_controls);
+    }
+    match strip_ansi(text) {
+        Cow::Borrowed(text) => normalize(text, tab_width, escape_controls),
+        Cow::Owned(text) => Cow::Owned(normalize(&text, tab_width, escape_controls).into_owned()),
+    }
+}
+
+/// Returns the link of the message at `index` if it is a command, see `Whisper::command()`.
+#[allow(clippy::option_option)]
+fn command(whisper: &Whisper, index: usize) -> Option<Option<&str>> {
+    whisper
+        .commands
+        .iter()
+        .rev()
+        .find_map(|(existing, link)| (*existing == index).then_some(link.as_deref()))
+}
+
+/// Returns `command` ready to be copied: without escape sequences, tabs expanded if `tab_width`
+/// is set, and control characters escaped.
+fn clean_command(command: &str, tab_width: Option<usize>) -> Cow<'_, str> {
+    match strip_ansi(command) {
+        Cow::Borrowed(command) => normalize(command, tab_width, true),
+        Cow::Owned(command) => Cow::Owned(normalize(&command, tab_width, true).into_owned()),
+    }
+}
+
+/// Returns whether `text` contains an ANSI escape sequence, e.g. colors added with `owo_colors`.
+pub fn has_ansi(text: &str) -> bool {
+    text.contains("\u{1b}[")
+}
+
+/// Returns `text` without its ANSI escape sequences: `ESC [` up to the final byte, and `ESC ]`,
+/// e.g. hyperlinks, up to `BEL` or `ESC \`.
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\u{1b}') {
+        return Cow::Borrowed(text);
+    }
+    let mut stripped = String::with_capacity(text.len());
+    let mut characters = text.chars().peekable();
+    while let Some(character) = characters.next() {
+        match (character, characters.peek()) {
+            ('\u{1b}', Some('[')) => {
+                characters
+                    .by_ref()
+                    .skip(1)
+                    .find(|character| ('\u{40}'..='\u{7e}').contains(character));
+            }
+            ('\u{1b}', Some(']')) => {
+                characters.next();
+                while let Some(character) = characters.next() {
+                    if character == '\u{7}'
+                        || (character == '\u{1b}' && characters.next_if_eq(&'\\').is_some())
+                    {
+                        break;
+                    }
+                }
+            }
+            _ => stripped.push(character),
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+/// Expands the tabs of `text` to stops every `tab_width` columns, if set, and replaces its other
+/// control characters by visible escapes if `escape_controls` is set.
+///
+/// C0 control characters and DEL are escaped in caret notation (`^M`, `^[`), C1 control characters
+/// are replaced by `U+FFFD`. Newlines are kept, so multi-line field values stay multi-line.
+fn normalize(text: &str, tab_width: Option<usize>, escape_controls: bool) -> Cow<'_, str> {
+    let unchanged = |character: char| match character {
+        '\t' => tab_width.is_none() && !escape_controls,
+        '\n' => true,
+        _ => !escape_controls || !character.is_control(),
+    };
+    if text.chars().all(unchanged) {
+        return Cow::Borrowed(text);
+    }
+    let mut normalized = String::with_capacity(text.len());
+    // The start of the current line of `normalized`, tab stops are relative to it
+    let mut line_start = 0;
+    for character in text.chars() {
+        match character {
+            '\t' if tab_width.is_some() => {
+                let tab_width = tab_width.unwrap_or_default().max(1);
+                let column = width::display_width(&normalized[line_start..]);
+                let spaces = tab_width - column % tab_width;
+                normalized.extend(core::iter::repeat_n(' ', spaces));
+            }
+            '\n' => {
+                normalized.push('\n');
+                line_start = normalized.len();
+            }
+            '\u{0}'..='\u{1f}' | '\u{7f}' if escape_controls => {
+                normalized.push('^');
+                normalized.push(char::from((character as u8) ^ 0x40));
+            }
+            '\u{80}'..='\u{9f}' if escape_controls => normalized.push(char::REPLACEMENT_CHARACTER),
+            _ => normalized.push(character),
+        }
+    }
+    Cow::Owned(normalized)
+}
+
+/// Renders `fields` as `key=value` after the last of `texts` if they fit in `width` columns,
+/// otherwise as one more text per field, multi-line values indented below their key.
+///
+/// `icon_width` is the width of the prefix of the first text.
+fn push_fields(
+    texts: &mut Vec<Cow<'_, str>>,
+    fields: &[(Cow<'_, str>, Cow<'_, str>)],
+    icon_width: usize,
+    width: usize,
+) {
+    if fields.is_empty() {
+        return;
+    }
+    let inline = fields
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let prefix_width = if texts.len() == 1 { icon_width } else { 2 };
+    if let Some(last) = texts.last_mut() {
+        let separator = usize::from(!last.is_empty());
+        let line_width =
+            prefix_width + width::display_width(last) + separator + width::display_width(&inline);
+        if !inline.contains('\n') && line_width <= width {
+            let last = last.to_mut();
+            if !last.is_empty() {
+                last.push(' ');
+            }
+            last.push_str(&inline);
+            return;
+        }
+    }
+    for (key, value) in fields {
+        let mut lines = value.lines();
+        texts.push(Cow::Owned(format!(
+            "{key}={}",
+            lines.next().unwrap_or_default()
+        )));
+        texts.extend(lines.map(|line| Cow::Owned(format!("  {line}"))));
+    }
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+    use crate::Timestamp;
+    use core::time::Duration;
+
+    #[test]
+    fn test_render_plain_whisper() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("cargo \u{1b}[1minstall\u{1b}[0m murmur")
+            .plain(true);
+        assert_eq!(
+            whisper.render(&RenderOptions::default()),
+            whisper.render(&RenderOptions::plain())
+        );
+        assert!(!whisper.render(&RenderOptions::default())[0].contains('\u{1b}'));
+    }
+
+    #[test]
+    #[cfg(not(feature = "plain"))]
+    fn test_render_commands() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaInfoCircle)
+            .message("to install it, run")
+            .command("cargo \u{1b}[1minstall\u{1b}[0m murmur")
+            .command_link("cargo doc --open", "https://docs.rs/murmur");
+        assert_eq!(
+            whisper.render(&RenderOptions::plain()),
+            [
+                "\u{f05a} to install it, run",
+                "    cargo install murmur",
+                "    cargo doc --open"
+            ]
+        );
+        let options = RenderOptions {
+            hyperlinks: true,
+            ..RenderOptions::default()
+        };
+        let lines = whisper.render(&options);
+        assert_eq!(lines[1], "    cargo install murmur");
+        assert_eq!(
+            lines[2],
+            "    \u{1b}]8;;https://docs.rs/murmur\u{1b}\\cargo doc --open\u{1b}]8;;\u{1b}\\"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "color", not(feature = "plain")))]
+    fn test_render_color_override() {
+        let lines = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("cached")
+            .color("blue")
+            .render(&RenderOptions::default());
+        assert_eq!(
+            lines,
+            ["\u{1b}[34m\u{f00c} \u{1b}[39m\u{1b}[34mcached\u{1b}[39m"]
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\u{1b}[31mred\u{1b}[39m text"), "red text");
+        assert_eq!(strip_ansi("\u{1b}[1;38;5;208mbold\u{1b}[0m"), "bold");
+        assert!(matches!(strip_ansi("plain"), Cow::Borrowed("plain")));
+        assert_eq!(
+            strip_ansi("\u{1b}]8;;https://docs.rs\u{1b}\\docs\u{1b}]8;;\u{1b}\\ and more"),
+            "docs and more"
+        );
+        assert_eq!(strip_ansi("\u{1b}]0;title\u{7}text"), "text");
+    }
+
+    #[test]
+    #[cfg(all(feature = "color", not(feature = "plain")))]
+    fn test_render_pre_colored_message() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("\u{1b}[31mred\u{1b}[39m")
+            .message("green");
+        let lines = whisper.render(&RenderOptions::default());
+        // The prefix takes the color of the icon, the pre-colored message is not wrapped
+        assert!(lines[0].ends_with("\u{f00c} \u{1b}[39m\u{1b}[31mred\u{1b}[39m"));
+        assert!(lines[1].ends_with("green\u{1b}[39m"));
+        assert_eq!(
+            whisper.render(&RenderOptions::plain()),
+            ["\u{f00c} red", "  green"]
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "plain"))]
+    fn test_render_plain() {
+        let lines = Whisper::new()
+            .icon(IconKind::NfFaTimes)
+            .message("first")
+            .message("second")
+            .render(&RenderOptions::plain());
+        assert_eq!(lines, ["\u{f00d} first", "  second"]);
+    }
+
+    #[test]
+    #[cfg(not(feature = "plain"))]
+    fn test_render_without_icon_or_messages() {
+        assert_eq!(Whisper::new().render(&RenderOptions::plain()), [""]);
+        assert_eq!(
+            Whisper::new()
+                .icon(IconKind::UnicodeCheckMark)
+                .render(&RenderOptions::plain()),
+            ["\u{2714}\u{fe0f} "]
+        );
+    }
+
+    #[test]
+    fn test_render_ascii_icons() {
+        let options = RenderOptions {
+            ascii_icons: true,
+            ..RenderOptions::plain()
+        };
+        let lines = Whisper::new()
+            .icon(IconKind::UnicodeWarningSign)
+            .message("careful")
+            .render(&options);
+        assert_eq!(lines, ["! careful"]);
+    }
+
+    #[test]
+    fn test_render_icon_gap() {
+        let options = RenderOptions {
+            ascii_icons: true,
+            ..RenderOptions::plain()
+        };
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("build")
+            .message_with_icon(IconKind::NfFaTimes, "test");
+        assert_eq!(
+            whisper.clone().icon_gap(0).render(&options),
+            ["vbuild", " xtest"]
+        );
+        assert_eq!(
+            whisper.icon_gap(3).render(&options),
+            ["v   build", "    x   test"]
+        );
+        // Without an icon there is no gap
+        assert_eq!(
+            Whisper::new().message("a").icon_gap(3).render(&options),
+            ["a"]
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "plain"))]
+    fn test_render_presentation_aligned() {
+        let options = RenderOptions {
+            presentation: Presentation::Text,
+            icon_width: width::widest_icon(),
+            ..RenderOptions::plain()
+        };
+        let lines = Whisper::new()
+            .icon(IconKind::UnicodeWarningSign)
+            .messages(["careful", "twice"])
+            .render(&options);
+        assert_eq!(lines, ["\u{26a0}\u{fe0e}  careful", "   twice"]);
+    }
+
+    #[test]
+    fn test_render_fields_inline_or_indented() {
+        let whisper = Whisper::new()
+            .message("request served")
+            .field_display("path", &"/health")
+            .field_debug("status", &200);
+        assert_eq!(
+            whisper.render(&RenderOptions::plain()),
+            ["request served path=/health status=200"]
+        );
+        let narrow = RenderOptions {
+            width: 30,
+            ..RenderOptions::plain()
+        };
+        assert_eq!(
+            whisper.render(&narrow),
+            ["request served", "  path=/health", "  status=200"]
+        );
+        assert_eq!(
+            Whisper::new().field_display("only", &1).render(&narrow),
+            ["only=1"]
+        );
+    }
+
+    #[test]
+    fn test_render_message_icons() {
+        let options = RenderOptions {
+            ascii_icons: true,
+            ..RenderOptions::plain()
+        };
+        let lines = Whisper::new()
+            .message_with_icon(IconKind::NfFaCheck, "build")
+            .message_with_icon(IconKind::NfFaTimes, "test")
+            .message("docs")
+            .render(&options);
+        assert_eq!(lines, ["v build", "  x test", "  docs"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "color", not(feature = "plain")))]
+    fn test_render_message_icon_colors() {
+        let lines = Whisper::new()
+            .icon(IconKind::NfFaInfoCircle)
+            .message("report")
+            .message_with_icon(IconKind::NfFaTimes, "failed")
+            .render(&RenderOptions::default());
+        let red = crate::color_map::color_fn("red").unwrap();
+        assert_eq!(lines[1], red("  \u{f00d} ") + &red("failed"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "color", not(feature = "plain")))]
+    fn test_render_stripe() {
+        let whisper = Whisper::new()
+            .messages(["header", "row 1", "row 2", "row 3"])
+            .stripe("bright_black");
+        let gray = crate::color_map::color_fn("bright_black").unwrap();
+        assert_eq!(
+            whisper.render(&RenderOptions::default()),
+            [
+                "header".to_string(),
+                gray("  ") + &gray("row 1"),
+                "  row 2".to_string(),
+                gray("  ") + &gray("row 3"),
+            ]
+        );
+        assert_eq!(
+            whisper.render(&RenderOptions::plain()),
+            ["header", "  row 1", "  row 2", "  row 3"]
+        );
+    }
+
+    #[test]
+    fn test_render_timestamp() {
+        let mut whisper = Whisper::new().messages(["linked", "2 warnings"]);
+        whisper.timestamp = Some(Timestamp::Relative(Duration::from_millis(12_300)));
+        assert_eq!(
+            whisper.render(&RenderOptions::plain()),
+            ["  +12.3s linked", "           2 warnings"]
+        );
+        whisper.timestamp = Some(Timestamp::Wall(Duration::from_secs(3_723)));
+        assert_eq!(
+            whisper.render(&RenderOptions::plain())[0],
+            "01:02:03 linked"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "color", not(feature = "plain")))]
+    fn test_render_correlation_id() {
+        let whisper = Whisper::new()
+            .messages(["deploy requested", "3 services"])
+            .correlation_id("5f0c9a1e");
+        assert_eq!(
+            whisper.render(&RenderOptions::plain()),
+            ["deploy requested", "  3 services [5f0c9a1e]"]
+        );
+        let gray = crate::color_map::color_fn("bright_black").unwrap();
+        assert!(whisper.render(&RenderOptions::default())[1].ends_with(&gray(" [5f0c9a1e]")));
+    }
+
+    #[test]
+    fn test_render_code_and_help() {
+        let mut whisper = Whisper::new()
+            .icon(IconKind::NfFaWarning)
+            .messages(["unused variable `x`", "src/main.rs:3"])
+            .code("W0612")
+            .help("prefix it with an underscore");
+        let options = RenderOptions {
+            ascii_icons: true,
+            ..RenderOptions::plain()
+        };
+        assert_eq!(
+            whisper.render(&options),
+            [
+                "! warning[W0612]: unused variable `x`",
+                "  src/main.rs:3",
+                "  help: prefix it with an underscore"
+            ]
+        );
+        whisper.timestamp = Some(Timestamp::Wall(Duration::from_secs(3_723)));
+        assert_eq!(
+            whisper.render(&options)[2],
+            "           help: prefix it with an underscore"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "color", not(feature = "plain")))]
+    fn test_render_snippet_carets_colored() {
+        let lines = Whisper::new()
+            .icon(IconKind::NfFaTimes)
+            .message("expected `;`")
+            .snippet("\tlet x = 1", 1, 10..11)
+            .tab_width(4)
+            .render(&RenderOptions::default());
+        let red = crate::color_map::color_fn("red").unwrap();
+        assert!(lines[2].ends_with(&format!("{}{}", " ".repeat(12), red("^"))));
+    }
+
+    #[test]
+    fn test_render_into_appends() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaWarning)
+            .messages(["slow", "3 retries"])
+            .help("check the network");
+        let mut out = String::from("before\n");
+        whisper.render_into(&RenderOptions::default(), &mut out);
+        let lines = whisper.render(&RenderOptions::default());
+        assert_eq!(out, format!("before\n{}\n", lines.join("\n")));
+    }
+
+    #[test]
+    fn test_segments_keep_decorations() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("done")
+            .decorate(|_, line| format!("> {line} <"));
+        let options = RenderOptions {
+            ascii_icons: true,
+            ..RenderOptions::plain()
+        };
+        let segments = whisper.segments(&options);
+        let texts = segments[0]
+            .iter()
+            .map(|segment| segment.text.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["> ", "v ", "done", " <"]);
+        assert_eq!(
+            segment::plain_line(&segments[0]),
+            whisper.render(&options)[0]
+        );
+    }
+
+    #[test]
+    fn test_rendered_whisper_decorated_once() {
+        let calls = Arc::new(core::sync::atomic::AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let whisper = Whisper::new()
+            .messages(["first", "second"])
+            .decorate(move |_, line| {
+                counted.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+                format!("| {line}")
+            });
+        let rendered = RenderedWhisper::new(&whisper, &RenderOptions::default());
+        assert_eq!(calls.load(core::sync::atomic::Ordering::Relaxed), 2);
+        assert_eq!(rendered.plain_lines(), ["| first", "|   second"]);
+    }
+
+    #[test]
+    #[cfg(not(feature = "plain"))]
+    fn test_render_without_nerd_fonts() {
+        let options = RenderOptions {
+            nerd_fonts: false,
+            ..RenderOptions::plain()
+        };
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("done")
+            .message_with_icon(IconKind::UnicodeBug, "bug");
+        let expected = Whisper::new()
+            .icon(IconKind::UnicodeCheckMark)
+            .message("done")
+            .message_with_icon(IconKind::UnicodeBug, "bug");
+        assert_eq!(whisper.render(&options), expected.render(&options));
+    }
+
+    #[test]
+    fn test_decorator_sees_the_printed_line() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("done")
+            .decorate(|index, line| format!("{index} len={} {line}", line.len()));
+        let options = RenderOptions::plain();
+        let rendered = RenderedWhisper::new(&whisper, &options);
+        assert_eq!(rendered.plain_lines(), whisper.render(&options));
+        // Sinks writing colors get the same decorated text
+        let colored = rendered.lines_for(AnsiPolicy::Always);
+        assert_eq!(strip_ansi(&colored[0]), whisper.render(&options)[0]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_whispered_decorated_lines_match_render() {
+        /// A sink recording the lines of the whispers starting with `render_tests`.
+        struct Lines(Arc<std::sync::Mutex<Vec<String>>>);
+
+        impl crate::Sink for Lines {
+            fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+                if rendered
+                    .messages
+                    .first()
+                    .is_some_and(|message| message.starts_with("render_tests"))
+                {
+                    self.0.lock().unwrap().extend(rendered.plain_lines());
+                }
+                Ok(())
+            }
+        }
+
+        let lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        crate::add_sink(Lines(Arc::clone(&lines)));
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("render_tests decorated")
+            .decorate(|index, line| format!("{index} len={} {line}", line.len()));
+        whisper.whisper().unwrap();
+        // Tests print to a pipe, colored only if forced by the environment
+        let options = RenderOptions::detect().unwrap();
+        if !options.color {
+            assert_eq!(*lines.lock().unwrap(), whisper.render(&options));
+        }
+    }
+
+    #[test]
+    fn test_links_follow_the_color_decision() {
+        let whisper = Whisper::new().command_link("cargo doc --open", "https://docs.rs/murmur");
+        let options = RenderOptions {
+            hyperlinks: true,
+            ..RenderOptions::plain()
+        };
+        let rendered = RenderedWhisper::new(&whisper, &options);
+        assert!(!rendered.lines_for(AnsiPolicy::Always)[0].contains("\u{1b}]8"));
+    }
+
+    #[test]
+    fn test_decorate_lines() {
+        let whisper = Whisper::new()
+            .messages(["first", "second"])
+            .field_display("attempt", &2)
+            .decorate(|index, line| format!("{index}:{line}"));
+        let options = RenderOptions {
+            width: 10,
+            ..RenderOptions::plain()
+        };
+        let lines = whisper.render(&options);
+        assert_eq!(lines, ["0:first", "1:  second", "2:  attempt=2"]);
+        let mut out = String::new();
+        whisper.render_into(&options, &mut out);
+        assert_eq!(out, "0:first\n1:  second\n2:  attempt=2\n");
+        assert_eq!(whisper.clone(), whisper);
+        assert_ne!(whisper.clone().decorate(|_, line| line.into()), whisper);
+    }
+
+    #[test]
+    fn test_render_rtl() {
+        let lines = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .message("\u{5e9}\u{5dc}\u{5d5}\u{5dd} murmur")
+            .message("\u{5e2}\u{5d5}\u{5d3}")
+            .direction(TextDirection::Rtl)
+            .render(&RenderOptions {
+                ascii_icons: true,
+                ..RenderOptions::plain()
+            });
+        assert_eq!(
+            lines,
+            [
+                "\u{2067}\u{5e9}\u{5dc}\u{5d5}\u{5dd} murmur\u{2069} v",
+                "\u{2067}\u{5e2}\u{5d5}\u{5d3}\u{2069}"
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_to_matches_render() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaWarning)
+            .messages(["first", "second", "third"])
+            .message_with_icon(IconKind::NfFaCheck, "fourth")
+            .field_display("key", &"value")
+            .stripe("bright_black");
+        for options in [RenderOptions::default(), RenderOptions::plain()] {
+            let mut written = Vec::new();
+            whisper.write_to(&mut written, &options).unwrap();
+            let mut expected = whisper.render(&options).join("\n");
+            expected.push('\n');
+            assert_eq!(String::from_utf8(written).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_stream_lines_match_rendered_whisper() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaWarning)
+            .messages(["first", "second"])
+            .field_display("key", &"value")
+            .decorate(|index, line| format!("{index}|{line}"));
+        let (glyph, color) = icon_glyph(&IconKind::NfFaWarning);
+        for options in [RenderOptions::default(), RenderOptions::plain()] {
+            let mut written = Vec::new();
+            stream_lines(&whisper, glyph, color, &options, 4, &mut written).unwrap();
+            let mut rendered = RenderedWhisper::with_glyph(&whisper, glyph, color, &options);
+            crate::indent::shift(&mut rendered, 4);
+            let mut expected = rendered.lines().join("\n");
+            expected.push('\n');
+            assert_eq!(String::from_utf8(written).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a\tbc\td", Some(4), false), "a   bc  d");
+        assert_eq!(
+            normalize("\t\u{65e5}\tx", Some(4), false),
+            "    \u{65e5}  x"
+        );
+        assert_eq!(normalize("ab\ncd\te", Some(4), false), "ab\ncd  e");
+        assert_eq!(normalize("a\tb", None, false), "a\tb");
+        assert_eq!(
+            normalize("done\r\u{1b}[2K\u{7f}\u{85}\tx", None, true),
+            "done^M^[[2K^?\u{fffd}^Ix"
+        );
+        assert_eq!(normalize("a\tb\r", Some(2), true), "a b^M");
+    }
+
+    #[test]
+    fn test_render_normalized_messages() {
+        let lines = Whisper::new()
+            .message("name\tsize")
+            .message("log.txt\t12k\r")
+            .field_display("cmd", &"cat\ta")
+            .tab_width(8)
+            .escape_controls()
+            .render(&RenderOptions::plain());
+        assert_eq!(lines, ["name    size", "  log.txt 12k^M cmd=cat     a"]);
+    }
+
+    #[test]
+    fn test_render_multi_line_field() {
+        let lines = Whisper::new()
+            .message("config")
+            .field_display("toml", &"a = 1\nb = 2")
+            .render(&RenderOptions::plain());
+        assert_eq!(lines, ["config", "  toml=a = 1", "    b = 2"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "color", not(feature = "plain")))]
+    fn test_render_color_follows_theme() {
+        let options = RenderOptions {
+            theme: Theme::light(),
+            ..RenderOptions::default()
+        };
+        let lines = Whisper::new()
+            .icon(IconKind::NfFaInfoCircle)
+            .message("info")
+            .render(&options);
+        let black = crate::color_map::color_fn("black").unwrap();
+        assert_eq!(lines, [black("\u{f05a} ") + &black("info")]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "color", not(feature = "plain")))]
+    fn test_rendered_whisper_keeps_plain_lines() {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaTimes)
+            .message("failed")
+            .level(Level::Warn);
+        let rendered = RenderedWhisper::new(&whisper, &RenderOptions::default());
+        let red = crate::color_map::color_fn("red").unwrap();
+        assert_eq!(rendered.lines(), [red("\u{f00d} ") + &red("failed")]);
+        assert_eq!(rendered.plain_lines(), ["\u{f00d} failed"]);
+        assert_eq!(rendered.level, Level::Warn);
+
+        let rendered = RenderedWhisper::new(&whisper, &RenderOptions::plain());
+        assert_eq!(rendered.lines(), rendered.plain_lines());
+        assert_eq!(
+            rendered.lines_for(AnsiPolicy::Always),
+            [red("\u{f00d} ") + &red("failed")]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "plain")]
+    fn test_plain_feature_renders_ascii_labels() {
+        let lines = Whisper::new()
+            .icon(IconKind::NfFaTimes)
+            .message("failed")
+            .render(&RenderOptions::default());
+        assert_eq!(lines, ["x failed"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_render_matches_icon_map() {
+        for (icon_kind, &(glyph, color)) in crate::icon_map::ICON_MAP.read().unwrap().iter() {
+            assert_eq!(icon_kind.glyph_and_color(), (glyph, color), "{icon_kind:?}");
+        }
+        let rendered = RenderedWhisper::with_glyph(
+            &Whisper::new().message("message"),
+            "",
+            None,
+            &RenderOptions::default(),
+        );
+        assert_eq!(rendered.lines(), ["message"]);
+    }
+}
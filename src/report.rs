@@ -0,0 +1,300 @@
+//! The `report` module gathers whispers by section, to print them together at the end of a run.
+//!
+//! Subsystems append their whispers to a `Report` under a named section. `Report::whisper()` prints
+//! each section as an underlined header followed by its whispers, indented, and a footer with the
+//! totals of the report, in a single batch.
+//!
+//! ```
+//! use murmur::{IconKind, Report, Whisper};
+//!
+//! let mut report = Report::new();
+//! report.push("lint", Whisper::new().icon(IconKind::NfFaWarning).message("src/main.rs: unused import"));
+//! report.push("test", Whisper::new().icon(IconKind::NfFaCheck).message("42 tests passed"));
+//! report.push("lint", Whisper::new().icon(IconKind::NfFaTimes).message("src/lib.rs: missing docs"));
+//! report.whisper().unwrap();
+//! ```
+//!
+//! A `Report` can also be shown on the alternate screen of the terminal, like `less` or `git log`
+//! do, waiting for a key before restoring the terminal, so the summary does not pollute the
+//! scrollback. When stdout is not a terminal, or the output is deterministic, the report is
+//! whispered instead.
+//!
+//! ```no_run
+//! use murmur::{IconKind, Report, Whisper};
+//!
+//! Report::new()
+//!     .append(Whisper::new().icon(IconKind::NfFaCheck).message("42 tests passed"))
+//!     .section("skipped", Whisper::new().icon(IconKind::NfFaWarning).message("3 tests skipped"))
+//!     .show()
+//!     .unwrap();
+//! ```
+//!
+use std::io::{self, StdoutLock, Write};
+
+use crate::{
+    batch, indent, sink, strings, sys, term, width, IconKind, Level, RenderedWhisper, Segment,
+    Style, Whisper, WhisperError, INDENT_WIDTH,
+};
+
+/// Switches to the alternate screen, then moves the cursor home and clears the screen.
+const ENTER: &str = "\x1b[?1049h\x1b[H\x1b[2J";
+
+/// Switches back to the main screen, restoring its content and cursor.
+const LEAVE: &[u8] = b"\x1b[?1049l";
+
+/// `Report` is a set of whispers, grouped by section, printed together.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Report {
+    /// The whispers of the report with the name of their section, in order.
+    entries: Vec<(Option<String>, Whisper)>,
+}
+
+impl Report {
+    /// Creates an empty `Report`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds `whisper` at the end of the report, outside of any section.
+    ///
+    /// Whispers outside of any section are printed first, without header nor indent.
+    #[must_use]
+    pub fn append(mut self, whisper: Whisper) -> Self {
+        self.entries.push((None, whisper));
+        self
+    }
+
+    /// Adds `whisper` at the end of the section `name`, see `push`.
+    #[must_use]
+    pub fn section(mut self, name: &str, whisper: Whisper) -> Self {
+        self.push(name, whisper);
+        self
+    }
+
+    /// Appends `whisper` to the section `name`, created after the existing sections if needed.
+    pub fn push(&mut self, name: &str, whisper: Whisper) {
+        self.entries.push((Some(String::from(name)), whisper));
+    }
+
+    /// Prints the report: every section with its header and its whispers, then the totals footer.
+    ///
+    /// The whispers are rendered first and emitted as a single batch, see `whisper_all`. They are
+    /// not rate limited, and the footer is counted in the tally as an informational whisper.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error rendering a whisper or emitting the report.
+    pub fn whisper(&self) -> Result<(), WhisperError> {
+        batch::emit(&self.render()?)
+    }
+
+    /// Shows the report on the alternate screen until a key is pressed, then restores the terminal.
+    ///
+    /// No whisper is emitted while the report is shown. Reports taller than the terminal scroll on
+    /// the alternate screen, long output is better paged, see `Whisper::page()`. Without a terminal,
+    /// the report is printed with `whisper()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if a lock is poisoned, and `WhisperError::Print` if there is an
+    /// error while printing the report.
+    pub fn show(&self) -> Result<(), WhisperError> {
+        if !term::capabilities().stdout_tty || term::deterministic() {
+            return self.whisper();
+        }
+        let lines = self
+            .render()?
+            .into_iter()
+            .flat_map(|rendered| rendered.lines())
+            .collect();
+        let lines = screen(lines, strings::strings().report_prompt);
+
+        let _output = sink::lock_output()?;
+        let mut screen = AlternateScreen(io::stdout().lock());
+        sink::write_lines(&mut screen.0, &lines).map_err(|_| WhisperError::Print)?;
+        sys::read_key();
+        drop(screen);
+        Ok(())
+    }
+
+    /// Returns the sections of the report in the order they were first added, whispers outside of
+    /// any section first.
+    fn sections(&self) -> Vec<(Option<&str>, Vec<&Whisper>)> {
+        let mut sections: Vec<(Option<&str>, Vec<&Whisper>)> = vec![(None, Vec::new())];
+        for (name, whisper) in &self.entries {
+            let name = name.as_deref();
+            match sections.iter_mut().find(|(section, _)| *section == name) {
+                Some((_, whispers)) => whispers.push(whisper),
+                None => sections.push((name, vec![whisper])),
+            }
+        }
+        sections
+    }
+
+    /// Renders the sections of the report and its totals footer.
+    fn render(&self) -> Result<Vec<RenderedWhisper>, WhisperError> {
+        let underline = if term::decision().ascii_icons {
+            "-"
+        } else {
+            "\u{2500}"
+        };
+        let mut rendered = Vec::new();
+        for (name, whispers) in self.sections() {
+            let mut section = whispers
+                .into_iter()
+                .map(Whisper::freeze)
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(name) = name {
+                for rendered in &mut section {
+                    indent::shift(rendered, INDENT_WIDTH);
+                }
+                if let Some(first) = section.first_mut() {
+                    let header = header(name, underline);
+                    let segments = header.map(|line| vec![Segment::new(line, Style::default())]);
+                    first.segments.splice(0..0, segments);
+                }
+            }
+            rendered.extend(section);
+        }
+        rendered.push(self.totals().freeze()?);
+        Ok(rendered)
+    }
+
+    /// Returns the totals footer of the report, marked by the most severe level of its whispers.
+    fn totals(&self) -> Whisper {
+        let count = |level| {
+            self.entries
+                .iter()
+                .filter(|(_, whisper)| whisper.severity() == level)
+                .count()
+        };
+        let (errors, warnings) = (count(Level::Error), count(Level::Warn));
+        let icon = match (errors, warnings) {
+            (0, 0) => IconKind::NfFaCheck,
+            (0, _) => IconKind::NfFaWarning,
+            _ => IconKind::NfFaTimes,
+        };
+        Whisper::new()
+            .icon(icon)
+            .level(Level::Info)
+            .message((strings::strings().report_totals)(
+                self.entries.len(),
+                errors,
+                warnings,
+            ))
+    }
+}
+
+impl FromIterator<Whisper> for Report {
+    fn from_iter<I: IntoIterator<Item = Whisper>>(whispers: I) -> Self {
+        Self {
+            entries: whispers
+                .into_iter()
+                .map(|whisper| (None, whisper))
+                .collect(),
+        }
+    }
+}
+
+/// `AlternateScreen` holds stdout while a report is shown, and leaves the alternate screen on drop,
+/// even if printing the report fails.
+struct AlternateScreen(StdoutLock<'static>);
+
+impl Drop for AlternateScreen {
+    fn drop(&mut self) {
+        let _ = self.0.write_all(LEAVE);
+        let _ = self.0.flush();
+    }
+}
+
+/// Returns the header of the section `name`: the name, underlined with `underline`.
+fn header(name: &str, underline: &str) -> [String; 2] {
+    [
+        String::from(name),
+        underline.repeat(width::display_width(name)),
+    ]
+}
+
+/// Returns the lines printed for a report of `lines`: entering the alternate screen, the lines, a
+/// blank line and `prompt`.
+fn screen(mut lines: Vec<String>, prompt: &str) -> Vec<String> {
+    match lines.first_mut() {
+        Some(first) => first.insert_str(0, ENTER),
+        None => lines.push(String::from(ENTER)),
+    }
+    lines.push(String::new());
+    lines.push(String::from(prompt));
+    lines
+}
+
+#[cfg(test)]
+mod report_tests {
+    use super::*;
+
+    #[test]
+    fn test_screen() {
+        let lines = screen(vec![String::from("a"), String::from("b")], "press");
+        assert_eq!(
+            lines,
+            [
+                format!("{ENTER}a"),
+                "b".into(),
+                String::new(),
+                "press".into()
+            ]
+        );
+        assert_eq!(screen(Vec::new(), "press"), [ENTER, "", "press"]);
+    }
+
+    #[test]
+    fn test_report_collects_whispers() {
+        let report = (0..3)
+            .map(|index| Whisper::new().message(format!("row {index}")))
+            .collect::<Report>();
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(Report::new().append(Whisper::new()).entries.len(), 1);
+    }
+
+    #[test]
+    fn test_sections() {
+        let mut report = Report::new()
+            .section("lint", Whisper::new().message("unused import"))
+            .append(Whisper::new().message("checked 3 crates"));
+        report.push("test", Whisper::new().message("42 passed"));
+        report.push("lint", Whisper::new().message("missing docs"));
+        let sections = report
+            .sections()
+            .into_iter()
+            .map(|(name, whispers)| (name, whispers.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(sections, [(None, 1), (Some("lint"), 2), (Some("test"), 1)]);
+    }
+
+    #[test]
+    fn test_header_and_indent() {
+        assert_eq!(header("lint", "-"), ["lint", "----"]);
+        let mut rendered = RenderedWhisper::new(
+            &Whisper::new().messages(["first", "second"]),
+            &crate::RenderOptions::plain(),
+        );
+        indent::shift(&mut rendered, INDENT_WIDTH);
+        assert_eq!(rendered.plain_lines(), ["  first", "    second"]);
+    }
+
+    #[test]
+    fn test_totals() {
+        let report = Report::new()
+            .section("lint", Whisper::new().icon(IconKind::NfFaWarning))
+            .section("lint", Whisper::new().icon(IconKind::NfFaTimes))
+            .section("test", Whisper::new().icon(IconKind::NfFaCheck));
+        let totals = report.totals();
+        assert_eq!(totals.icon_kind, Some(IconKind::NfFaTimes));
+        assert_eq!(totals.severity(), Level::Info);
+        assert_eq!(totals.messages, ["3 whispers: 1 error, 1 warning"]);
+        let totals = Report::new().totals();
+        assert_eq!(totals.icon_kind, Some(IconKind::NfFaCheck));
+    }
+}
@@ -0,0 +1,185 @@
+//! The `segment` module is the styled intermediate representation whispers are rendered to.
+//!
+//! A rendered line is a list of `Segment`s, each a text with its `Style`, instead of a string with
+//! the ANSI escape sequences of its colors baked in. Every output serializes the segments its own
+//! way: `ansi_line` for terminals, `plain_line` for logs, or its own markup for a TUI widget or an
+//! HTML page, and `line_width` measures them without parsing escape sequences.
+//!
+//! ```
+//! use murmur::{IconKind, RenderOptions, Whisper};
+//!
+//! let lines = Whisper::new()
+//!     .icon(IconKind::NfFaTimes)
+//!     .message("failed")
+//!     .segments(&RenderOptions::default());
+//! assert_eq!(lines[0][1].text, "failed");
+//! assert_eq!(murmur::line_width(&lines[0]), 8);
+//! ```
+//!
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::fmt;
+
+use crate::render::strip_ansi;
+use crate::{width, Color};
+
+/// `Style` is how the text of a `Segment` is printed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Style {
+    /// The color of the text, `None` for the default color of the output.
+    pub color: Option<Color>,
+}
+
+impl Style {
+    /// Returns the style printing text in `color`, the default color for `None`.
+    #[must_use]
+    pub const fn color(color: Option<Color>) -> Self {
+        Self { color }
+    }
+}
+
+/// `Segment` is a run of text of a rendered line printed in a single `Style`.
+///
+/// The text of a message colored by the caller keeps its escape sequences, `plain()` strips them.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Segment<'a> {
+    /// The text of the segment.
+    pub text: Cow<'a, str>,
+    /// The style of the text.
+    pub style: Style,
+    /// The target of the text, printed as an `OSC 8` hyperlink, see `RenderOptions::hyperlinks`.
+    pub link: Option<Cow<'a, str>>,
+}
+
+impl<'a> Segment<'a> {
+    /// Creates a `Segment` of `text` in `style`, without a link.
+    #[must_use]
+    pub fn new(text: impl Into<Cow<'a, str>>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+            link: None,
+        }
+    }
+
+    /// Returns the segment without its borrows, to keep it beyond the whisper it was rendered from.
+    #[must_use]
+    pub fn into_owned(self) -> Segment<'static> {
+        Segment {
+            text: Cow::Owned(self.text.into_owned()),
+            style: self.style,
+            link: self.link.map(|link| Cow::Owned(link.into_owned())),
+        }
+    }
+
+    /// Returns the text of the segment without escape sequences.
+    #[must_use]
+    pub fn plain(&self) -> Cow<'_, str> {
+        strip_ansi(&self.text)
+    }
+
+    /// Returns the number of columns the segment takes in a terminal.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        width::display_width(&self.plain())
+    }
+
+    /// Writes the segment to `out` with the escape sequences of its style and link.
+    ///
+    /// The escape sequences of the color are written around the text, instead of coloring a copy
+    /// of it, unless the color function of the color does more than wrapping its text.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `out`.
+    pub fn write_ansi<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        let (open, close) = self.link.as_ref().map_or((["", "", ""], ""), |link| {
+            (
+                ["\u{1b}]8;;", link.as_ref(), "\u{1b}\\"],
+                "\u{1b}]8;;\u{1b}\\",
+            )
+        });
+        let parts = [open[0], open[1], open[2], self.text.as_ref(), close];
+        let Some((color, wrapped)) = self
+            .style
+            .color
+            .and_then(|color| color.paint("\u{0}").map(|wrapped| (color, wrapped)))
+        else {
+            return parts.iter().try_for_each(|part| out.write_str(part));
+        };
+        match wrapped.split_once('\u{0}') {
+            Some((start, end)) => {
+                out.write_str(start)?;
+                parts.iter().try_for_each(|part| out.write_str(part))?;
+                out.write_str(end)
+            }
+            None => out.write_str(&color.paint(&parts.concat()).unwrap_or_default()),
+        }
+    }
+}
+
+/// Returns `segments` serialized for a terminal, with the escape sequences of their styles.
+#[must_use]
+pub fn ansi_line(segments: &[Segment<'_>]) -> String {
+    let mut line = String::new();
+    // Writing to a `String` cannot fail
+    let _ = write_ansi(&mut line, segments);
+    line
+}
+
+/// Returns the text of `segments` without any escape sequence, e.g. for a log file.
+#[must_use]
+pub fn plain_line(segments: &[Segment<'_>]) -> String {
+    segments.iter().map(Segment::plain).collect()
+}
+
+/// Returns `segments` serialized for an output, with the escape sequences of their styles if `ansi`
+/// is set, see `ansi_line` and `plain_line`.
+#[must_use]
+pub fn serialize_line(segments: &[Segment<'_>], ansi: bool) -> String {
+    if ansi {
+        ansi_line(segments)
+    } else {
+        plain_line(segments)
+    }
+}
+
+/// Returns the number of columns `segments` take in a terminal.
+#[must_use]
+pub fn line_width(segments: &[Segment<'_>]) -> usize {
+    width::display_width(&plain_line(segments))
+}
+
+/// Writes `segments` to `out` with the escape sequences of their styles, see `Segment::write_ansi`.
+///
+/// # Errors
+///
+/// Returns the error of `out`.
+pub fn write_ansi<W: fmt::Write>(out: &mut W, segments: &[Segment<'_>]) -> fmt::Result {
+    segments
+        .iter()
+        .try_for_each(|segment| segment.write_ansi(out))
+}
+
+#[cfg(test)]
+mod segment_tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_serialize_segments() {
+        let mut command = Segment::new("cargo test", Style::default());
+        command.link = Some(Cow::Borrowed("https://doc.rust-lang.org"));
+        let line = vec![
+            Segment::new("\u{1b}[1mrun\u{1b}[0m ", Style::default()),
+            command,
+        ];
+        assert_eq!(plain_line(&line), "run cargo test");
+        assert_eq!(line_width(&line), 14);
+        assert_eq!(
+            ansi_line(&line),
+            "\u{1b}[1mrun\u{1b}[0m \u{1b}]8;;https://doc.rust-lang.org\u{1b}\\cargo test\u{1b}]8;;\u{1b}\\"
+        );
+        assert_eq!(line[1].clone().into_owned(), line[1]);
+    }
+}
@@ -0,0 +1,191 @@
+//! The `session` module keeps the step prefixes of a long run aligned without knowing them upfront.
+//!
+//! Tools print whispers prefixed by a label or a step, e.g. `[fetch]` or `crate-name`, whose width
+//! is only known once all of them were seen. A `Session` remembers the widest prefix seen so far and
+//! pads every following prefix to it, so the icons and messages line up from then on.
+//!
+//! ```
+//! use murmur::{IconKind, RenderOptions, Session, Whisper};
+//!
+//! let mut session = Session::new();
+//! let options = RenderOptions::plain();
+//! let check = Whisper::new().icon(IconKind::NfFaCheck);
+//! # #[cfg(not(feature = "plain"))]
+//! assert_eq!(session.render("[db]", &check.with_messages(["migrated"]), &options), ["[db] \u{f00c} migrated"]);
+//! # #[cfg(not(feature = "plain"))]
+//! assert_eq!(session.render("[frontend]", &check.with_messages(["built"]), &options), ["[frontend] \u{f00c} built"]);
+//! # #[cfg(not(feature = "plain"))]
+//! assert_eq!(session.render("[db]", &check.with_messages(["seeded"]), &options), ["[db]       \u{f00c} seeded"]);
+//! ```
+//!
+//! With `redraw(true)`, `Session::whisper` also moves the cursor up and prints the whispers of the
+//! session again when a wider prefix comes in, so earlier lines line up too.
+//!
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::{cursor, osc, WhisperError};
+use crate::{width, RenderOptions, Whisper};
+
+/// `Session` pads the prefixes of whispers to the widest one seen, see the `session` module.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    /// The width of the widest prefix seen, in columns.
+    prefix_width: usize,
+    /// Whether the whispers of the session are printed again when a wider prefix comes in.
+    redraw: bool,
+    /// The prefixes and whispers printed by `whisper`, with their number of lines, to redraw them.
+    #[cfg(feature = "std")]
+    printed: Vec<(String, Whisper, usize)>,
+}
+
+impl Session {
+    /// Creates a `Session` that has seen no prefix yet.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prints the whispers of the session again, aligned, when a wider prefix comes in, `false` by
+    /// default.
+    ///
+    /// The previous lines are redrawn by moving the cursor up, so only when stdout is a terminal,
+    /// nothing else was printed since the session started, and its lines fit on the screen.
+    #[must_use]
+    pub const fn redraw(mut self, redraw: bool) -> Self {
+        self.redraw = redraw;
+        self
+    }
+
+    /// Returns the width of the widest prefix seen, in columns.
+    #[must_use]
+    pub const fn prefix_width(&self) -> usize {
+        self.prefix_width
+    }
+
+    /// Renders `whisper` after `prefix` padded to the widest prefix seen, including this one.
+    ///
+    /// The following lines are indented by the width of the prefix column.
+    pub fn render(
+        &mut self,
+        prefix: &str,
+        whisper: &Whisper,
+        options: &RenderOptions,
+    ) -> Vec<String> {
+        self.prefix_width = self.prefix_width.max(width::display_width(prefix));
+        prefixed(prefix, self.prefix_width, whisper).render(options)
+    }
+
+    /// Whispers `whisper` like `Whisper::whisper()`, after `prefix` padded to the widest prefix seen.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `Whisper::whisper()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{IconKind, Session, Whisper};
+    ///
+    /// let mut session = Session::new().redraw(true);
+    /// for (step, message) in [("fetch", "3 crates"), ("compile", "murmur"), ("fetch", "done")] {
+    ///     session.whisper(step, &Whisper::new().icon(IconKind::NfFaCheck).message(message)).unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn whisper(&mut self, prefix: &str, whisper: &Whisper) -> Result<(), WhisperError> {
+        let previous_width = self.prefix_width;
+        self.prefix_width = previous_width.max(width::display_width(prefix));
+        if self.redraw && self.prefix_width > previous_width && osc::enabled() {
+            self.redraw_printed()?;
+        }
+        let prefixed = prefixed(prefix, self.prefix_width, whisper);
+        if self.redraw {
+            let lines = prefixed.freeze()?.segments.len();
+            self.printed
+                .push((String::from(prefix), whisper.clone(), lines));
+        }
+        prefixed.whisper()
+    }
+
+    /// Clears the lines printed by the session and prints them again with the current prefix width.
+    ///
+    /// The lines are only printed to the terminal, the sinks already received the whispers.
+    #[cfg(feature = "std")]
+    fn redraw_printed(&mut self) -> Result<(), WhisperError> {
+        let count = self
+            .printed
+            .iter()
+            .map(|(_, _, lines)| lines)
+            .sum::<usize>();
+        if count == 0 {
+            return Ok(());
+        }
+        let mut text = cursor::clear_lines(count + 1);
+        for (prefix, whisper, lines) in &mut self.printed {
+            let rendered = prefixed(prefix, self.prefix_width, whisper).freeze()?;
+            *lines = rendered.segments.len();
+            for line in &rendered.lines() {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        osc::write(&text)
+    }
+}
+
+/// Returns `whisper` with its first line after `prefix` padded to `prefix_width` columns, and the
+/// other lines indented as wide, after its own decorator if any.
+fn prefixed(prefix: &str, prefix_width: usize, whisper: &Whisper) -> Whisper {
+    let padding = prefix_width.saturating_sub(width::display_width(prefix));
+    let column = format!("{prefix}{} ", " ".repeat(padding));
+    let blank = " ".repeat(prefix_width + 1);
+    let decorator = whisper.decorator.clone();
+    whisper.clone().decorate(move |index, line| {
+        let line = decorator.as_ref().map_or_else(
+            || String::from(line),
+            |decorator| decorator.apply(index, line),
+        );
+        let column = if index == 0 { &column } else { &blank };
+        format!("{column}{line}")
+    })
+}
+
+#[cfg(test)]
+mod session_tests {
+    use super::*;
+    use crate::IconKind;
+
+    #[test]
+    fn test_prefixes_pad_to_the_widest_seen() {
+        let mut session = Session::new();
+        let options = RenderOptions {
+            ascii_icons: true,
+            ..RenderOptions::plain()
+        };
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaCheck)
+            .messages(["a", "b"]);
+        assert_eq!(
+            session.render("wide", &whisper, &options),
+            ["wide v a", "       b"]
+        );
+        assert_eq!(
+            session.render("x", &whisper, &options),
+            ["x    v a", "       b"]
+        );
+        assert_eq!(session.prefix_width(), 4);
+    }
+
+    #[test]
+    fn test_prefix_keeps_the_decorator() {
+        let whisper = Whisper::new()
+            .message("a")
+            .decorate(|index, line| format!("{index}:{line}"));
+        assert_eq!(
+            prefixed("p", 2, &whisper).render(&RenderOptions::plain()),
+            ["p  0:a"]
+        );
+    }
+}
@@ -0,0 +1,1028 @@
+//! The `sink` module decides where whispers are written to.
+//!
+//! `whisper()` renders a whisper once into a `RenderedWhisper` and emits it to every registered
+//! `Sink`. By default the only sink is `StdoutSink`, more sinks are added with `add_sink`, e.g. to
+//! print to the terminal, append to a log file and write JSON lines at the same time.
+//!
+//! ```no_run
+//! use murmur::{FileSink, JsonSink, Whisper};
+//!
+//! murmur::add_sink(FileSink::new("murmur.log").unwrap());
+//! murmur::add_sink(JsonSink::new(std::io::stderr()));
+//!
+//! // Printed to stdout, appended to murmur.log and written as JSON to stderr
+//! Whisper::new().message("fan out").whisper().unwrap();
+//! ```
+//!
+//! `add_sink_with_level` registers a sink with a minimum severity, so the choice of what reaches
+//! each output is made once where the sinks are set up, not at every call site:
+//!
+//! ```no_run
+//! use murmur::{FileSink, IconKind, Level, Whisper};
+//!
+//! // The terminal shows everything, the log file only warnings and errors
+//! murmur::add_sink_with_level(FileSink::new("murmur.log").unwrap(), Level::Warn);
+//!
+//! Whisper::new().message("only printed").whisper().unwrap();
+//! Whisper::new().icon(IconKind::NfFaWarning).message("printed and logged").whisper().unwrap();
+//! ```
+//!
+//! ## Ordering
+//!
+//! Whispers are emitted atomically: all the lines of a whisper are written with a single write,
+//! and a whisper reaches every sink before the next one, so whispers from different threads never
+//! interleave and all sinks receive them in the same order. Sinks must therefore not whisper themselves.
+//! A batch of `whisper_all` is emitted as a whole, no other whisper comes in between.
+//!
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, MutexGuard, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    cursor, delimiter, footer, formatter, osc, pager, queue, term, throttle, AnsiPolicy, Level,
+    RenderedWhisper, WhisperError,
+};
+
+/// `Sink` is an output whispers are emitted to.
+pub trait Sink: Send + Sync {
+    /// Emits `rendered`, sinks filtering on `RenderedWhisper::level` return `Ok(())` for ignored whispers.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WhisperError` if the whisper could not be emitted.
+    fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError>;
+
+    /// Emits a batch of whispers, in order, see `whisper_all`.
+    ///
+    /// Sinks writing to a buffered output override it to write the batch at once. By default every
+    /// whisper is emitted even if one fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of `emit`.
+    fn emit_batch(&self, batch: &[RenderedWhisper]) -> Result<(), WhisperError> {
+        batch
+            .iter()
+            .map(|rendered| self.emit(rendered))
+            .fold(Ok(()), Result::and)
+    }
+
+    /// Returns whether the sink can take a whisper without blocking, `Readiness::Ready` by default.
+    ///
+    /// Sinks with a bounded queue, e.g. `BoundedSink`, return `Readiness::Busy` while it is full.
+    fn poll_ready(&self) -> Readiness {
+        Readiness::Ready
+    }
+
+    /// Emits `rendered` if the sink is ready, see `poll_ready`, otherwise returns
+    /// `Readiness::Busy` without emitting it, so the caller decides whether to wait.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `emit`.
+    fn try_emit(&self, rendered: &RenderedWhisper) -> Result<Readiness, WhisperError> {
+        match self.poll_ready() {
+            Readiness::Ready => self.emit(rendered).map(|()| Readiness::Ready),
+            Readiness::Busy => Ok(Readiness::Busy),
+        }
+    }
+}
+
+/// `Readiness` is whether a `Sink` can take a whisper without blocking, see `Sink::poll_ready`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Readiness {
+    /// The sink takes a whisper without blocking.
+    Ready,
+    /// The sink is busy, emitting a whisper would block or drop it.
+    Busy,
+}
+
+/// Held while a whisper is emitted, so whispers reach the sinks one at a time.
+static OUTPUT: Mutex<()> = Mutex::new(());
+
+/// The sinks every whisper is emitted to, `StdoutSink` until they are changed.
+static SINKS: LazyLock<RwLock<Vec<Box<dyn Sink>>>> =
+    LazyLock::new(|| RwLock::new(vec![Box::new(StdoutSink)]));
+
+/// Set while `StdoutSink` is the only sink, as it is until the sinks are changed.
+static DEFAULT_SINKS: AtomicBool = AtomicBool::new(true);
+
+/// Adds `sink` to the registered sinks, every following whisper is also emitted to it.
+///
+/// # Panics
+///
+/// Panics if the sink lock is poisoned.
+pub fn add_sink<S: Sink + 'static>(sink: S) {
+    SINKS.write().unwrap().push(Box::new(sink));
+    DEFAULT_SINKS.store(false, Ordering::Relaxed);
+}
+
+/// Adds `sink` to the registered sinks, emitting only the whispers at or above `min_level` to it.
+///
+/// # Panics
+///
+/// Panics if the sink lock is poisoned.
+pub fn add_sink_with_level<S: Sink + 'static>(sink: S, min_level: Level) {
+    add_sink(MinLevel {
+        sink: Box::new(sink),
+        min_level,
+    });
+}
+
+/// Replaces all registered sinks with `sinks`, e.g. to stop printing to stdout.
+///
+/// # Panics
+///
+/// Panics if the sink lock is poisoned.
+///
+/// # Examples
+/// ```
+/// use murmur::{JsonSink, Sink, Whisper};
+///
+/// let sinks: Vec<Box<dyn Sink>> = vec![Box::new(JsonSink::new(std::io::stdout()))];
+/// murmur::set_sinks(sinks);
+/// Whisper::new().message("only JSON").whisper().unwrap();
+/// murmur::clear_sinks();
+/// ```
+pub fn set_sinks(sinks: Vec<Box<dyn Sink>>) {
+    *SINKS.write().unwrap() = sinks;
+    DEFAULT_SINKS.store(false, Ordering::Relaxed);
+}
+
+/// Removes every added sink, leaving only the default `StdoutSink`.
+///
+/// # Panics
+///
+/// Panics if the sink lock is poisoned.
+pub fn clear_sinks() {
+    *SINKS.write().unwrap() = vec![Box::new(StdoutSink)];
+    DEFAULT_SINKS.store(true, Ordering::Relaxed);
+}
+
+/// Locks the output, so no whisper is emitted until the returned guard is dropped, see `Report`.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the output lock is poisoned.
+pub fn lock_output() -> Result<MutexGuard<'static, ()>, WhisperError> {
+    OUTPUT.lock().map_err(|_| WhisperError::Lock)
+}
+
+/// Returns whether whispers are written to stdout while they are rendered, instead of being
+/// rendered in memory first: `StdoutSink` is the only sink, stdout is not a terminal, so nothing
+/// is paged, throttled or drawn below, and whispers are neither queued, formatted nor delimited.
+pub fn streams() -> bool {
+    DEFAULT_SINKS.load(Ordering::Relaxed)
+        && !term::capabilities().stdout_tty
+        && !footer::active()
+        && !delimiter::delimiters()
+        && formatter::is_text()
+        && !queue::queueing()
+}
+
+/// Writes a whisper to stdout with `write`, while holding the output lock, see `streams`.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the output lock is poisoned, or `WhisperError::Print` if there
+/// is an error while printing the whisper.
+pub fn stream(write: impl FnOnce(&mut dyn Write) -> io::Result<()>) -> Result<(), WhisperError> {
+    let _output = lock_output()?;
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    let result = write(&mut stdout).and_then(|()| stdout.flush());
+    if result.is_err() {
+        // Reset the colors a whisper failing mid-way may have left on
+        let (mut stdout, _) = stdout.into_parts();
+        let _ = stdout.write_all(RESET);
+    }
+    result.map_err(|_| WhisperError::Print)
+}
+
+/// Emits `rendered` to every registered sink, while holding the output lock, or queues it, see
+/// `queue_whispers`.
+///
+/// Every sink is tried even if one fails, the first error is returned.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the output or sink lock is poisoned, or the error of a failing sink.
+pub fn emit(rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+    if queue::push(core::slice::from_ref(rendered)) {
+        return Ok(());
+    }
+    let _output = lock_output()?;
+    emit_all(&SINKS.read().map_err(|_| WhisperError::Lock)?, rendered)
+}
+
+/// Emits `batch` to every registered sink, while holding the output lock for the whole batch, or
+/// queues it, see `queue_whispers`.
+///
+/// Every sink is tried even if one fails, the first error is returned.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the output or sink lock is poisoned, or the error of a failing sink.
+pub fn emit_batch(batch: &[RenderedWhisper]) -> Result<(), WhisperError> {
+    if queue::push(batch) {
+        return Ok(());
+    }
+    let _output = lock_output()?;
+    SINKS
+        .read()
+        .map_err(|_| WhisperError::Lock)?
+        .iter()
+        .map(|sink| sink.emit_batch(batch))
+        .fold(Ok(()), Result::and)
+}
+
+/// Emits `rendered` to every sink of `sinks`, returning the first error.
+fn emit_all(sinks: &[Box<dyn Sink>], rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+    sinks
+        .iter()
+        .map(|sink| sink.emit(rendered))
+        .fold(Ok(()), Result::and)
+}
+
+/// `MinLevel` emits only the whispers at or above `min_level` to `sink`, see `add_sink_with_level`.
+struct MinLevel {
+    /// The sink the whispers are emitted to.
+    sink: Box<dyn Sink>,
+    /// The lowest level emitted.
+    min_level: Level,
+}
+
+impl Sink for MinLevel {
+    fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+        if rendered.level < self.min_level {
+            return Ok(());
+        }
+        self.sink.emit(rendered)
+    }
+
+    fn emit_batch(&self, batch: &[RenderedWhisper]) -> Result<(), WhisperError> {
+        if batch
+            .iter()
+            .all(|rendered| rendered.level >= self.min_level)
+        {
+            return self.sink.emit_batch(batch);
+        }
+        let batch = batch
+            .iter()
+            .filter(|rendered| rendered.level >= self.min_level)
+            .cloned()
+            .collect::<Vec<_>>();
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.sink.emit_batch(&batch)
+    }
+
+    fn poll_ready(&self) -> Readiness {
+        self.sink.poll_ready()
+    }
+}
+
+/// `StdoutSink` prints the rendered lines to stdout, it is the default sink.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+impl Sink for StdoutSink {
+    /// Prints every line of `rendered` to stdout, through a pager if the whisper is paged, and
+    /// above the footer if there is one. Informational whispers are skipped beyond the refresh rate,
+    /// see `set_refresh_rate`. Whispers are printed in the selected format, see `set_format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Print` if there is an error while printing the lines.
+    fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+        if !throttle::admit(rendered) {
+            return Ok(());
+        }
+        if let Some(lines) = formatter::apply(rendered) {
+            return Self::print(&Self::delimit(rendered, Cow::Owned(lines)));
+        }
+        // Print the lines directly if the pager is disabled or fails to start
+        if pager::should_page(rendered) && pager::page(&rendered.lines()).is_ok() {
+            return Ok(());
+        }
+        Self::print(&Self::lines(rendered))
+    }
+
+    /// Prints the lines of every whisper of `batch` to stdout with a single write and flush.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Print` if there is an error while printing the lines.
+    fn emit_batch(&self, batch: &[RenderedWhisper]) -> Result<(), WhisperError> {
+        let lines = batch.iter().flat_map(Self::lines).collect::<Vec<_>>();
+        Self::print(&lines)
+    }
+}
+
+impl StdoutSink {
+    /// Prints `lines` to stdout, above the footer if there is one, see `footer`.
+    fn print(lines: &[String]) -> Result<(), WhisperError> {
+        let mut stdout = io::stdout().lock();
+        if !footer::active() {
+            return write_lines(&mut stdout, lines).map_err(|_| WhisperError::Print);
+        }
+        stdout
+            .write_all(cursor::CLEAR_LINE.as_bytes())
+            .and_then(|()| write_lines(&mut stdout, lines))
+            .and_then(|()| footer::redraw(&mut stdout))
+            .map_err(|_| WhisperError::Print)
+    }
+
+    /// Returns the lines printed for `rendered` in the selected format, with the `text` format the
+    /// first one setting the terminal title if asked by `Whisper::set_title()`.
+    fn lines(rendered: &RenderedWhisper) -> Vec<String> {
+        if let Some(lines) = formatter::apply(rendered) {
+            return Self::delimit(rendered, Cow::Owned(lines)).into_owned();
+        }
+        let mut lines = rendered.lines();
+        if !rendered.set_title || !osc::enabled() {
+            return Self::delimit(rendered, Cow::Owned(lines)).into_owned();
+        }
+        let title = rendered.messages.first().map_or("", String::as_str);
+        if let Some(first) = lines.first_mut() {
+            first.insert_str(0, &osc::title_sequence(title));
+        }
+        lines
+    }
+
+    /// Returns `lines` between the sentinel lines of `rendered` when stdout is not a terminal and
+    /// delimiters are on, see `set_delimiters`.
+    fn delimit<'a>(rendered: &RenderedWhisper, lines: Cow<'a, [String]>) -> Cow<'a, [String]> {
+        if term::capabilities().stdout_tty {
+            return lines;
+        }
+        delimiter::wrap(rendered, lines)
+    }
+}
+
+/// `Rotation` decides when a `FileSink` starts a new file.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum Rotation {
+    /// The file grows forever, the default.
+    #[default]
+    Never,
+    /// A new file is started before the file would grow beyond this many bytes.
+    Size(u64),
+    /// A new file is started on the first whisper of every day, in UTC.
+    Daily,
+}
+
+/// The number of rotated files a `FileSink` keeps by default.
+const DEFAULT_KEEP: usize = 5;
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// The open log file of a `FileSink`.
+#[derive(Debug)]
+struct LogFile {
+    /// The file, opened in append mode.
+    file: File,
+    /// The number of bytes in the file.
+    len: u64,
+    /// The day the file was last written, in days since the Unix epoch.
+    day: u64,
+}
+
+/// `FileSink` appends the lines of every whisper to a file, uncolored unless its `AnsiPolicy`
+/// says otherwise, optionally rotating it.
+///
+/// Rotated files are renamed with a numbered suffix: `murmur.log` becomes `murmur.log.1`, the
+/// previous `murmur.log.1` becomes `murmur.log.2`, and files beyond `keep` are deleted.
+///
+/// ```no_run
+/// use murmur::{FileSink, Rotation};
+///
+/// murmur::add_sink(
+///     FileSink::new("daemon.log")
+///         .unwrap()
+///         .rotation(Rotation::Size(10 * 1024 * 1024))
+///         .keep(3),
+/// );
+/// ```
+#[derive(Debug)]
+pub struct FileSink {
+    /// The path of the current file.
+    path: PathBuf,
+    /// When a new file is started.
+    rotation: Rotation,
+    /// The number of rotated files kept.
+    keep: usize,
+    /// Whether the lines are written with colors.
+    ansi: AnsiPolicy,
+    /// The current file.
+    log_file: Mutex<LogFile>,
+}
+
+impl FileSink {
+    /// Opens `path` for appending, creating it if needed. The file is not rotated until `rotation` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `io::Error` if the file cannot be opened.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let log_file = open_log_file(&path)?;
+        Ok(Self {
+            path,
+            rotation: Rotation::Never,
+            keep: DEFAULT_KEEP,
+            ansi: AnsiPolicy::Never,
+            log_file: Mutex::new(log_file),
+        })
+    }
+
+    /// Sets when a new file is started.
+    #[must_use]
+    pub const fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets the number of rotated files kept next to the current one, 5 by default.
+    #[must_use]
+    pub const fn keep(mut self, keep: usize) -> Self {
+        self.keep = keep;
+        self
+    }
+
+    /// Sets whether the lines are written with colors, `AnsiPolicy::Never` by default.
+    #[must_use]
+    pub const fn ansi(mut self, ansi: AnsiPolicy) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    /// Returns whether `log_file` must be rotated before writing `len` more bytes on `today`.
+    const fn must_rotate(&self, log_file: &LogFile, len: u64, today: u64) -> bool {
+        match self.rotation {
+            Rotation::Never => false,
+            Rotation::Size(max_len) => log_file.len > 0 && log_file.len + len > max_len,
+            Rotation::Daily => log_file.len > 0 && log_file.day != today,
+        }
+    }
+
+    /// Shifts the rotated files by one, moves the current file to `.1` and opens a new one.
+    fn rotate(&self, log_file: &mut LogFile) -> io::Result<()> {
+        let rotated = |index: usize| {
+            let mut path = self.path.clone().into_os_string();
+            path.push(format!(".{index}"));
+            PathBuf::from(path)
+        };
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            match fs::remove_file(rotated(self.keep)) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                _ => {}
+            }
+            for index in (1..self.keep).rev() {
+                match fs::rename(rotated(index), rotated(index + 1)) {
+                    Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                    _ => {}
+                }
+            }
+            fs::rename(&self.path, rotated(1))?;
+        }
+        *log_file = open_log_file(&self.path)?;
+        Ok(())
+    }
+}
+
+impl Sink for FileSink {
+    /// Appends the lines of `rendered` for the `AnsiPolicy` of the sink to the file, rotating it
+    /// first if needed.
+    ///
+    /// The lines are written between sentinel lines if delimiters are on, see `set_delimiters`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if the file lock is poisoned and `WhisperError::Write` if
+    /// rotating or writing fails.
+    fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+        let lines = delimiter::wrap(rendered, Cow::Owned(rendered.lines_for(self.ansi)));
+        let mut log_file = self.log_file.lock().map_err(|_| WhisperError::Lock)?;
+        let len = lines.iter().map(|line| line.len() as u64 + 1).sum();
+        let today = today();
+        if self.must_rotate(&log_file, len, today) {
+            self.rotate(&mut log_file)
+                .map_err(|_| WhisperError::Write)?;
+        }
+        write_lines(&mut log_file.file, &lines).map_err(|_| WhisperError::Write)?;
+        log_file.len += len;
+        log_file.day = today;
+        drop(log_file);
+        Ok(())
+    }
+}
+
+/// Opens the log file at `path` for appending, creating it if needed.
+fn open_log_file(path: &Path) -> io::Result<LogFile> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let metadata = file.metadata()?;
+    let day = metadata
+        .modified()
+        .map_or_else(|_| today(), days_since_epoch);
+    Ok(LogFile {
+        file,
+        len: metadata.len(),
+        day,
+    })
+}
+
+/// Returns the current day in days since the Unix epoch.
+fn today() -> u64 {
+    days_since_epoch(SystemTime::now())
+}
+
+/// Returns the day of `time` in days since the Unix epoch, 0 for times before it.
+fn days_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / SECONDS_PER_DAY)
+}
+
+/// `JsonSink` writes every whisper as one line of JSON, e.g. for log collectors.
+///
+/// ```text
+/// {"level":"warn","icon":"NfFaWarning","messages":["disk almost full"],"fields":{"used":"92%"}}
+/// ```
+///
+/// The `fields` object is only written for whispers with fields, and `correlation_id` for whispers
+/// with a correlation ID.
+pub struct JsonSink {
+    /// The writer the JSON lines are written to.
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonSink {
+    /// Creates a sink writing JSON lines to `writer`, e.g. `std::io::stderr()` or a `File`.
+    pub fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    /// Returns the JSON object written for `rendered`, without the trailing newline.
+    #[must_use]
+    pub fn to_json(rendered: &RenderedWhisper) -> String {
+        let mut json = format!("{{\"level\":\"{}\"", rendered.level);
+        if let Some(icon_kind) = &rendered.icon_kind {
+            let _ = write!(json, ",\"icon\":\"{}\"", icon_kind.as_str());
+        }
+        json.push_str(",\"messages\":[");
+        for (index, message) in rendered.messages.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, "\"{}\"", escape_json(message));
+        }
+        json.push(']');
+        if !rendered.fields.is_empty() {
+            json.push_str(",\"fields\":{");
+            for (index, (key, value)) in rendered.fields.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                let _ = write!(json, "\"{}\":\"{}\"", escape_json(key), escape_json(value));
+            }
+            json.push('}');
+        }
+        if let Some(id) = &rendered.correlation_id {
+            let _ = write!(json, ",\"correlation_id\":\"{}\"", escape_json(id));
+        }
+        json.push('}');
+        json
+    }
+}
+
+impl Sink for JsonSink {
+    /// Writes `rendered` as one line of JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WhisperError::Lock` if the writer lock is poisoned and `WhisperError::Write` if writing fails.
+    fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+        let mut writer = self.writer.lock().map_err(|_| WhisperError::Lock)?;
+        write_lines(&mut *writer, &[Self::to_json(rendered)]).map_err(|_| WhisperError::Write)
+    }
+}
+
+/// Writes every line of `lines` followed by a newline to `writer` with a single write, then flushes it.
+///
+/// Interrupted writes and flushes are retried and short writes are completed, see `write_fully`.
+pub fn write_lines<W: Write + ?Sized>(writer: &mut W, lines: &[String]) -> io::Result<()> {
+    let len = lines.iter().map(|line| line.len() + 1).sum();
+    let mut buffer = String::with_capacity(len);
+    for line in lines {
+        buffer.push_str(line);
+        buffer.push('\n');
+    }
+    write_fully(writer, buffer.as_bytes())?;
+    loop {
+        match writer.flush() {
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
+/// Writes all of `bytes` to `writer`, retrying interrupted writes and completing short writes.
+///
+/// If writing fails after part of colored `bytes` was written, a color reset and a newline are
+/// written on a best-effort basis, so the terminal is not left in the color of a half-printed line.
+fn write_fully<W: Write + ?Sized>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let mut written = 0;
+    while written < bytes.len() {
+        match writer.write(&bytes[written..]) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write the whole whisper",
+                ));
+            }
+            Ok(len) => written += len,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => {
+                if written > 0 && bytes[..written].contains(&0x1b) {
+                    let _ = writer.write_all(RESET);
+                }
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The escape sequence resetting colors and a newline, written after a whisper failed mid-way.
+const RESET: &[u8] = b"\x1b[0m\n";
+
+/// Escapes `text` for a JSON string literal.
+pub fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            control if control.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", u32::from(control));
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod sink_tests {
+    use super::*;
+    use crate::{IconKind, RenderOptions, Whisper};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A sink counting the whispers it receives.
+    struct Counter(Arc<AtomicUsize>);
+
+    impl Sink for Counter {
+        fn emit(&self, _rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    /// A sink that always fails.
+    struct Failing;
+
+    impl Sink for Failing {
+        fn emit(&self, _rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+            Err(WhisperError::Sink)
+        }
+    }
+
+    /// A writer shared with the test, to read back what a sink wrote.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// The uncolored lines of `rendered()`, as written to a file.
+    fn plain_text() -> String {
+        rendered().plain_lines().join("\n") + "\n"
+    }
+
+    /// Renders a warning whisper with colors.
+    fn rendered() -> RenderedWhisper {
+        let whisper = Whisper::new()
+            .icon(IconKind::NfFaWarning)
+            .message("disk \"/\" almost full")
+            .message("92%");
+        RenderedWhisper::new(&whisper, &RenderOptions::default())
+    }
+
+    #[test]
+    fn test_emit_reaches_every_sink() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let sinks: Vec<Box<dyn Sink>> = vec![
+            Box::new(Counter(Arc::clone(&count))),
+            Box::new(Failing),
+            Box::new(Counter(Arc::clone(&count))),
+        ];
+        let result = emit_all(&sinks, &rendered());
+        assert!(matches!(result, Err(WhisperError::Sink)));
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_min_level_filters_whispers() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let sink = MinLevel {
+            sink: Box::new(Counter(Arc::clone(&count))),
+            min_level: Level::Warn,
+        };
+        let info = RenderedWhisper::new(&Whisper::new().message("info"), &RenderOptions::plain());
+        sink.emit(&info).unwrap();
+        sink.emit(&rendered()).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        sink.emit_batch(&[info.clone(), rendered(), info]).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_json_sink() {
+        let shared = Shared::default();
+        JsonSink::new(shared.clone()).emit(&rendered()).unwrap();
+        let written = String::from_utf8(shared.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            written,
+            "{\"level\":\"warn\",\"icon\":\"NfFaWarning\",\"messages\":[\"disk \\\"/\\\" almost full\",\"92%\"]}\n"
+        );
+    }
+
+    #[test]
+    fn test_json_fields() {
+        let whisper = Whisper::new()
+            .message("disk almost full")
+            .field_display("used", &"92%")
+            .field_debug("mount", &"/");
+        let json = JsonSink::to_json(&RenderedWhisper::new(&whisper, &RenderOptions::plain()));
+        assert_eq!(
+            json,
+            "{\"level\":\"info\",\"messages\":[\"disk almost full\"],\"fields\":{\"used\":\"92%\",\"mount\":\"\\\"/\\\"\"}}"
+        );
+    }
+
+    #[test]
+    fn test_json_correlation_id() {
+        let whisper = Whisper::new()
+            .message("deploy requested")
+            .correlation_id("5f0c9a1e");
+        let json = JsonSink::to_json(&RenderedWhisper::new(&whisper, &RenderOptions::plain()));
+        assert_eq!(
+            json,
+            "{\"level\":\"info\",\"messages\":[\"deploy requested\"],\"correlation_id\":\"5f0c9a1e\"}"
+        );
+    }
+
+    #[test]
+    fn test_file_sink_appends_plain_lines() {
+        let path =
+            std::env::temp_dir().join(format!("murmur-file-sink-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = FileSink::new(&path).unwrap();
+        sink.emit(&rendered()).unwrap();
+        sink.emit(&rendered()).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, plain_text().repeat(2));
+    }
+
+    #[test]
+    fn test_file_sink_ansi_policy() {
+        let path = std::env::temp_dir().join(format!("murmur-ansi-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let rendered = RenderedWhisper::new(
+            &Whisper::new()
+                .icon(IconKind::NfFaWarning)
+                .message("colored"),
+            &RenderOptions::plain(),
+        );
+        let sink = FileSink::new(&path).unwrap().ansi(AnsiPolicy::Always);
+        sink.emit(&rendered).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            written,
+            rendered.lines_for(AnsiPolicy::Always).join("\n") + "\n"
+        );
+        assert_eq!(rendered.lines(), rendered.plain_lines());
+        #[cfg(all(feature = "color", not(feature = "plain")))]
+        assert!(written.contains("\x1b["));
+    }
+
+    #[test]
+    fn test_file_sink_rotates_by_size() {
+        let dir = std::env::temp_dir().join(format!("murmur-rotation-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("murmur.log");
+        let line_len = plain_text().len() as u64;
+        let sink = FileSink::new(&path)
+            .unwrap()
+            .rotation(Rotation::Size(line_len * 2))
+            .keep(2);
+        for _ in 0..7 {
+            sink.emit(&rendered()).unwrap();
+        }
+        let mut files = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        let len = |name: &str| fs::metadata(dir.join(name)).unwrap().len();
+        assert_eq!(files, ["murmur.log", "murmur.log.1", "murmur.log.2"]);
+        assert_eq!(len("murmur.log"), line_len);
+        assert_eq!(len("murmur.log.1"), line_len * 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_file_sink_rotates_daily() {
+        let path = std::env::temp_dir().join(format!("murmur-daily-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let sink = FileSink::new(&path)
+            .unwrap()
+            .rotation(Rotation::Daily)
+            .keep(0);
+        sink.emit(&rendered()).unwrap();
+        sink.log_file.lock().unwrap().day -= 1;
+        assert!(sink.must_rotate(&sink.log_file.lock().unwrap(), 1, today()));
+        sink.emit(&rendered()).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(written.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_write_lines_writes_once() {
+        /// A writer counting its writes.
+        #[derive(Default)]
+        struct Writes(Vec<usize>);
+
+        impl Write for Writes {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.push(buf.len());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writes = Writes::default();
+        let lines = ["first".to_string(), "  second".to_string()];
+        write_lines(&mut writes, &lines).unwrap();
+        assert_eq!(writes.0, ["first\n  second\n".len()]);
+    }
+
+    #[test]
+    fn test_write_lines_retries_interrupted_and_short_writes() {
+        /// A writer interrupted every other call, writing at most 3 bytes at once.
+        #[derive(Default)]
+        struct Flaky {
+            /// The bytes written.
+            written: Vec<u8>,
+            /// The number of calls.
+            calls: usize,
+        }
+
+        impl Write for Flaky {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.calls += 1;
+                if self.calls % 2 == 1 {
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                let len = buf.len().min(3);
+                self.written.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.calls += 1;
+                if self.calls % 2 == 1 {
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                Ok(())
+            }
+        }
+
+        let mut flaky = Flaky::default();
+        let lines = ["first".to_string(), "  second".to_string()];
+        write_lines(&mut flaky, &lines).unwrap();
+        assert_eq!(flaky.written, b"first\n  second\n");
+    }
+
+    #[test]
+    fn test_failed_write_resets_colors() {
+        /// A writer failing after its first write.
+        #[derive(Default)]
+        struct Broken(Vec<u8>);
+
+        impl Write for Broken {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if !self.0.is_empty() && buf != RESET {
+                    return Err(io::ErrorKind::BrokenPipe.into());
+                }
+                let len = buf.len().min(8);
+                self.0.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut broken = Broken::default();
+        let lines = ["\x1b[31mfailed\x1b[39m".to_string()];
+        let error = write_lines(&mut broken, &lines).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(broken.0, b"\x1b[31mfai\x1b[0m\n");
+    }
+
+    /// A sink writing the lines of the whispers starting with `sink_tests` one at a time, so only
+    /// the output lock keeps the whispers of concurrent threads from interleaving.
+    struct LineByLine(Shared);
+
+    impl Sink for LineByLine {
+        fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+            if !rendered
+                .messages
+                .first()
+                .is_some_and(|message| message.starts_with("sink_tests"))
+            {
+                return Ok(());
+            }
+            for line in rendered.plain_lines() {
+                writeln!(self.0.clone(), "{line}").map_err(|_| WhisperError::Write)?;
+                std::thread::yield_now();
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_concurrent_whispers_do_not_interleave() {
+        let shared = Shared::default();
+        add_sink(LineByLine(shared.clone()));
+        let threads = (0..4)
+            .map(|thread| {
+                std::thread::spawn(move || {
+                    let whisper = Whisper::new()
+                        .message(format!("sink_tests {thread}"))
+                        .messages((0..9).map(|line| format!("{thread}:{line}")));
+                    for _ in 0..5 {
+                        whisper.whisper().unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let written = String::from_utf8(shared.0.lock().unwrap().clone()).unwrap();
+        let lines = written.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4 * 5 * 10);
+        for whisper in lines.chunks(10) {
+            let thread = whisper[0].trim_start_matches("sink_tests ");
+            assert!(whisper[1..]
+                .iter()
+                .all(|line| line.trim_start().starts_with(&format!("{thread}:"))));
+        }
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("a\u{1}\tb\\"), "a\\u0001\\tb\\\\");
+    }
+}
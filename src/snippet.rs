@@ -0,0 +1,97 @@
+//! The `snippet` module attaches the offending line of a source file to a whisper.
+//!
+//! Tools reporting a position in a user file add a `Snippet` with `Whisper::snippet()`. The line is
+//! printed after the messages with its number in a gutter, and the reported columns are underlined
+//! with carets in the color of the whisper, like compilers do.
+//!
+//! ```
+//! use murmur::{IconKind, RenderOptions, Whisper};
+//!
+//! let source = "[package]\nname = murmur\n";
+//! let lines = Whisper::new()
+//!     .icon(IconKind::NfFaTimes)
+//!     .message("Cargo.toml:2:8: expected a string")
+//!     .snippet(source, 2, 8..14)
+//!     .render(&RenderOptions::plain());
+//! # #[cfg(not(feature = "plain"))]
+//! assert_eq!(
+//!     lines,
+//!     [
+//!         "\u{f00d} Cargo.toml:2:8: expected a string",
+//!         "  2 | name = murmur",
+//!         "    |        ^^^^^^",
+//!     ]
+//! );
+//! ```
+//!
+use alloc::string::String;
+use core::ops::Range;
+
+/// `Snippet` is a line of a source file with the columns to underline, see `Whisper::snippet()`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Snippet {
+    /// The number of the line, starting at 1.
+    pub line: usize,
+    /// The text of the line, without its line ending.
+    pub text: String,
+    /// The columns to underline, in characters starting at 1, the end excluded.
+    pub columns: Range<usize>,
+}
+
+impl Snippet {
+    /// Creates the `Snippet` of the line numbered `line` of `source`, starting at 1, underlining
+    /// `columns`, in characters starting at 1 with the end excluded.
+    ///
+    /// The text is empty if `source` has fewer lines.
+    #[must_use]
+    pub fn new(source: &str, line: usize, columns: Range<usize>) -> Self {
+        let text = source
+            .lines()
+            .nth(line.saturating_sub(1))
+            .unwrap_or_default();
+        Self {
+            line,
+            text: String::from(text),
+            columns,
+        }
+    }
+
+    /// Returns the text before the underlined columns and the underlined text.
+    ///
+    /// Columns past the end of the line are clamped to it.
+    #[must_use]
+    pub fn split(&self) -> (&str, &str) {
+        let offset = |column: usize| {
+            self.text
+                .char_indices()
+                .nth(column.saturating_sub(1))
+                .map_or(self.text.len(), |(offset, _)| offset)
+        };
+        let start = offset(self.columns.start);
+        let end = offset(self.columns.end).max(start);
+        (&self.text[..start], &self.text[start..end])
+    }
+}
+
+#[cfg(test)]
+mod snippet_tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let snippet = Snippet::new("first\r\nsecond\n", 2, 1..3);
+        assert_eq!(snippet.text, "second");
+        assert_eq!(Snippet::new("first", 4, 1..3).text, "");
+    }
+
+    #[test]
+    fn test_split() {
+        let snippet = Snippet::new("let caf\u{e9} = 1;", 1, 5..9);
+        assert_eq!(snippet.split(), ("let ", "caf\u{e9}"));
+        let past_end = Snippet {
+            columns: 12..20,
+            ..snippet
+        };
+        assert_eq!(past_end.split(), ("let caf\u{e9} = ", "1;"));
+    }
+}
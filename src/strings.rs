@@ -0,0 +1,147 @@
+//! The `strings` module holds the texts murmur adds to whispers, so they can be translated.
+//!
+//! murmur generates a few texts of its own, e.g. the `suppressed N similar messages` line of rate
+//! limited whispers. Tools shipping in other languages replace them with `set_strings`. The
+//! `doctor` report is meant for bug reports and stays in English.
+//!
+//! ```
+//! use murmur::Strings;
+//!
+//! murmur::set_strings(Strings {
+//!     suppressed: |count| format!("{count} messages similaires ignor\u{e9}s"),
+//!     ..Strings::english()
+//! });
+//! # murmur::set_strings(Strings::english());
+//! ```
+//!
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// `Strings` are the texts murmur adds to whispers.
+///
+/// Texts depending on a count are functions, so translations can follow the plural rules of their language.
+#[derive(Debug, Clone, Copy)]
+pub struct Strings {
+    /// The last line of a rate limited whisper, given the number of similar whispers suppressed.
+    pub suppressed: fn(usize) -> String,
+    /// The last line of a `Report`, asking to press a key to leave it.
+    pub report_prompt: &'static str,
+    /// The completion line of `progress_iter`, given the number of items and the elapsed time.
+    pub completed: fn(usize, Duration) -> String,
+    /// The summary of a `TaskList`, given the number of tasks, of succeeded and of failed tasks.
+    pub tasks_summary: fn(usize, usize, usize) -> String,
+    /// The totals footer of a `Report`, given the number of whispers, of errors and of warnings.
+    pub report_totals: fn(usize, usize, usize) -> String,
+    /// The header of `Whisper::from_results`, given the number of succeeded and of failed items.
+    pub results_summary: fn(usize, usize) -> String,
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl Strings {
+    /// The built-in English texts.
+    #[must_use]
+    pub const fn english() -> Self {
+        Self {
+            suppressed: english_suppressed,
+            report_prompt: "press any key to continue",
+            completed: english_completed,
+            tasks_summary: english_tasks_summary,
+            report_totals: english_report_totals,
+            results_summary: english_results_summary,
+        }
+    }
+}
+
+/// Returns the English line of a rate limited whisper.
+fn english_suppressed(count: usize) -> String {
+    if count == 1 {
+        String::from("suppressed 1 similar message")
+    } else {
+        format!("suppressed {count} similar messages")
+    }
+}
+
+/// Returns the English completion line of `progress_iter`.
+fn english_completed(count: usize, elapsed: Duration) -> String {
+    let items = if count == 1 { "item" } else { "items" };
+    format!("{count} {items} in {}", format_elapsed(elapsed))
+}
+
+/// Returns the English summary of a `TaskList`.
+fn english_tasks_summary(count: usize, succeeded: usize, failed: usize) -> String {
+    let tasks = if count == 1 { "task" } else { "tasks" };
+    format!("{count} {tasks}: {succeeded} succeeded, {failed} failed")
+}
+
+/// Returns the English totals footer of a `Report`.
+fn english_report_totals(count: usize, errors: usize, warnings: usize) -> String {
+    let whispers = if count == 1 { "whisper" } else { "whispers" };
+    let errors_text = if errors == 1 { "error" } else { "errors" };
+    let warnings_text = if warnings == 1 { "warning" } else { "warnings" };
+    format!("{count} {whispers}: {errors} {errors_text}, {warnings} {warnings_text}")
+}
+
+/// Returns the English header of `Whisper::from_results`.
+fn english_results_summary(succeeded: usize, failed: usize) -> String {
+    format!("{succeeded} succeeded, {failed} failed")
+}
+
+/// Returns `elapsed` in seconds with a decimal, or in minutes and seconds from a minute on.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds < 60 {
+        format!("{:.1}s", elapsed.as_secs_f64())
+    } else {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+/// The global `Strings`, `Strings::english()` until `set_strings` is called.
+static STRINGS: RwLock<Strings> = RwLock::new(Strings::english());
+
+/// Sets the global `Strings` used by every whisper.
+///
+/// # Panics
+///
+/// Panics if the strings lock is poisoned.
+pub fn set_strings(strings: Strings) {
+    *STRINGS.write().unwrap() = strings;
+}
+
+/// Returns the global `Strings`.
+pub fn strings() -> Strings {
+    STRINGS
+        .read()
+        .map_or_else(|_| Strings::english(), |strings| *strings)
+}
+
+#[cfg(test)]
+mod strings_tests {
+    use super::*;
+
+    #[test]
+    fn test_english_suppressed() {
+        let suppressed = Strings::english().suppressed;
+        assert_eq!(suppressed(1), "suppressed 1 similar message");
+        assert_eq!(suppressed(3), "suppressed 3 similar messages");
+    }
+
+    #[test]
+    fn test_english_completed() {
+        let completed = Strings::english().completed;
+        assert_eq!(completed(1, Duration::from_millis(1_300)), "1 item in 1.3s");
+        assert_eq!(completed(42, Duration::from_secs(75)), "42 items in 1m15s");
+    }
+
+    #[test]
+    fn test_english_report_totals() {
+        let report_totals = Strings::english().report_totals;
+        assert_eq!(report_totals(1, 0, 1), "1 whisper: 0 errors, 1 warning");
+        assert_eq!(report_totals(5, 1, 2), "5 whispers: 1 error, 2 warnings");
+    }
+}
@@ -0,0 +1,290 @@
+//! The `svg` module exports whispers as an SVG image of a terminal, to show CLI output in docs.
+//!
+//! Screenshots of a terminal go stale and cannot be diffed. An `SvgExport` renders whispers with
+//! the same segments a terminal prints, so documentation sites can embed an accurate, styled
+//! example of the output of a tool, regenerated with the tool itself.
+//!
+//! ```
+//! use murmur::{IconKind, SvgExport, Whisper};
+//!
+//! let svg = SvgExport::new()
+//!     .title("cargo xtask release")
+//!     .whisper(&Whisper::new().icon(IconKind::NfFaCheck).message("tagged v2.0.0"))
+//!     .whisper(&Whisper::new().icon(IconKind::NfFaWarning).message("changelog has no date"))
+//!     .render();
+//! assert!(svg.starts_with("<svg"));
+//! assert!(svg.contains("tagged v2.0.0"));
+//! ```
+//!
+//! Every segment is positioned at its column, so icons of 2 columns keep the messages aligned
+//! whatever the metrics of the font. Messages colored by the caller are exported without their
+//! colors, and without the `color` feature the image is monochrome.
+//!
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+
+use crate::{width, Color, RenderOptions, RenderedWhisper, Segment, Whisper};
+
+/// The font size of an `SvgExport` by default, in pixels.
+const DEFAULT_FONT_SIZE: u32 = 14;
+
+/// The background color of the terminal.
+const BACKGROUND: &str = "#1e1e1e";
+
+/// The default text color of the terminal.
+const FOREGROUND: &str = "#d4d4d4";
+
+/// The colors of the 16 colors of the terminal palette, the first ones of the `Ansi256` colors.
+const PALETTE: [&str; 16] = [
+    "#000000", "#cd3131", "#0dbc79", "#e5e510", "#2472c8", "#bc3fbc", "#11a8cd", "#e5e5e5",
+    "#666666", "#f14c4c", "#23d18b", "#f5f543", "#3b8eea", "#d670d6", "#29b8db", "#ffffff",
+];
+
+/// `SvgExport` renders whispers to an SVG image of a terminal, see the `svg` module.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SvgExport {
+    /// The title shown in the title bar, if any.
+    title: Option<String>,
+    /// The font size, in pixels.
+    font_size: u32,
+    /// The options the whispers are rendered with, always with color.
+    options: RenderOptions,
+    /// The lines of the whispers added so far.
+    lines: Vec<Vec<Segment<'static>>>,
+}
+
+impl Default for SvgExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvgExport {
+    /// Creates an `SvgExport` without title or whispers, rendering with `RenderOptions::default()`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            title: None,
+            font_size: DEFAULT_FONT_SIZE,
+            options: RenderOptions::default(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Shows `title` in the title bar of the image, e.g. the command that printed the whispers.
+    #[must_use]
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(String::from(title));
+        self
+    }
+
+    /// Sets the font size in pixels, 14 by default.
+    #[must_use]
+    pub const fn font_size(mut self, font_size: u32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Renders the whispers added next with `options`, e.g. with ASCII icons or a light theme.
+    ///
+    /// The whispers are always rendered with color.
+    #[must_use]
+    pub fn options(mut self, options: RenderOptions) -> Self {
+        self.options = RenderOptions {
+            color: true,
+            ..options
+        };
+        self
+    }
+
+    /// Adds the lines of `whisper` to the image.
+    #[must_use]
+    pub fn whisper(mut self, whisper: &Whisper) -> Self {
+        self.lines.extend(whisper.segments(&self.options));
+        self
+    }
+
+    /// Adds the lines of a whisper already rendered, e.g. kept by a `Sink`, to the image.
+    #[must_use]
+    pub fn rendered(mut self, rendered: &RenderedWhisper) -> Self {
+        self.lines.extend(rendered.segments.iter().cloned());
+        self
+    }
+
+    /// Returns the SVG image of the whispers added so far.
+    #[must_use]
+    pub fn render(&self) -> String {
+        // Lengths are computed in tenths of pixels, for a monospace font 0.6 em wide
+        let column = self.font_size * 6;
+        let line_height = self.font_size * 14;
+        let margin = self.font_size * 10;
+        let title_bar = if self.title.is_some() { line_height } else { 0 };
+        let columns = self
+            .lines
+            .iter()
+            .map(|line| crate::line_width(line))
+            .chain(self.title.as_deref().map(width::display_width))
+            .max()
+            .unwrap_or_default();
+        let width = margin * 2 + column * u32::try_from(columns).unwrap_or(u32::MAX / column / 2);
+        let rows = u32::try_from(self.lines.len()).unwrap_or(u32::MAX / line_height / 2);
+        let height = title_bar + margin * 2 + line_height * rows;
+
+        let mut svg = String::new();
+        let (width, height) = (px(width), px(height));
+        // Writing to a `String` cannot fail
+        let _ = write!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"ui-monospace, Menlo, Consolas, monospace\" \
+             font-size=\"{}\">\n\
+             <rect width=\"100%\" height=\"100%\" rx=\"6\" fill=\"{BACKGROUND}\"/>\n",
+            self.font_size,
+        );
+        if let Some(title) = &self.title {
+            let _ = writeln!(
+                svg,
+                "<text x=\"50%\" y=\"{}\" text-anchor=\"middle\" fill=\"{}\">{}</text>",
+                px(line_height),
+                PALETTE[8],
+                escape(title),
+            );
+        }
+        for (row, line) in (0..).zip(&self.lines) {
+            let baseline = title_bar + margin + line_height * row + self.font_size * 10;
+            let _ = write!(
+                svg,
+                "<text y=\"{}\" fill=\"{FOREGROUND}\" xml:space=\"preserve\">",
+                px(baseline)
+            );
+            let mut offset = 0;
+            for segment in line {
+                let text = segment.plain();
+                let x = margin + column * u32::try_from(offset).unwrap_or_default();
+                offset += segment.width();
+                if text.trim().is_empty() {
+                    continue;
+                }
+                write_segment(&mut svg, segment, &text, &px(x));
+            }
+            svg.push_str("</text>\n");
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Writes the `tspan` of `segment` at `x`, with its `text` without escape sequences.
+fn write_segment(svg: &mut String, segment: &Segment<'_>, text: &str, x: &str) {
+    if let Some(link) = &segment.link {
+        let _ = write!(svg, "<a href=\"{}\">", escape(link));
+    }
+    let _ = write!(svg, "<tspan x=\"{x}\"");
+    match segment.style.color {
+        Some(Color::Dimmed) => svg.push_str(" opacity=\"0.6\""),
+        Some(color) => {
+            if let Some(fill) = fill(color) {
+                let _ = write!(svg, " fill=\"{fill}\"");
+            }
+        }
+        None => {}
+    }
+    let _ = write!(svg, ">{}</tspan>", escape(text));
+    if segment.link.is_some() {
+        svg.push_str("</a>");
+    }
+}
+
+/// Returns the fill of `color`, `None` for the default text color.
+fn fill(color: Color) -> Option<String> {
+    let index = match color {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::White => 7,
+        Color::BrightBlack => 8,
+        Color::Ansi256(code) => return Some(ansi256(code)),
+        Color::Rgb(red, green, blue) => return Some(format!("#{red:02x}{green:02x}{blue:02x}")),
+        Color::Dimmed | Color::Custom(_) => return None,
+    };
+    Some(String::from(PALETTE[index]))
+}
+
+/// Returns the color of the `Ansi256` color `code`: the 16 colors of the palette, then a 6x6x6 cube
+/// and 24 grays.
+fn ansi256(code: u8) -> String {
+    match code {
+        0..=15 => String::from(PALETTE[usize::from(code)]),
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let code = code - 16;
+            let (red, green, blue) = (level(code / 36), level(code / 6 % 6), level(code % 6));
+            format!("#{red:02x}{green:02x}{blue:02x}")
+        }
+        _ => {
+            let gray = 8 + (code - 232) * 10;
+            format!("#{gray:02x}{gray:02x}{gray:02x}")
+        }
+    }
+}
+
+/// Returns `tenths` of pixels as a length, e.g. `8.4`.
+fn px(tenths: u32) -> String {
+    match tenths % 10 {
+        0 => format!("{}", tenths / 10),
+        decimal => format!("{}.{decimal}", tenths / 10),
+    }
+}
+
+/// Returns `text` with the characters XML reserves escaped.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod svg_tests {
+    use super::*;
+    use crate::IconKind;
+
+    #[test]
+    fn test_render_svg() {
+        let svg = SvgExport::new()
+            .title("a < b")
+            .options(RenderOptions {
+                ascii_icons: true,
+                ..RenderOptions::plain()
+            })
+            .whisper(&Whisper::new().icon(IconKind::NfFaCheck).message("x & y"))
+            .render();
+        assert!(svg.contains(">a &lt; b</text>"));
+        assert!(svg.contains("<tspan x=\"14\""));
+        assert!(svg.contains(">x &amp; y</tspan>"));
+        // A 2 columns prefix of 8.4 pixels each, after the margin
+        assert!(svg.contains("<tspan x=\"30.8\""));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill(Color::Rgb(255, 8, 0)).as_deref(), Some("#ff0800"));
+        assert_eq!(ansi256(196), "#ff0000");
+        assert_eq!(ansi256(232), "#080808");
+        assert_eq!(fill(Color::Dimmed), None);
+    }
+}
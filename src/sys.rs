@@ -0,0 +1,256 @@
+//! The `sys` module wraps the few platform calls murmur needs that the standard library does not expose.
+//!
+//! This is the only module allowed to use `unsafe` code, every call is a thin wrapper around a single
+//! system call and falls back to `None` when the call fails or the platform is not supported.
+//!
+#![allow(unsafe_code)]
+
+/// Returns the size of the terminal attached to stdout, stderr or stdin as `(width, height)`.
+///
+/// The first stream connected to a terminal wins, `None` is returned if none of them is.
+#[cfg(unix)]
+pub fn terminal_size() -> Option<(usize, usize)> {
+    [libc::STDOUT_FILENO, libc::STDERR_FILENO, libc::STDIN_FILENO]
+        .into_iter()
+        .find_map(|fd| {
+            let mut size = libc::winsize {
+                ws_row: 0,
+                ws_col: 0,
+                ws_xpixel: 0,
+                ws_ypixel: 0,
+            };
+            // SAFETY: `TIOCGWINSZ` only writes into the `winsize` struct we own.
+            let result = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) };
+            (result == 0 && size.ws_col > 0)
+                .then(|| (usize::from(size.ws_col), usize::from(size.ws_row)))
+        })
+}
+
+/// Returns the size of the terminal, not supported on this platform.
+#[cfg(not(unix))]
+pub fn terminal_size() -> Option<(usize, usize)> {
+    None
+}
+
+/// Enables virtual terminal processing on the Windows console attached to stdout, so ANSI
+/// escape sequences are interpreted instead of printed, e.g. in `cmd.exe` and old `PowerShell` hosts.
+///
+/// Returns whether the console processes ANSI escape sequences.
+#[cfg(all(windows, feature = "windows-vt"))]
+pub fn enable_virtual_terminal() -> bool {
+    use winapi::shared::minwindef::DWORD;
+    use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
+    use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+    use winapi::um::processenv::GetStdHandle;
+    use winapi::um::winbase::STD_OUTPUT_HANDLE;
+    use winapi::um::wincon::ENABLE_VIRTUAL_TERMINAL_PROCESSING;
+
+    // SAFETY: `GetStdHandle` has no preconditions, the returned handle is checked below.
+    let handle = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) };
+    if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+        return false;
+    }
+    let mut mode: DWORD = 0;
+    // SAFETY: `handle` is a valid console handle and `GetConsoleMode` only writes into `mode`.
+    if unsafe { GetConsoleMode(handle, std::ptr::addr_of_mut!(mode)) } == 0 {
+        return false;
+    }
+    if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 {
+        return true;
+    }
+    // SAFETY: `handle` is a valid console handle.
+    unsafe { SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0 }
+}
+
+/// Enables virtual terminal processing, only needed on Windows, terminals process ANSI escape sequences elsewhere.
+#[cfg(not(all(windows, feature = "windows-vt")))]
+pub const fn enable_virtual_terminal() -> bool {
+    true
+}
+
+/// `TtyMode` switches a terminal to non-canonical mode and restores the original settings on drop.
+#[cfg(unix)]
+pub struct TtyMode {
+    /// The file descriptor of the terminal.
+    fd: libc::c_int,
+    /// The settings to restore on drop.
+    original: libc::termios,
+}
+
+#[cfg(unix)]
+impl TtyMode {
+    /// Switches the terminal behind `fd` to non-canonical mode.
+    ///
+    /// * `echo`: whether typed characters are echoed back.
+    /// * `timeout_ds`: if `Some`, reads return after this many deciseconds even without input,
+    ///   otherwise reads block until at least one byte is available.
+    ///
+    /// Returns `None` if `fd` is not a terminal.
+    pub fn non_canonical(fd: libc::c_int, echo: bool, timeout_ds: Option<u8>) -> Option<Self> {
+        Self::configure(fd, |mode| {
+            mode.c_lflag &= !libc::ICANON;
+            if !echo {
+                mode.c_lflag &= !libc::ECHO;
+            }
+            mode.c_cc[libc::VMIN] = u8::from(timeout_ds.is_none());
+            mode.c_cc[libc::VTIME] = timeout_ds.unwrap_or(0);
+        })
+    }
+
+    /// Stops echoing typed characters on the terminal behind `fd`, lines are still read on enter.
+    ///
+    /// Returns `None` if `fd` is not a terminal.
+    pub fn without_echo(fd: libc::c_int) -> Option<Self> {
+        Self::configure(fd, |mode| mode.c_lflag &= !libc::ECHO)
+    }
+
+    /// Applies `change` to the settings of the terminal behind `fd`.
+    fn configure(fd: libc::c_int, change: impl FnOnce(&mut libc::termios)) -> Option<Self> {
+        // SAFETY: `termios` is a plain C struct, fully initialized by `tcgetattr` before use.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        // SAFETY: `tcgetattr` only writes into the `termios` struct we own.
+        if unsafe { libc::tcgetattr(fd, std::ptr::addr_of_mut!(original)) } != 0 {
+            return None;
+        }
+        let mut mode = original;
+        change(&mut mode);
+        // SAFETY: `tcsetattr` only reads the `termios` struct we own.
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, std::ptr::addr_of!(mode)) } != 0 {
+            return None;
+        }
+        Some(Self { fd, original })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for TtyMode {
+    fn drop(&mut self) {
+        // SAFETY: restores the settings read by `tcgetattr` on the same file descriptor.
+        unsafe {
+            libc::tcsetattr(self.fd, libc::TCSANOW, std::ptr::addr_of!(self.original));
+        }
+    }
+}
+
+/// Writes `query` to the controlling terminal and returns its answer, read until `terminator`
+/// or until the terminal stays silent for `timeout_ds` deciseconds.
+///
+/// Used for terminal queries such as `OSC 11`, returns `None` when there is no controlling terminal.
+#[cfg(unix)]
+pub fn query_tty(query: &str, terminator: u8, timeout_ds: u8) -> Option<Vec<u8>> {
+    use std::io::{Read, Write};
+    use std::os::fd::AsRawFd;
+
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let _mode = TtyMode::non_canonical(tty.as_raw_fd(), false, Some(timeout_ds))?;
+    tty.write_all(query.as_bytes()).ok()?;
+    tty.flush().ok()?;
+
+    let mut answer = Vec::new();
+    let mut byte = [0_u8; 1];
+    while matches!(tty.read(&mut byte), Ok(1)) {
+        answer.push(byte[0]);
+        if byte[0] == terminator || answer.ends_with(b"\x1b\\") || answer.len() > 256 {
+            break;
+        }
+    }
+    (!answer.is_empty()).then_some(answer)
+}
+
+/// Queries the controlling terminal, not supported on this platform.
+#[cfg(not(unix))]
+pub fn query_tty(_query: &str, _terminator: u8, _timeout_ds: u8) -> Option<Vec<u8>> {
+    None
+}
+
+/// Waits until a key is pressed on the controlling terminal, without echoing it.
+///
+/// Returns `None` when there is no controlling terminal.
+#[cfg(unix)]
+pub fn read_key() -> Option<u8> {
+    use std::io::Read;
+    use std::os::fd::AsRawFd;
+
+    let mut tty = std::fs::File::open("/dev/tty").ok()?;
+    let _mode = TtyMode::non_canonical(tty.as_raw_fd(), false, None)?;
+    let mut byte = [0_u8; 1];
+    matches!(tty.read(&mut byte), Ok(1)).then_some(byte[0])
+}
+
+/// Waits until a line is entered on stdin, terminals only send keys on enter on this platform.
+#[cfg(not(unix))]
+pub fn read_key() -> Option<u8> {
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line).ok()?;
+    Some(line.bytes().next().unwrap_or(b'\n'))
+}
+
+/// Reads a line from the controlling terminal without echoing it, without the line terminator.
+///
+/// Falls back to reading a line from stdin, e.g. when it is piped, returns `None` on end of input.
+#[cfg(unix)]
+pub fn read_hidden_line() -> Option<String> {
+    use std::io::BufRead;
+    use std::os::fd::AsRawFd;
+
+    let mut line = String::new();
+    let read = match std::fs::File::open("/dev/tty") {
+        Ok(tty) => {
+            let _mode = TtyMode::without_echo(tty.as_raw_fd());
+            std::io::BufReader::new(tty).read_line(&mut line)
+        }
+        Err(_) => std::io::stdin().read_line(&mut line),
+    };
+    trim_line(read.ok()?, line)
+}
+
+/// Reads a line from the console without echoing it, without the line terminator.
+///
+/// Returns `None` on end of input.
+#[cfg(all(windows, feature = "windows-vt"))]
+pub fn read_hidden_line() -> Option<String> {
+    use winapi::shared::minwindef::DWORD;
+    use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
+    use winapi::um::processenv::GetStdHandle;
+    use winapi::um::winbase::STD_INPUT_HANDLE;
+    use winapi::um::wincon::ENABLE_ECHO_INPUT;
+
+    // SAFETY: `GetStdHandle` has no preconditions, the handle is only used if `GetConsoleMode` accepts it.
+    let handle = unsafe { GetStdHandle(STD_INPUT_HANDLE) };
+    let mut mode: DWORD = 0;
+    // SAFETY: `GetConsoleMode` only writes into `mode`, and fails on invalid or non-console handles.
+    let console = unsafe { GetConsoleMode(handle, std::ptr::addr_of_mut!(mode)) } != 0;
+    if console {
+        // SAFETY: `handle` is a valid console handle.
+        unsafe { SetConsoleMode(handle, mode & !ENABLE_ECHO_INPUT) };
+    }
+    let mut line = String::new();
+    let read = std::io::stdin().read_line(&mut line);
+    if console {
+        // SAFETY: restores the mode read by `GetConsoleMode` on the same handle.
+        unsafe { SetConsoleMode(handle, mode) };
+    }
+    trim_line(read.ok()?, line)
+}
+
+/// Reads a line from stdin, echo cannot be disabled on this platform.
+#[cfg(not(any(unix, all(windows, feature = "windows-vt"))))]
+pub fn read_hidden_line() -> Option<String> {
+    let mut line = String::new();
+    let read = std::io::stdin().read_line(&mut line);
+    trim_line(read.ok()?, line)
+}
+
+/// Returns `line` without its line terminator, `None` if `read` is 0 bytes, at the end of input.
+fn trim_line(read: usize, mut line: String) -> Option<String> {
+    if read == 0 {
+        return None;
+    }
+    let len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(len);
+    Some(line)
+}
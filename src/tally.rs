@@ -0,0 +1,123 @@
+//! The `tally` module counts the whispers emitted per `Level`, to end a program with a fitting exit code.
+//!
+//! Every whisper emitted by `whisper()` is counted. `exit_code()` suggests 1 if an error was
+//! whispered, and the code set with `set_warning_exit_code` if only warnings were, 0 by default.
+//!
+//! ```no_run
+//! use murmur::{IconKind, Whisper};
+//!
+//! Whisper::new().icon(IconKind::NfFaWarning).message("2 tests skipped").whisper().unwrap();
+//! murmur::set_warning_exit_code(2);
+//! std::process::exit(murmur::exit_code());
+//! ```
+//!
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+
+use enum_iterator::cardinality;
+
+use crate::Level;
+
+/// The number of levels.
+const LEVELS: usize = cardinality::<Level>();
+
+/// The number of whispers emitted per level, indexed by the discriminant of the level.
+static COUNTS: [AtomicUsize; LEVELS] = [const { AtomicUsize::new(0) }; LEVELS];
+
+/// The exit code suggested when warnings but no errors were whispered.
+static WARNING_EXIT_CODE: AtomicI32 = AtomicI32::new(0);
+
+/// `Tally` is the number of whispers emitted per `Level`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Tally {
+    /// The number of whispers per level, indexed by the discriminant of the level.
+    counts: [usize; LEVELS],
+}
+
+impl Tally {
+    /// Returns the number of whispers emitted at `level`.
+    #[must_use]
+    pub const fn count(&self, level: Level) -> usize {
+        self.counts[level as usize]
+    }
+
+    /// Returns the number of errors.
+    #[must_use]
+    pub const fn errors(&self) -> usize {
+        self.count(Level::Error)
+    }
+
+    /// Returns the number of warnings.
+    #[must_use]
+    pub const fn warnings(&self) -> usize {
+        self.count(Level::Warn)
+    }
+}
+
+/// Counts a whisper of `level` in the tally.
+pub fn count(level: Level) {
+    COUNTS[level as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns the number of whispers emitted per `Level` since the start or the last `reset_tally`.
+///
+/// # Examples
+/// ```
+/// use murmur::{IconKind, Level, Whisper};
+///
+/// let before = murmur::tally().count(Level::Success);
+/// Whisper::new().icon(IconKind::NfFaCheck).message("counted").whisper().unwrap();
+/// assert!(murmur::tally().count(Level::Success) > before);
+/// ```
+#[must_use]
+pub fn tally() -> Tally {
+    Tally {
+        counts: core::array::from_fn(|index| COUNTS[index].load(Ordering::Relaxed)),
+    }
+}
+
+/// Resets the tally to zero.
+pub fn reset_tally() {
+    for count in &COUNTS {
+        count.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Sets the exit code `exit_code()` suggests when warnings but no errors were whispered, 0 by default.
+pub fn set_warning_exit_code(code: i32) {
+    WARNING_EXIT_CODE.store(code, Ordering::Relaxed);
+}
+
+/// Returns the suggested exit code of the program: 1 if an error was whispered, the code set with
+/// `set_warning_exit_code` if only warnings were, 0 otherwise.
+#[must_use]
+pub fn exit_code() -> i32 {
+    exit_code_for(&tally(), WARNING_EXIT_CODE.load(Ordering::Relaxed))
+}
+
+/// Returns the suggested exit code for `tally`, see `exit_code`.
+const fn exit_code_for(tally: &Tally, warning_exit_code: i32) -> i32 {
+    if tally.errors() > 0 {
+        1
+    } else if tally.warnings() > 0 {
+        warning_exit_code
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tally_tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_for() {
+        let mut tally = Tally::default();
+        assert_eq!(exit_code_for(&tally, 2), 0);
+        tally.counts[Level::Warn as usize] = 3;
+        assert_eq!(exit_code_for(&tally, 2), 2);
+        assert_eq!(exit_code_for(&tally, 0), 0);
+        tally.counts[Level::Error as usize] = 1;
+        assert_eq!(exit_code_for(&tally, 2), 1);
+        assert_eq!(tally.warnings(), 3);
+    }
+}
@@ -0,0 +1,190 @@
+//! The `tasks` module shows the steps of a job with their status, like `cargo` does.
+//!
+//! A `TaskList` prints one line per task, marked with the icon of its `TaskStatus`. On a terminal
+//! the lines are updated in place as tasks run and complete. Elsewhere, e.g. on CI, a task is only
+//! printed once it completes. `TaskList::finish` whispers a summary line.
+//!
+//! ```
+//! use murmur::{TaskList, TaskStatus};
+//!
+//! let mut tasks = TaskList::new();
+//! let build = tasks.push("build");
+//! let test = tasks.push("test");
+//! tasks.set(build, TaskStatus::Running);
+//! tasks.set(build, TaskStatus::Success);
+//! tasks.set(test, TaskStatus::Running);
+//! tasks.set(test, TaskStatus::Failed);
+//! tasks.finish().unwrap();
+//! ```
+//!
+use crate::{cursor, osc, strings, Color, IconKind, Whisper, WhisperError};
+
+/// `TaskStatus` is the status of a task of a `TaskList`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum TaskStatus {
+    /// The task did not start yet.
+    #[default]
+    Pending,
+    /// The task is running.
+    Running,
+    /// The task succeeded.
+    Success,
+    /// The task failed.
+    Failed,
+}
+
+impl TaskStatus {
+    /// Returns the icon of the status.
+    #[must_use]
+    pub const fn icon_kind(self) -> IconKind {
+        match self {
+            Self::Pending => IconKind::NfFaClock,
+            Self::Running => IconKind::NfFaRefresh,
+            Self::Success => IconKind::NfFaCheck,
+            Self::Failed => IconKind::NfFaTimes,
+        }
+    }
+
+    /// Returns whether the task completed, successfully or not.
+    #[must_use]
+    pub const fn is_done(self) -> bool {
+        matches!(self, Self::Success | Self::Failed)
+    }
+}
+
+/// `TaskList` is a list of named tasks printed with their status, see the module documentation.
+#[derive(Debug, Default)]
+pub struct TaskList {
+    /// The name and status of every task, in order.
+    tasks: Vec<(String, TaskStatus)>,
+    /// The number of lines drawn on the terminal, to redraw them in place.
+    drawn: usize,
+}
+
+impl TaskList {
+    /// Creates an empty `TaskList`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            drawn: 0,
+        }
+    }
+
+    /// Adds a pending task named `name`, and returns its index for `set`.
+    pub fn push(&mut self, name: &str) -> usize {
+        self.tasks.push((String::from(name), TaskStatus::Pending));
+        self.draw();
+        self.tasks.len() - 1
+    }
+
+    /// Sets the status of the task at `index`, ignored if there is no such task.
+    pub fn set(&mut self, index: usize, status: TaskStatus) {
+        let Some((name, existing)) = self.tasks.get_mut(index) else {
+            return;
+        };
+        if *existing == status {
+            return;
+        }
+        *existing = status;
+        if osc::enabled() {
+            self.draw();
+        } else if status.is_done() {
+            let _ = task_whisper(name, status).whisper();
+        }
+    }
+
+    /// Returns the status of the task at `index`, if any.
+    #[must_use]
+    pub fn status(&self, index: usize) -> Option<TaskStatus> {
+        self.tasks.get(index).map(|&(_, status)| status)
+    }
+
+    /// Whispers the summary of the tasks, an error if a task failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `Whisper::whisper()`.
+    pub fn finish(self) -> Result<(), WhisperError> {
+        summary(&self.tasks).whisper()
+    }
+
+    /// Redraws every task in place, if stdout is a terminal.
+    fn draw(&mut self) {
+        if !osc::enabled() {
+            return;
+        }
+        let mut output = format!("{}\r{}", cursor::up(self.drawn), cursor::CLEAR_BELOW);
+        let mut drawn = 0;
+        for (name, status) in &self.tasks {
+            if let Ok(rendered) = task_whisper(name, *status).freeze() {
+                drawn += rendered.segments.len();
+                for line in rendered.lines() {
+                    output.push_str(&line);
+                    output.push('\n');
+                }
+            }
+        }
+        if osc::write(&output).is_ok() {
+            self.drawn = drawn;
+        }
+    }
+}
+
+/// Returns the whisper of the task `name` with `status`.
+fn task_whisper(name: &str, status: TaskStatus) -> Whisper {
+    let whisper = Whisper::new().icon(status.icon_kind()).message(name);
+    match status {
+        // Pending tasks are dimmed, so the running and completed ones stand out
+        TaskStatus::Pending => whisper.color(Color::Dimmed),
+        _ => whisper,
+    }
+}
+
+/// Returns the summary whisper of `tasks`.
+fn summary(tasks: &[(String, TaskStatus)]) -> Whisper {
+    let count = |wanted| {
+        tasks
+            .iter()
+            .filter(|&&(_, status)| status == wanted)
+            .count()
+    };
+    let (succeeded, failed) = (count(TaskStatus::Success), count(TaskStatus::Failed));
+    let message = (strings::strings().tasks_summary)(tasks.len(), succeeded, failed);
+    let icon_kind = if failed > 0 {
+        IconKind::NfFaTimes
+    } else {
+        IconKind::NfFaCheck
+    };
+    Whisper::new().icon(icon_kind).message(message)
+}
+
+#[cfg(test)]
+mod tasks_tests {
+    use super::*;
+    use crate::Level;
+
+    #[test]
+    fn test_set_status() {
+        let mut tasks = TaskList::new();
+        let index = tasks.push("tasks_tests");
+        assert_eq!(tasks.status(index), Some(TaskStatus::Pending));
+        tasks.set(index, TaskStatus::Success);
+        tasks.set(7, TaskStatus::Failed);
+        assert_eq!(tasks.status(index), Some(TaskStatus::Success));
+        assert_eq!(tasks.status(7), None);
+    }
+
+    #[test]
+    fn test_summary() {
+        let tasks = [
+            (String::from("build"), TaskStatus::Success),
+            (String::from("test"), TaskStatus::Failed),
+            (String::from("lint"), TaskStatus::Pending),
+        ];
+        let whisper = summary(&tasks);
+        assert_eq!(whisper.severity(), Level::Error);
+        assert_eq!(whisper.messages, ["3 tasks: 1 succeeded, 1 failed"]);
+        assert_eq!(summary(&tasks[..1]).severity(), Level::Success);
+    }
+}
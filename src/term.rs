@@ -0,0 +1,927 @@
+//! The `term` module detects and caches the capabilities of the terminal murmur is writing to.
+//!
+//! Detection happens once, on first use, and looks at whether stdout and stderr are terminals,
+//! the terminal size, the color depth advertised through the environment, hyperlink support
+//! and whether `NerdFonts` are likely to be installed.
+//!
+//! The detected `Capabilities` drive the `Auto` modes of the crate, e.g. `ColorMode::Auto`
+//! only colors whispers when stdout supports colors, and `IconMode::Auto` falls back to ASCII
+//! icons when `TERM=dumb`, on CI without color support, or when the output is not a terminal.
+//! What was decided, and why, is available from `decision()`.
+//!
+//! The terminal size is the exception: users resize the terminal mid-run, so `size()` queries it
+//! again once its cached value is older than [`SIZE_TTL`], and whispers wrap to the new width.
+//!
+//! ```
+//! use murmur::term;
+//!
+//! let capabilities = term::capabilities();
+//! println!("color depth: {:?}", capabilities.color_depth);
+//! println!("width: {}", capabilities.width_or_default());
+//! ```
+//!
+use core::fmt;
+use core::str::FromStr;
+use std::env;
+use std::error::Error;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+pub use crate::theme::Background;
+use crate::{level, sys, Level, Presentation};
+
+/// The width assumed when the terminal width cannot be detected.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// The height assumed when the terminal height cannot be detected.
+pub const DEFAULT_HEIGHT: usize = 24;
+
+/// How long `size()` reuses the size of the terminal before querying it again.
+pub const SIZE_TTL: Duration = Duration::from_millis(250);
+
+/// `Stream` is one of the standard output streams of the process.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Stream {
+    /// The standard output stream.
+    Stdout,
+    /// The standard error stream.
+    Stderr,
+}
+
+/// `ColorDepth` is the number of colors a terminal can display.
+///
+/// The variants are ordered, so `depth >= ColorDepth::Ansi256` checks for at least 256 colors.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// No colors at all.
+    None,
+    /// The 16 basic ANSI colors.
+    Basic,
+    /// The 256 colors ANSI palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+/// `ColorMode` decides whether whispers are colored.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum ColorMode {
+    /// Color whispers when the detected `Capabilities` support colors.
+    #[default]
+    Auto,
+    /// Always color whispers.
+    Always,
+    /// Never color whispers.
+    Never,
+}
+
+/// `IconMode` decides whether icons are printed as glyphs or as ASCII.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum IconMode {
+    /// Print glyphs, unless the detected `Capabilities` call for the ASCII fallback.
+    #[default]
+    Auto,
+    /// Always print glyphs.
+    Glyphs,
+    /// Always print the ASCII fallback of the icons.
+    Ascii,
+}
+
+impl ColorMode {
+    /// Returns the lowercase name of the mode, as parsed by `from_str`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Always => "always",
+            Self::Never => "never",
+        }
+    }
+}
+
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the value of a `--color <auto|always|never>` command line option, with any argument
+/// parser, e.g. `value_parser!(ColorMode)` in clap.
+///
+/// # Examples
+/// ```
+/// use murmur::term::{self, ColorMode};
+///
+/// let color_mode: ColorMode = "never".parse().unwrap();
+/// term::set_color_mode(color_mode);
+/// assert!("sometimes".parse::<ColorMode>().is_err());
+/// # term::set_color_mode(ColorMode::Auto);
+/// ```
+impl FromStr for ColorMode {
+    type Err = ParseModeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_mode(value, [Self::Auto, Self::Always, Self::Never], Self::as_str)
+    }
+}
+
+impl IconMode {
+    /// Returns the lowercase name of the mode, as parsed by `from_str`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Glyphs => "glyphs",
+            Self::Ascii => "ascii",
+        }
+    }
+}
+
+impl fmt::Display for IconMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the value of an `--icons <auto|glyphs|ascii>` command line option.
+impl FromStr for IconMode {
+    type Err = ParseModeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_mode(value, [Self::Auto, Self::Glyphs, Self::Ascii], Self::as_str)
+    }
+}
+
+/// `ParseModeError` is returned when parsing a `ColorMode` or an `IconMode` fails.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ParseModeError {
+    /// The value that could not be parsed.
+    value: String,
+    /// The accepted values, separated by commas.
+    expected: String,
+}
+
+impl fmt::Display for ParseModeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid mode {:?}, expected {}",
+            self.value, self.expected
+        )
+    }
+}
+
+impl Error for ParseModeError {}
+
+/// Returns the mode of `modes` named `value` by `as_str`, ignoring case.
+fn parse_mode<M: Copy, const N: usize>(
+    value: &str,
+    modes: [M; N],
+    as_str: fn(M) -> &'static str,
+) -> Result<M, ParseModeError> {
+    modes
+        .into_iter()
+        .find(|&mode| as_str(mode).eq_ignore_ascii_case(value))
+        .ok_or_else(|| ParseModeError {
+            value: String::from(value),
+            expected: modes.map(as_str).join(", "),
+        })
+}
+
+/// `Reason` explains the `Decision` taken by the `Auto` modes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Reason {
+    /// Stdout is a terminal supporting colors, or colors are forced by `CLICOLOR_FORCE`.
+    Terminal,
+    /// `TERM` is set to `dumb`.
+    DumbTerminal,
+    /// Running on CI without color support.
+    Ci,
+    /// Stdout is redirected to a file or a pipe.
+    NotATerminal,
+    /// Stdout is a terminal, but colors are disabled, e.g. by `NO_COLOR`.
+    NoColor,
+}
+
+/// `Decision` is what murmur decided to print, given the `Capabilities` and the modes.
+///
+/// # Examples
+/// ```
+/// use murmur::term;
+///
+/// let decision = term::decision();
+/// eprintln!("murmur: color={} ascii={} ({:?})", decision.color, decision.ascii_icons, decision.reason);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Decision {
+    /// Whether whispers are colored.
+    pub color: bool,
+    /// Whether icons are replaced by their ASCII fallback.
+    pub ascii_icons: bool,
+    /// Whether `Nf` icons are printed as glyphs, instead of their `Unicode` counterpart.
+    pub nerd_fonts: bool,
+    /// Why the `Auto` modes decided so.
+    pub reason: Reason,
+}
+
+impl Decision {
+    /// Takes the decision for `capabilities` in the given modes.
+    #[must_use]
+    pub const fn new(
+        capabilities: &Capabilities,
+        color_mode: ColorMode,
+        icon_mode: IconMode,
+    ) -> Self {
+        let reason = capabilities.reason();
+        let color = match color_mode {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => !matches!(capabilities.color_depth, ColorDepth::None),
+        };
+        let ascii_icons = match icon_mode {
+            IconMode::Glyphs => false,
+            IconMode::Ascii => true,
+            IconMode::Auto => !matches!(reason, Reason::Terminal | Reason::NoColor),
+        };
+        Self {
+            color,
+            ascii_icons,
+            nerd_fonts: true,
+            reason,
+        }
+    }
+}
+
+/// `Capabilities` describes what the terminal attached to the process supports.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Capabilities {
+    /// Whether stdout is a terminal.
+    pub stdout_tty: bool,
+    /// Whether stderr is a terminal.
+    pub stderr_tty: bool,
+    /// The width of the terminal in columns, if it could be detected.
+    pub width: Option<usize>,
+    /// The height of the terminal in rows, if it could be detected.
+    pub height: Option<usize>,
+    /// The number of colors stdout can display.
+    pub color_depth: ColorDepth,
+    /// Whether the terminal supports `OSC 8` hyperlinks.
+    pub hyperlinks: bool,
+    /// Whether `TERM` is set to `dumb`.
+    pub dumb: bool,
+    /// Whether the process runs on CI, i.e. `CI` is set.
+    pub ci: bool,
+}
+
+impl Capabilities {
+    /// Detects the capabilities of the current terminal.
+    ///
+    /// Prefer [`capabilities`], which detects once and caches the result.
+    #[must_use]
+    pub fn detect() -> Self {
+        let (width, height) = sys::terminal_size()
+            .or_else(|| Some((env_usize("COLUMNS")?, env_usize("LINES")?)))
+            .unzip();
+        Self::from_env(
+            |key| env::var(key).ok(),
+            std::io::stdout().is_terminal(),
+            std::io::stderr().is_terminal(),
+            width,
+            height,
+        )
+    }
+
+    /// Builds `Capabilities` from an environment lookup function and the stream states.
+    ///
+    /// This is the pure part of [`Capabilities::detect`].
+    fn from_env<F>(
+        var: F,
+        stdout_tty: bool,
+        stderr_tty: bool,
+        width: Option<usize>,
+        height: Option<usize>,
+    ) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let color_depth = detect_color_depth(&var, stdout_tty);
+        let hyperlinks = stdout_tty && detect_hyperlinks(&var);
+        let dumb = var("TERM").is_some_and(|term| term == "dumb");
+        let ci = is_truthy(var("CI"));
+        Self {
+            stdout_tty,
+            stderr_tty,
+            width,
+            height,
+            color_depth,
+            hyperlinks,
+            dumb,
+            ci,
+        }
+    }
+
+    /// Returns the `Reason` for the decisions of the `Auto` modes.
+    #[must_use]
+    pub const fn reason(&self) -> Reason {
+        let no_color = matches!(self.color_depth, ColorDepth::None);
+        if self.dumb {
+            Reason::DumbTerminal
+        } else if !no_color {
+            Reason::Terminal
+        } else if self.ci {
+            Reason::Ci
+        } else if !self.stdout_tty {
+            Reason::NotATerminal
+        } else {
+            Reason::NoColor
+        }
+    }
+
+    /// Returns whether `stream` is a terminal.
+    #[must_use]
+    pub const fn is_tty(&self, stream: Stream) -> bool {
+        match stream {
+            Stream::Stdout => self.stdout_tty,
+            Stream::Stderr => self.stderr_tty,
+        }
+    }
+
+    /// Returns the detected width, or [`DEFAULT_WIDTH`] if it is unknown.
+    #[must_use]
+    pub fn width_or_default(&self) -> usize {
+        self.width.unwrap_or(DEFAULT_WIDTH)
+    }
+
+    /// Returns the detected height, or [`DEFAULT_HEIGHT`] if it is unknown.
+    #[must_use]
+    pub fn height_or_default(&self) -> usize {
+        self.height.unwrap_or(DEFAULT_HEIGHT)
+    }
+}
+
+/// The cached `Capabilities` of the terminal.
+static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+
+/// The current `IconMode`, stored as its discriminant.
+static ICON_MODE: AtomicU8 = AtomicU8::new(IconMode::Auto as u8);
+
+/// The current `Presentation`, stored as its discriminant.
+static PRESENTATION: AtomicU8 = AtomicU8::new(Presentation::Emoji as u8);
+
+/// The last size returned by `size()`, with the instant it was queried.
+static SIZE: Mutex<Option<(Instant, (usize, usize))>> = Mutex::new(None);
+
+/// Whether the console processes ANSI escape sequences, cached on first print.
+static VIRTUAL_TERMINAL: OnceLock<bool> = OnceLock::new();
+
+/// Whether `NerdFonts` are likely available, detected on first use.
+static NERD_FONTS: OnceLock<bool> = OnceLock::new();
+
+/// The cached `Background` of the terminal.
+static BACKGROUND: OnceLock<Option<Background>> = OnceLock::new();
+
+/// The current `ColorMode`, stored as its discriminant.
+static COLOR_MODE: AtomicU8 = AtomicU8::new(ColorMode::Auto as u8);
+
+/// Whether deterministic output was requested with `set_deterministic`.
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+
+/// Whether `MURMUR_DETERMINISTIC` is set, cached on first use.
+static DETERMINISTIC_ENV: OnceLock<bool> = OnceLock::new();
+
+/// Returns the capabilities of the terminal, detecting them on first use.
+///
+/// # Examples
+/// ```
+/// use murmur::term::{self, Stream};
+///
+/// if term::capabilities().is_tty(Stream::Stdout) {
+///     println!("writing to a terminal");
+/// }
+/// ```
+pub fn capabilities() -> &'static Capabilities {
+    CAPABILITIES.get_or_init(Capabilities::detect)
+}
+
+/// Returns whether `NerdFonts` are likely to be installed and used by the terminal, detected once.
+///
+/// `MURMUR_NERD_FONT` set to `1` or `0` overrides the detection. Without `NerdFonts`, the `Auto`
+/// icon mode prints the `Nf` icons as their `Unicode` counterpart, see `Decision::nerd_fonts`.
+#[must_use]
+pub fn nerd_fonts() -> bool {
+    *NERD_FONTS.get_or_init(|| detect_nerd_fonts(&|name: &str| env::var(name).ok()))
+}
+
+/// Returns the current size of the terminal as `(width, height)`, following resizes.
+///
+/// The size is queried again once the last one is older than [`SIZE_TTL`], so wrapping and status
+/// lines adapt when the terminal is resized without a system call per whisper. Without a terminal,
+/// the size detected by `capabilities()`, or [`DEFAULT_WIDTH`] and [`DEFAULT_HEIGHT`], is returned.
+///
+/// # Examples
+/// ```
+/// use murmur::term;
+///
+/// let (width, height) = term::size();
+/// assert!(width > 0 && height > 0);
+/// ```
+#[must_use]
+pub fn size() -> (usize, usize) {
+    let now = Instant::now();
+    let Ok(mut cached) = SIZE.lock() else {
+        return query_size();
+    };
+    match *cached {
+        Some((queried, size)) if now.duration_since(queried) < SIZE_TTL => size,
+        _ => {
+            let size = query_size();
+            *cached = Some((now, size));
+            size
+        }
+    }
+}
+
+/// Queries the size of the terminal, falling back to the size of the `Capabilities`.
+fn query_size() -> (usize, usize) {
+    sys::terminal_size().unwrap_or_else(|| {
+        let capabilities = capabilities();
+        (
+            capabilities.width_or_default(),
+            capabilities.height_or_default(),
+        )
+    })
+}
+
+/// Sets the global `ColorMode`, `ColorMode::Auto` by default.
+///
+/// # Examples
+/// ```
+/// use murmur::term::{self, ColorMode};
+///
+/// term::set_color_mode(ColorMode::Never);
+/// assert!(!term::color_enabled());
+/// term::set_color_mode(ColorMode::Auto);
+/// ```
+pub fn set_color_mode(color_mode: ColorMode) {
+    COLOR_MODE.store(color_mode as u8, Ordering::Relaxed);
+}
+
+/// Returns the global `ColorMode`.
+#[must_use]
+pub fn color_mode() -> ColorMode {
+    match COLOR_MODE.load(Ordering::Relaxed) {
+        x if x == ColorMode::Always as u8 => ColorMode::Always,
+        x if x == ColorMode::Never as u8 => ColorMode::Never,
+        _ => ColorMode::Auto,
+    }
+}
+
+/// Sets the global `IconMode`, `IconMode::Auto` by default.
+///
+/// # Examples
+/// ```
+/// use murmur::term::{self, IconMode};
+///
+/// term::set_icon_mode(IconMode::Ascii);
+/// assert!(term::decision().ascii_icons);
+/// term::set_icon_mode(IconMode::Auto);
+/// ```
+pub fn set_icon_mode(icon_mode: IconMode) {
+    ICON_MODE.store(icon_mode as u8, Ordering::Relaxed);
+}
+
+/// Returns the global `IconMode`.
+#[must_use]
+pub fn icon_mode() -> IconMode {
+    match ICON_MODE.load(Ordering::Relaxed) {
+        x if x == IconMode::Glyphs as u8 => IconMode::Glyphs,
+        x if x == IconMode::Ascii as u8 => IconMode::Ascii,
+        _ => IconMode::Auto,
+    }
+}
+
+/// Applies the color mode, icon mode and verbosity parsed from the command line, see
+/// `set_color_mode`, `set_icon_mode` and `set_verbosity`.
+///
+/// # Examples
+/// ```
+/// use murmur::term::{self, ColorMode, IconMode};
+/// use murmur::Level;
+///
+/// // E.g. the values of `--color`, `--icons` and `--quiet` parsed by clap
+/// let (color, icons, quiet) = ("never", "ascii", true);
+/// let verbosity = if quiet { Level::Warn } else { Level::Info };
+/// term::apply(color.parse().unwrap(), icons.parse().unwrap(), verbosity);
+/// assert_eq!(term::color_mode(), ColorMode::Never);
+/// assert_eq!(term::icon_mode(), IconMode::Ascii);
+/// assert_eq!(murmur::verbosity(), Level::Warn);
+/// # term::apply(ColorMode::Auto, IconMode::Auto, Level::Trace);
+/// ```
+pub fn apply(color: ColorMode, icons: IconMode, verbosity: Level) {
+    set_color_mode(color);
+    set_icon_mode(icons);
+    level::set_verbosity(verbosity);
+}
+
+/// Sets the global `Presentation` of icon glyphs, `Presentation::Emoji` by default.
+///
+/// Terminals that print `U+FE0F` emoji in 1 column misalign the messages following Unicode icons,
+/// `Presentation::Text` or `Presentation::Strip` print them in the same width everywhere.
+///
+/// # Examples
+/// ```
+/// use murmur::{term, IconKind, Presentation, RenderOptions, Whisper};
+///
+/// term::set_presentation(Presentation::Strip);
+/// let options = RenderOptions { color: false, ascii_icons: false, ..RenderOptions::detect().unwrap() };
+/// let lines = Whisper::new().icon(IconKind::UnicodeWarningSign).message("slow").render(&options);
+/// # #[cfg(not(feature = "plain"))]
+/// assert_eq!(lines, ["\u{26a0} slow"]);
+/// term::set_presentation(Presentation::Emoji);
+/// ```
+pub fn set_presentation(presentation: Presentation) {
+    PRESENTATION.store(presentation as u8, Ordering::Relaxed);
+}
+
+/// Returns the global `Presentation`.
+#[must_use]
+pub fn presentation() -> Presentation {
+    match PRESENTATION.load(Ordering::Relaxed) {
+        x if x == Presentation::Text as u8 => Presentation::Text,
+        x if x == Presentation::Strip as u8 => Presentation::Strip,
+        _ => Presentation::Emoji,
+    }
+}
+
+/// Returns whether the console processes ANSI escape sequences.
+///
+/// On Windows, with the `windows-vt` feature, the first call enables virtual terminal processing
+/// on the console so colors work in `cmd.exe` and old `PowerShell` hosts. If that fails,
+/// `ColorMode::Auto` falls back to no color. Always `true` on other platforms.
+pub fn virtual_terminal() -> bool {
+    *VIRTUAL_TERMINAL.get_or_init(sys::enable_virtual_terminal)
+}
+
+/// Makes the output independent of the machine, e.g. for snapshot tests, `false` by default.
+///
+/// In deterministic mode, the `Auto` modes print glyphs without color, whispers are laid out
+/// for [`DEFAULT_WIDTH`] columns, and transcripts record every whisper at time 0. Setting the
+/// `MURMUR_DETERMINISTIC` environment variable to `1` turns it on too.
+///
+/// # Examples
+/// ```
+/// use murmur::term;
+///
+/// term::set_deterministic(true);
+/// assert!(!term::decision().color);
+/// term::set_deterministic(false);
+/// ```
+pub fn set_deterministic(deterministic: bool) {
+    DETERMINISTIC.store(deterministic, Ordering::Relaxed);
+}
+
+/// Returns whether the output is deterministic, see `set_deterministic`.
+#[must_use]
+pub fn deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed)
+        || *DETERMINISTIC_ENV.get_or_init(|| is_truthy(env::var("MURMUR_DETERMINISTIC").ok()))
+}
+
+/// Returns what murmur decided to print, according to the modes and the `Capabilities`.
+#[must_use]
+pub fn decision() -> Decision {
+    let mut decision = Decision::new(capabilities(), color_mode(), icon_mode());
+    if deterministic() {
+        decision.color &= color_mode() == ColorMode::Always;
+        decision.ascii_icons &= icon_mode() == IconMode::Ascii;
+        return decision;
+    }
+    if decision.color && color_mode() == ColorMode::Auto && capabilities().stdout_tty {
+        decision.color = virtual_terminal();
+    }
+    // The font directories are only probed when glyphs are printed
+    if icon_mode() == IconMode::Auto && !decision.ascii_icons {
+        decision.nerd_fonts = nerd_fonts();
+    }
+    decision
+}
+
+/// Returns whether whispers should be colored, according to the `ColorMode` and the `Capabilities`.
+#[must_use]
+pub fn color_enabled() -> bool {
+    decision().color
+}
+
+/// Returns the background of the terminal, detecting it on first use.
+///
+/// The detection tries, in order:
+/// 1. the `MURMUR_BACKGROUND` environment variable, set to `light` or `dark`,
+/// 2. an `OSC 11` query, answered by most terminals with their background color,
+/// 3. the `COLORFGBG` environment variable set by rxvt, konsole and others.
+///
+/// Returns `None` if the background could not be detected.
+///
+/// # Examples
+/// ```
+/// use murmur::term::{self, Background};
+///
+/// if term::background() == Some(Background::Light) {
+///     println!("light terminal detected");
+/// }
+/// ```
+pub fn background() -> Option<Background> {
+    *BACKGROUND.get_or_init(|| {
+        let var = |key: &str| env::var(key).ok();
+        if let Some(background) = var("MURMUR_BACKGROUND") {
+            return parse_background_name(&background);
+        }
+        let capabilities = capabilities();
+        let osc_11 = (capabilities.stdout_tty && capabilities.color_depth > ColorDepth::None)
+            .then(|| sys::query_tty("\x1b]11;?\x07", b'\x07', 1))
+            .flatten()
+            .and_then(|answer| parse_osc_11(&String::from_utf8_lossy(&answer)));
+        osc_11.or_else(|| parse_colorfgbg(&var("COLORFGBG")?))
+    })
+}
+
+/// Parses a `light` or `dark` background name.
+fn parse_background_name(name: &str) -> Option<Background> {
+    match name.trim().to_lowercase().as_str() {
+        "light" => Some(Background::Light),
+        "dark" => Some(Background::Dark),
+        _ => None,
+    }
+}
+
+/// Parses an `OSC 11` answer such as `ESC ] 11 ; rgb:ffff/ffff/ffff BEL`.
+fn parse_osc_11(answer: &str) -> Option<Background> {
+    let rgb = answer.split("rgb:").nth(1)?;
+    let rgb = rgb.trim_end_matches(['\x07', '\\', '\x1b']);
+    let channels = rgb
+        .split('/')
+        .map(|hex| {
+            let max = 16_f64.powi(i32::try_from(hex.len()).ok()?) - 1.0;
+            Some(f64::from(u16::from_str_radix(hex, 16).ok()?) / max)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let [red, green, blue] = channels[..] else {
+        return None;
+    };
+    let luminance = 0.0722_f64.mul_add(blue, 0.2126_f64.mul_add(red, 0.7152 * green));
+    Some(if luminance > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+/// Parses `COLORFGBG`, `fg;bg` or `fg;default;bg`, where `bg` is an ANSI color index.
+fn parse_colorfgbg(colorfgbg: &str) -> Option<Background> {
+    let background: u8 = colorfgbg.rsplit(';').next()?.trim().parse().ok()?;
+    Some(match background {
+        7 | 9..=15 => Background::Light,
+        _ => Background::Dark,
+    })
+}
+
+/// Parses an environment variable as a `usize`.
+fn env_usize(key: &str) -> Option<usize> {
+    env::var(key).ok()?.trim().parse().ok()
+}
+
+/// Returns whether an environment variable is set to a non-empty value other than `0`.
+fn is_truthy(value: Option<String>) -> bool {
+    value.is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Detects the color depth following the `NO_COLOR` and `CLICOLOR_FORCE` conventions.
+fn detect_color_depth<F>(var: &F, stdout_tty: bool) -> ColorDepth
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return ColorDepth::None;
+    }
+    let forced = is_truthy(var("CLICOLOR_FORCE"));
+    let term = var("TERM").unwrap_or_default();
+    if !forced && (!stdout_tty || term == "dumb") {
+        return ColorDepth::None;
+    }
+    let colorterm = var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Basic
+    }
+}
+
+/// Detects `OSC 8` hyperlink support from the terminal identification variables.
+fn detect_hyperlinks<F>(var: &F) -> bool
+where
+    F: Fn(&str) -> Option<String>,
+{
+    /// Terminals known to support hyperlinks, as reported by `TERM_PROGRAM`.
+    const PROGRAMS: [&str; 5] = ["iTerm.app", "WezTerm", "vscode", "ghostty", "Hyper"];
+
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+    let term = var("TERM").unwrap_or_default();
+    PROGRAMS.contains(&term_program.as_str())
+        || var("WT_SESSION").is_some()
+        || var("KONSOLE_VERSION").is_some()
+        || var("VTE_VERSION")
+            .and_then(|version| version.parse::<u32>().ok())
+            .is_some_and(|version| version >= 5000)
+        || term.contains("kitty")
+        || term.contains("alacritty")
+        || term.contains("foot")
+}
+
+/// Detects whether `NerdFonts` are likely available.
+///
+/// `MURMUR_NERD_FONT` overrides the detection, otherwise terminals that bundle the `NerdFont`
+/// symbols are trusted and the usual font directories are probed for installed `NerdFonts`.
+fn detect_nerd_fonts<F>(var: &F) -> bool
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if let Some(value) = var("MURMUR_NERD_FONT") {
+        return is_truthy(Some(value));
+    }
+    if var("TERM_PROGRAM").is_some_and(|program| program == "WezTerm") {
+        return true;
+    }
+    let home = var("HOME")
+        .or_else(|| var("USERPROFILE"))
+        .map(PathBuf::from);
+    let mut dirs: Vec<PathBuf> = [
+        "/usr/share/fonts",
+        "/usr/local/share/fonts",
+        "/Library/Fonts",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect();
+    if let Some(home) = home {
+        dirs.push(home.join(".local/share/fonts"));
+        dirs.push(home.join(".fonts"));
+        dirs.push(home.join("Library/Fonts"));
+    }
+    if let Some(local_app_data) = var("LOCALAPPDATA") {
+        dirs.push(PathBuf::from(local_app_data).join("Microsoft/Windows/Fonts"));
+    }
+    if let Some(windir) = var("WINDIR") {
+        dirs.push(PathBuf::from(windir).join("Fonts"));
+    }
+    dirs.iter().any(|dir| contains_nerd_font(dir, 2))
+}
+
+/// Returns whether `dir`, or one of its subdirectories up to `depth`, contains a `NerdFont` file.
+fn contains_nerd_font(dir: &Path, depth: usize) -> bool {
+    let Ok(entries) = dir.read_dir() else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            return depth > 0 && contains_nerd_font(&path, depth - 1);
+        }
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        name.contains("nerd font") || name.contains("nerdfont") || name.contains("nerd-font")
+    })
+}
+
+#[cfg(test)]
+mod term_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_modes() {
+        assert_eq!("Always".parse(), Ok(ColorMode::Always));
+        assert_eq!("ascii".parse(), Ok(IconMode::Ascii));
+        for color_mode in [ColorMode::Auto, ColorMode::Always, ColorMode::Never] {
+            assert_eq!(color_mode.to_string().parse(), Ok(color_mode));
+        }
+        let error = "sometimes".parse::<ColorMode>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"invalid mode "sometimes", expected auto, always, never"#
+        );
+    }
+
+    fn capabilities_with(vars: &[(&str, &str)], stdout_tty: bool) -> Capabilities {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+            .collect();
+        Capabilities::from_env(|key| vars.get(key).cloned(), stdout_tty, false, None, None)
+    }
+
+    #[test]
+    fn test_color_depth_detection() {
+        let depth = |vars: &[(&str, &str)], tty| capabilities_with(vars, tty).color_depth;
+        assert_eq!(depth(&[("TERM", "xterm")], true), ColorDepth::Basic);
+        assert_eq!(
+            depth(&[("TERM", "xterm-256color")], true),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            depth(&[("COLORTERM", "truecolor")], true),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(depth(&[("TERM", "xterm")], false), ColorDepth::None);
+        assert_eq!(depth(&[("TERM", "dumb")], true), ColorDepth::None);
+        assert_eq!(depth(&[("NO_COLOR", "1")], true), ColorDepth::None);
+        assert_eq!(depth(&[("CLICOLOR_FORCE", "1")], false), ColorDepth::Basic);
+    }
+
+    #[test]
+    fn test_hyperlinks_detection() {
+        assert!(capabilities_with(&[("TERM_PROGRAM", "WezTerm")], true).hyperlinks);
+        assert!(capabilities_with(&[("VTE_VERSION", "6003")], true).hyperlinks);
+        assert!(!capabilities_with(&[("VTE_VERSION", "4000")], true).hyperlinks);
+        assert!(!capabilities_with(&[("TERM_PROGRAM", "WezTerm")], false).hyperlinks);
+    }
+
+    #[test]
+    fn test_nerd_font_override() {
+        let detect = |vars: &[(&str, &str)]| {
+            let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+            detect_nerd_fonts(&|key: &str| vars.get(key).map(|value| (*value).to_string()))
+        };
+        assert!(detect(&[("MURMUR_NERD_FONT", "1")]));
+        assert!(detect(&[("TERM_PROGRAM", "WezTerm")]));
+        assert!(!detect(&[
+            ("MURMUR_NERD_FONT", "0"),
+            ("TERM_PROGRAM", "WezTerm")
+        ]));
+    }
+
+    #[test]
+    fn test_background_parsing() {
+        assert_eq!(parse_background_name("Light"), Some(Background::Light));
+        assert_eq!(parse_background_name("dark"), Some(Background::Dark));
+        assert_eq!(parse_background_name("blue"), None);
+        assert_eq!(
+            parse_osc_11("\x1b]11;rgb:ffff/ffff/ffff\x07"),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            parse_osc_11("\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\"),
+            Some(Background::Dark)
+        );
+        assert_eq!(parse_osc_11("garbage"), None);
+        assert_eq!(parse_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(parse_colorfgbg("15;default;0"), Some(Background::Dark));
+        assert_eq!(parse_colorfgbg("15;default"), None);
+    }
+
+    #[test]
+    fn test_decision() {
+        let decide = |vars: &[(&str, &str)], tty, color_mode, icon_mode| {
+            Decision::new(&capabilities_with(vars, tty), color_mode, icon_mode)
+        };
+        let auto = |vars: &[(&str, &str)], tty| decide(vars, tty, ColorMode::Auto, IconMode::Auto);
+
+        let terminal = auto(&[("TERM", "xterm")], true);
+        assert!(terminal.color && !terminal.ascii_icons);
+        assert_eq!(terminal.reason, Reason::Terminal);
+
+        let dumb = auto(&[("TERM", "dumb")], true);
+        assert!(!dumb.color && dumb.ascii_icons);
+        assert_eq!(dumb.reason, Reason::DumbTerminal);
+
+        let ci = auto(&[("CI", "true")], false);
+        assert!(!ci.color && ci.ascii_icons);
+        assert_eq!(ci.reason, Reason::Ci);
+
+        let file = auto(&[("TERM", "xterm")], false);
+        assert!(!file.color && file.ascii_icons);
+        assert_eq!(file.reason, Reason::NotATerminal);
+
+        let no_color = auto(&[("NO_COLOR", "1")], true);
+        assert!(!no_color.color && !no_color.ascii_icons);
+
+        let forced = decide(
+            &[("TERM", "dumb")],
+            false,
+            ColorMode::Always,
+            IconMode::Glyphs,
+        );
+        assert!(forced.color && !forced.ascii_icons);
+    }
+
+    #[test]
+    fn test_size_defaults() {
+        let capabilities = capabilities_with(&[], false);
+        assert_eq!(capabilities.width_or_default(), DEFAULT_WIDTH);
+        assert_eq!(capabilities.height_or_default(), DEFAULT_HEIGHT);
+        assert!(!capabilities.is_tty(Stream::Stdout));
+    }
+}
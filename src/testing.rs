@@ -0,0 +1,277 @@
+//! The `testing` module provides assertions for the output of whispers in tests.
+//!
+//! `assert_whisper_eq!` compares the lines of a whisper with the expected lines and fails with a
+//! line by line diff, `assert_whisper_contains!` checks that the whisper contains a text. Both take
+//! a `Whisper`, rendered without color, or a `RenderedWhisper` captured with `murmur::recent()`.
+//!
+//! ```
+//! use murmur::{assert_whisper_contains, assert_whisper_eq, IconKind, RenderOptions, Whisper};
+//!
+//! let whisper = Whisper::new().icon(IconKind::NfFaCheck).messages(["built", "3 crates"]);
+//! # #[cfg(not(feature = "plain"))]
+//! assert_whisper_eq!(whisper, ["\u{f00c} built", "  3 crates"]);
+//! assert_whisper_contains!(whisper, "3 crates");
+//!
+//! // Rendered with other options, e.g. to compare colored lines
+//! let options = RenderOptions { ascii_icons: true, ..RenderOptions::plain() };
+//! assert_whisper_eq!(whisper, ["v built", "  3 crates"], &options);
+//! ```
+//!
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(target_has_atomic = "64")]
+use core::time::Duration;
+
+#[cfg(target_has_atomic = "64")]
+use crate::Clock;
+use crate::{RenderOptions, RenderedWhisper, Whisper};
+
+/// `WhisperLines` is implemented by what the assertion macros accept, and returns its uncolored lines.
+pub trait WhisperLines {
+    /// Returns the lines without color.
+    fn plain_lines(&self) -> Vec<String>;
+}
+
+impl WhisperLines for Whisper {
+    fn plain_lines(&self) -> Vec<String> {
+        self.render(&RenderOptions::plain())
+    }
+}
+
+impl WhisperLines for RenderedWhisper {
+    fn plain_lines(&self) -> Vec<String> {
+        Self::plain_lines(self)
+    }
+}
+
+impl<T: WhisperLines + ?Sized> WhisperLines for &T {
+    fn plain_lines(&self) -> Vec<String> {
+        (**self).plain_lines()
+    }
+}
+
+/// Asserts that a whisper renders to the expected lines.
+///
+/// The whisper is a `Whisper` or a `RenderedWhisper`, compared without color. A `Whisper` is
+/// rendered with the `RenderOptions` given as third argument, e.g. to compare colored lines.
+///
+/// On failure, the lines are printed as a diff, `-` for expected lines and `+` for actual lines.
+///
+/// # Examples
+/// ```
+/// use murmur::{assert_whisper_eq, Whisper};
+///
+/// assert_whisper_eq!(Whisper::new().messages(["first", "second"]), ["first", "  second"]);
+/// ```
+#[macro_export]
+macro_rules! assert_whisper_eq {
+    ($whisper:expr, $expected:expr $(,)?) => {
+        $crate::testing::assert_lines_eq(
+            &$crate::testing::WhisperLines::plain_lines(&$whisper),
+            $expected,
+        )
+    };
+    ($whisper:expr, $expected:expr, $options:expr $(,)?) => {
+        $crate::testing::assert_lines_eq(&$whisper.render($options), $expected)
+    };
+}
+
+/// Asserts that the uncolored lines of a whisper contain a text.
+///
+/// # Examples
+/// ```
+/// use murmur::{assert_whisper_contains, Whisper};
+///
+/// assert_whisper_contains!(Whisper::new().message("3 tests passed"), "passed");
+/// ```
+#[macro_export]
+macro_rules! assert_whisper_contains {
+    ($whisper:expr, $needle:expr $(,)?) => {
+        $crate::testing::assert_lines_contain(
+            &$crate::testing::WhisperLines::plain_lines(&$whisper),
+            $needle,
+        )
+    };
+}
+
+/// Panics with a diff if `actual` differs from `expected`, see `assert_whisper_eq!`.
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_lines_eq<I, S>(actual: &[String], expected: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let expected = expected
+        .into_iter()
+        .map(|line| String::from(line.as_ref()))
+        .collect::<Vec<_>>();
+    assert!(
+        actual == expected.as_slice(),
+        "whisper lines differ\n{}",
+        diff(&expected, actual)
+    );
+}
+
+/// Panics if no line of `actual` contains `needle`, see `assert_whisper_contains!`.
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_lines_contain(actual: &[String], needle: &str) {
+    if !actual.join("\n").contains(needle) {
+        let lines = actual.iter().fold(String::new(), |mut lines, line| {
+            let _ = writeln!(lines, "  {}", line.escape_debug());
+            lines
+        });
+        panic!(
+            "whisper does not contain `{}`\n{lines}",
+            needle.escape_debug()
+        );
+    }
+}
+
+/// `FakeClock` is a `Clock` that only moves when told to, for deterministic timestamps and
+/// durations in tests, see the `clock` module.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use murmur::testing::FakeClock;
+/// use murmur::{Clock, Whisper};
+///
+/// let clock = FakeClock::new().at(Duration::from_secs(13 * 3600));
+/// clock.advance(Duration::from_secs(61));
+/// murmur::assert_whisper_eq!(Whisper::new().message("ready").timestamp_from(&clock), ["13:01:01 ready"]);
+/// ```
+#[cfg(target_has_atomic = "64")]
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    /// The monotonic time, in nanoseconds.
+    now: AtomicU64,
+    /// The wall-clock time at the origin, in nanoseconds since the Unix epoch, `None` if unknown.
+    epoch: Option<u64>,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl FakeClock {
+    /// Creates a `FakeClock` at its origin, without a wall-clock time.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the wall-clock time of the origin, as the duration since the Unix epoch.
+    #[must_use]
+    pub fn at(mut self, since_epoch: Duration) -> Self {
+        self.epoch = Some(nanos(since_epoch));
+        self
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.now.fetch_add(nanos(duration), Ordering::Relaxed);
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.now.load(Ordering::Relaxed))
+    }
+
+    fn since_epoch(&self) -> Option<Duration> {
+        self.epoch
+            .map(|epoch| Duration::from_nanos(epoch) + self.now())
+    }
+}
+
+/// Returns `duration` in nanoseconds, saturated to `u64::MAX`.
+#[cfg(target_has_atomic = "64")]
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Returns a line by line diff of `expected` and `actual`, control characters escaped.
+fn diff(expected: &[String], actual: &[String]) -> String {
+    let mut diff = String::new();
+    for index in 0..expected.len().max(actual.len()) {
+        match (expected.get(index), actual.get(index)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                let _ = writeln!(diff, "  {}", actual.escape_debug());
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    let _ = writeln!(diff, "- {}", expected.escape_debug());
+                }
+                if let Some(actual) = actual {
+                    let _ = writeln!(diff, "+ {}", actual.escape_debug());
+                }
+            }
+        }
+    }
+    format!("{diff}(- expected, + actual)")
+}
+
+#[cfg(test)]
+mod testing_tests {
+    use super::*;
+    use std::panic;
+
+    /// Returns the panic message of `f`.
+    fn panic_message(f: impl FnOnce() + panic::UnwindSafe) -> String {
+        let payload = panic::catch_unwind(f).unwrap_err();
+        payload.downcast_ref::<String>().unwrap().clone()
+    }
+
+    #[test]
+    fn test_assert_whisper_eq() {
+        let whisper = Whisper::new().messages(["first", "second"]);
+        assert_whisper_eq!(whisper, ["first", "  second"]);
+        assert_whisper_eq!(
+            RenderedWhisper::new(&whisper, &RenderOptions::default()),
+            vec!["first", "  second"]
+        );
+        assert_whisper_eq!(whisper, ["first", "  second"], &RenderOptions::plain());
+    }
+
+    #[test]
+    fn test_fake_clock_durations() {
+        let clock = FakeClock::new();
+        assert_eq!(clock.since_epoch(), None);
+        let start = clock.now();
+        clock.advance(Duration::from_millis(61_250));
+        assert_whisper_eq!(
+            Whisper::new()
+                .message("done")
+                .timestamp_relative_from(start, &clock),
+            ["  +61.2s done"]
+        );
+        assert_whisper_eq!(Whisper::new().timestamp_from(&clock), ["00:00:00 "]);
+    }
+
+    #[test]
+    fn test_assert_whisper_eq_diff() {
+        let message = panic_message(|| {
+            assert_whisper_eq!(
+                Whisper::new().messages(["same", "actual\t"]),
+                ["same", "expected", "missing"]
+            );
+        });
+        assert_eq!(
+            message,
+            "whisper lines differ\n  same\n- expected\n+   actual\\t\n- missing\n(- expected, + actual)"
+        );
+    }
+
+    #[test]
+    fn test_assert_whisper_contains() {
+        assert_whisper_contains!(Whisper::new().messages(["a", "b c"]), "a\n  b");
+        let message = panic_message(|| {
+            assert_whisper_contains!(Whisper::new().message("a"), "b");
+        });
+        assert_eq!(message, "whisper does not contain `b`\n  a\n");
+    }
+}
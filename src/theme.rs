@@ -0,0 +1,178 @@
+//! The `theme` module lets the colors of the icons adapt to the terminal background.
+//!
+//! The default colors of the icons are chosen for dark backgrounds, on light backgrounds `white`
+//! icons are invisible. A `Theme` remaps those colors, either for a known background or for the
+//! background detected by `term::background()`, and can recolor whole icon categories at once.
+//!
+//! ```
+//! use murmur::{Category, Color, Theme};
+//!
+//! murmur::set_theme(Theme::adaptive().category_color(Category::Vcs, Color::Magenta));
+//! ```
+//!
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+#[cfg(feature = "std")]
+use crate::term;
+use crate::{Category, Color, IconKind};
+
+/// `Background` is the brightness of the terminal background.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Background {
+    /// A dark background, the colors of the icons are chosen for it.
+    Dark,
+    /// A light background, on which `White` text is invisible.
+    Light,
+}
+
+/// `Theme` decides the color a whisper is printed with.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Theme {
+    /// The background the colors are chosen for.
+    background: Background,
+    /// Colors overriding the default color of every icon in a category.
+    category_colors: Vec<(Category, Color)>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The theme for dark backgrounds, which uses the default colors of the icons.
+    #[must_use]
+    pub const fn dark() -> Self {
+        Self {
+            background: Background::Dark,
+            category_colors: Vec::new(),
+        }
+    }
+
+    /// The theme for light backgrounds, which prints `white` icons in `black` and `cyan` icons in `blue`.
+    #[must_use]
+    pub const fn light() -> Self {
+        Self {
+            background: Background::Light,
+            category_colors: Vec::new(),
+        }
+    }
+
+    /// The theme matching the background detected by `term::background()`, `Theme::dark()` if unknown.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::Theme;
+    ///
+    /// murmur::set_theme(Theme::adaptive());
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn adaptive() -> Self {
+        match term::background() {
+            Some(Background::Light) => Self::light(),
+            Some(Background::Dark) | None => Self::dark(),
+        }
+    }
+
+    /// Prints every icon of `category` with `color` instead of its default color.
+    ///
+    /// `color` is a `Color`, or the name of a color like `"magenta"`, see `Color::from_name()`.
+    #[must_use]
+    pub fn category_color<C: Into<Color>>(mut self, category: Category, color: C) -> Self {
+        self.category_colors
+            .retain(|(existing, _)| *existing != category);
+        self.category_colors.push((category, color.into()));
+        self
+    }
+
+    /// Returns the background the theme is chosen for.
+    #[must_use]
+    pub const fn background(&self) -> Background {
+        self.background
+    }
+
+    /// Returns the color to print `icon_kind` with, given its default `color`.
+    #[must_use]
+    pub fn color_for(&self, icon_kind: &IconKind, color: Color) -> Color {
+        let category = icon_kind.category();
+        let color = self
+            .category_colors
+            .iter()
+            .find_map(|(existing, color)| (*existing == category).then_some(*color))
+            .unwrap_or(color);
+        match (self.background, color) {
+            (Background::Light, Color::White) => Color::Black,
+            (Background::Light, Color::Cyan) => Color::Blue,
+            _ => color,
+        }
+    }
+}
+
+/// The global `Theme`, `Theme::dark()` until `set_theme` is called.
+#[cfg(feature = "std")]
+pub static THEME: RwLock<Theme> = RwLock::new(Theme::dark());
+
+/// Sets the global `Theme` used by every whisper.
+///
+/// # Panics
+///
+/// Panics if the theme lock is poisoned.
+#[cfg(feature = "std")]
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap() = theme;
+}
+
+#[cfg(test)]
+mod theme_tests {
+    use super::*;
+
+    #[test]
+    fn test_dark_theme_keeps_default_colors() {
+        let theme = Theme::dark();
+        assert_eq!(
+            theme.color_for(&IconKind::NfFaInfoCircle, Color::White),
+            Color::White
+        );
+        assert_eq!(
+            theme.color_for(&IconKind::NfFaRefresh, Color::Cyan),
+            Color::Cyan
+        );
+    }
+
+    #[test]
+    fn test_light_theme_remaps_invisible_colors() {
+        let theme = Theme::light();
+        assert_eq!(
+            theme.color_for(&IconKind::NfFaInfoCircle, Color::White),
+            Color::Black
+        );
+        assert_eq!(
+            theme.color_for(&IconKind::NfFaRefresh, Color::Cyan),
+            Color::Blue
+        );
+        assert_eq!(
+            theme.color_for(&IconKind::NfFaTimes, Color::Red),
+            Color::Red
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "icons-oct")]
+    fn test_category_color() {
+        let theme = Theme::light()
+            .category_color(Category::Vcs, "green")
+            .category_color(Category::Vcs, "white");
+        assert_eq!(
+            theme.color_for(&IconKind::NfOctTag, Color::Yellow),
+            Color::Black
+        );
+        assert_eq!(
+            theme.color_for(&IconKind::NfFaCheck, Color::Green),
+            Color::Green
+        );
+    }
+}
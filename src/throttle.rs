@@ -0,0 +1,95 @@
+//! The `throttle` module limits how often progress whispers are printed to the terminal.
+//!
+//! Tools whispering thousands of progress updates per second spend more time drawing the terminal
+//! than working. With `set_refresh_rate(n)`, `StdoutSink` prints at most `n` informational whispers
+//! per second when stdout is a terminal, and skips the others. Whispers above `Level::Info`, e.g.
+//! warnings, are always printed, and the other sinks still receive every whisper, so log files stay
+//! complete. `flush_throttled` prints the last skipped whisper, e.g. the final state of a loop.
+//!
+//! ```
+//! use murmur::Whisper;
+//!
+//! murmur::set_refresh_rate(30);
+//! for file in 0..1_000 {
+//!     Whisper::new().message(format!("indexed {file} files")).whisper().unwrap();
+//! }
+//! murmur::flush_throttled().unwrap();
+//! # murmur::set_refresh_rate(0);
+//! ```
+//!
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{term, Level, RenderedWhisper, Sink, StdoutSink, WhisperError};
+
+/// The maximum number of informational whispers printed per second, 0 when not throttled.
+static REFRESH_RATE: AtomicU32 = AtomicU32::new(0);
+
+/// When the last throttled whisper was printed, and the last one skipped since.
+static STATE: Mutex<(Option<Instant>, Option<RenderedWhisper>)> = Mutex::new((None, None));
+
+/// Sets the maximum number of informational whispers `StdoutSink` prints per second to a terminal,
+/// 0 to print them all, the default.
+pub fn set_refresh_rate(per_second: u32) {
+    REFRESH_RATE.store(per_second, Ordering::Relaxed);
+}
+
+/// Returns the maximum number of informational whispers printed per second, 0 when not throttled.
+#[must_use]
+pub fn refresh_rate() -> u32 {
+    REFRESH_RATE.load(Ordering::Relaxed)
+}
+
+/// Prints the last whisper skipped by the refresh rate, if any, see `set_refresh_rate`.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Lock` if the throttle lock is poisoned, or the error of `StdoutSink`.
+pub fn flush_throttled() -> Result<(), WhisperError> {
+    let skipped = STATE.lock().map_err(|_| WhisperError::Lock)?.1.take();
+    skipped.map_or(Ok(()), |rendered| StdoutSink.emit(&rendered))
+}
+
+/// Returns whether `StdoutSink` prints `rendered` now, remembering it as skipped otherwise.
+pub fn admit(rendered: &RenderedWhisper) -> bool {
+    let rate = refresh_rate();
+    if rate == 0
+        || rendered.level > Level::Info
+        || !term::capabilities().stdout_tty
+        || term::deterministic()
+    {
+        return true;
+    }
+    let Ok(mut state) = STATE.lock() else {
+        return true;
+    };
+    let now = Instant::now();
+    if due(state.0, now, rate) {
+        *state = (Some(now), None);
+        true
+    } else {
+        state.1 = Some(rendered.clone());
+        false
+    }
+}
+
+/// Returns whether a whisper is printed at `now`, the last one printed at `last`, with at most
+/// `rate` whispers per second.
+fn due(last: Option<Instant>, now: Instant, rate: u32) -> bool {
+    last.is_none_or(|last| now.duration_since(last) >= Duration::from_secs(1) / rate)
+}
+
+#[cfg(test)]
+mod throttle_tests {
+    use super::*;
+
+    #[test]
+    fn test_due() {
+        let start = Instant::now();
+        assert!(due(None, start, 10));
+        assert!(!due(Some(start), start + Duration::from_millis(50), 10));
+        assert!(due(Some(start), start + Duration::from_millis(100), 10));
+        assert!(due(Some(start), start + Duration::from_secs(1), 1));
+    }
+}
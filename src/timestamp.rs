@@ -0,0 +1,78 @@
+//! The `timestamp` module formats the timestamp column printed before the icon of a whisper.
+//!
+//! `Whisper::timestamp()` shows the wall-clock time, `Whisper::timestamp_relative(start)` the time
+//! elapsed since `start` like `+12.3s`, which benchmark and build logs usually want. Both are
+//! rendered in the same right-aligned, dimmed column, so the icons stay aligned. With
+//! `term::set_deterministic`, timestamps are zero.
+//!
+//! ```
+//! use std::time::Instant;
+//! use murmur::{IconKind, Whisper};
+//!
+//! let start = Instant::now();
+//! Whisper::new().icon(IconKind::NfFaCheck).message("compiled").timestamp_relative(start).whisper().unwrap();
+//! Whisper::new().icon(IconKind::NfFaInfoCircle).message("done").timestamp().whisper().unwrap();
+//! ```
+//!
+use alloc::format;
+use alloc::string::String;
+use core::time::Duration;
+
+/// The minimum width of the timestamp column, the width of a wall-clock time.
+pub const TIMESTAMP_WIDTH: usize = 8;
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// `Timestamp` is the time shown in the timestamp column of a whisper.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Timestamp {
+    /// A wall-clock time, as the duration since the Unix epoch, shown as `HH:MM:SS` in UTC.
+    Wall(Duration),
+    /// A duration since a start, shown as `+12.3s`.
+    Relative(Duration),
+}
+
+impl Timestamp {
+    /// Returns the text of the timestamp, without padding.
+    #[must_use]
+    pub fn text(self) -> String {
+        match self {
+            Self::Wall(since_epoch) => {
+                let seconds = since_epoch.as_secs() % SECONDS_PER_DAY;
+                format!(
+                    "{:02}:{:02}:{:02}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                )
+            }
+            Self::Relative(elapsed) => format!("+{:.1}s", elapsed.as_secs_f64()),
+        }
+    }
+
+    /// Returns the timestamp column: the text right-aligned to `TIMESTAMP_WIDTH` columns.
+    #[must_use]
+    pub fn column(self) -> String {
+        format!("{:>TIMESTAMP_WIDTH$}", self.text())
+    }
+}
+
+#[cfg(test)]
+mod timestamp_tests {
+    use super::*;
+
+    #[test]
+    fn test_wall_column() {
+        let since_epoch = Duration::from_secs(19_000 * SECONDS_PER_DAY + 13 * 3600 + 5 * 60 + 9);
+        assert_eq!(Timestamp::Wall(since_epoch).column(), "13:05:09");
+    }
+
+    #[test]
+    fn test_relative_column() {
+        let elapsed = Duration::from_millis(12_340);
+        assert_eq!(Timestamp::Relative(elapsed).column(), "  +12.3s");
+        let long = Duration::from_secs(1_234_567);
+        assert_eq!(Timestamp::Relative(long).column(), "+1234567.0s");
+    }
+}
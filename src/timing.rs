@@ -0,0 +1,124 @@
+//! The `timing` module records how long whispers take to render and to reach their sinks.
+//!
+//! Output-heavy tools can spend most of their time waiting for a slow terminal, e.g. the Windows
+//! console host, without it showing in a profiler of their own code. The trace is off by default.
+//! Once `trace_timings(n)` is called, the timings of the last `n` whispers are kept and `timings()`
+//! returns them.
+//!
+//! ```
+//! use murmur::Whisper;
+//!
+//! murmur::trace_timings(100);
+//! Whisper::new().message("timed").whisper().unwrap();
+//! let timing = murmur::timings().pop().unwrap();
+//! assert_eq!(timing.lines, 1);
+//! assert!(timing.total() >= timing.emit);
+//! # murmur::trace_timings(0);
+//! ```
+//!
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{Level, RenderedWhisper};
+
+/// The number of timings kept, 0 while the trace is off.
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+/// The timings of the last whispers, oldest first.
+static TIMINGS: Mutex<VecDeque<WhisperTiming>> = Mutex::new(VecDeque::new());
+
+/// `WhisperTiming` is how long a whisper took to render and to be emitted, see `trace_timings`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct WhisperTiming {
+    /// The severity of the whisper.
+    pub level: Level,
+    /// The first message of the whisper, to tell the timings apart.
+    pub message: Option<String>,
+    /// The number of lines of the whisper.
+    pub lines: usize,
+    /// When the whisper started rendering.
+    pub start: Instant,
+    /// The time spent rendering the whisper, including the rate limit and the icon lookup.
+    pub render: Duration,
+    /// The time spent emitting the whisper to every sink, including writing and flushing them.
+    pub emit: Duration,
+}
+
+impl WhisperTiming {
+    /// Returns the time spent rendering and emitting the whisper.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.render + self.emit
+    }
+}
+
+/// Keeps the timings of the last `capacity` whispers, 0 turns the trace off and clears it.
+///
+/// # Panics
+///
+/// Panics if the timings lock is poisoned.
+pub fn trace_timings(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    let mut timings = TIMINGS.lock().unwrap();
+    let excess = timings.len().saturating_sub(capacity);
+    timings.drain(..excess);
+    timings.shrink_to(capacity);
+}
+
+/// Returns the timings of the last whispers, oldest first, empty while the trace is off.
+///
+/// # Panics
+///
+/// Panics if the timings lock is poisoned.
+#[must_use]
+pub fn timings() -> Vec<WhisperTiming> {
+    TIMINGS.lock().unwrap().iter().cloned().collect()
+}
+
+/// Returns whether the timings are traced.
+pub fn enabled() -> bool {
+    CAPACITY.load(Ordering::Relaxed) > 0
+}
+
+/// Records the timing of `rendered`, which started rendering at `start` and was rendered in `render`.
+pub fn record(rendered: &RenderedWhisper, start: Instant, render: Duration) {
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    if capacity == 0 {
+        return;
+    }
+    let timing = WhisperTiming {
+        level: rendered.level,
+        message: rendered.messages.first().cloned(),
+        lines: rendered.segments.len(),
+        start,
+        render,
+        emit: start.elapsed().saturating_sub(render),
+    };
+    if let Ok(mut timings) = TIMINGS.lock() {
+        if timings.len() >= capacity {
+            timings.pop_front();
+        }
+        timings.push_back(timing);
+    }
+}
+
+#[cfg(test)]
+mod timing_tests {
+    use super::*;
+    use crate::{RenderOptions, Whisper};
+
+    #[test]
+    fn test_record_keeps_the_last_timings() {
+        let rendered = RenderedWhisper::new(&Whisper::new().message("a"), &RenderOptions::plain());
+        trace_timings(2);
+        for _ in 0..3 {
+            record(&rendered, Instant::now(), Duration::ZERO);
+        }
+        assert_eq!(timings().len(), 2);
+        trace_timings(0);
+        assert!(timings().is_empty());
+        assert!(!enabled());
+    }
+}
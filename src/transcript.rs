@@ -0,0 +1,216 @@
+//! The `transcript` module records whispers to a file and replays them.
+//!
+//! `record` adds a sink appending every whisper to a transcript, `replay` whispers a transcript
+//! again with the current terminal settings. Users can attach a transcript to a bug report, and
+//! maintainers replay it to reproduce the session.
+//!
+//! A transcript starts with a `# murmur transcript v1` header, followed by one whisper per line:
+//! the Unix time in milliseconds, the level, the icon (`-` for none) and the messages, separated by
+//! tabs. Tabs, newlines and backslashes in messages are escaped. With `term::set_deterministic`,
+//! every whisper is recorded at time 0.
+//!
+//! ```no_run
+//! use murmur::{IconKind, Whisper};
+//!
+//! murmur::record("session.murmur").unwrap();
+//! Whisper::new().icon(IconKind::NfFaCheck).message("recorded").whisper().unwrap();
+//!
+//! murmur::replay("session.murmur").unwrap();
+//! ```
+//!
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use enum_iterator::all;
+
+use crate::{add_sink, sink, term, IconKind, Level, RenderedWhisper, Sink, Whisper, WhisperError};
+
+/// The first line of every transcript.
+const HEADER: &str = "# murmur transcript v1";
+
+/// `TranscriptSink` appends every whisper to a transcript file.
+struct TranscriptSink {
+    /// The transcript, opened in append mode.
+    file: Mutex<File>,
+}
+
+impl Sink for TranscriptSink {
+    fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+        let millis = if term::deterministic() {
+            0
+        } else {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_millis())
+        };
+        let line = format_entry(millis, rendered);
+        let mut file = self.file.lock().map_err(|_| WhisperError::Lock)?;
+        sink::write_lines(&mut *file, &[line]).map_err(|_| WhisperError::Write)
+    }
+}
+
+/// Starts recording every following whisper to the transcript at `path`.
+///
+/// A new transcript is created if `path` does not exist, otherwise the whispers are appended.
+///
+/// # Errors
+///
+/// Returns the `io::Error` if the transcript cannot be opened or its header cannot be written.
+pub fn record<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{HEADER}")?;
+    }
+    add_sink(TranscriptSink {
+        file: Mutex::new(file),
+    });
+    Ok(())
+}
+
+/// Whispers every whisper of the transcript at `path` again, in order.
+///
+/// The whispers are rendered with the current terminal settings and theme. If a recording is
+/// active, replayed whispers are recorded too.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Transcript` if the file cannot be read or is not a transcript, or the
+/// error of a failing whisper.
+pub fn replay<P: AsRef<Path>>(path: P) -> Result<(), WhisperError> {
+    let transcript = fs::read_to_string(path).map_err(|_| WhisperError::Transcript)?;
+    for whisper in parse_transcript(&transcript)? {
+        whisper.whisper()?;
+    }
+    Ok(())
+}
+
+/// Returns the transcript line of `rendered`, recorded at `millis` since the Unix epoch.
+fn format_entry(millis: u128, rendered: &RenderedWhisper) -> String {
+    let icon = rendered.icon_kind.as_ref().map_or("-", IconKind::as_str);
+    let mut line = format!("{millis}\t{}\t{icon}", rendered.level);
+    for message in &rendered.messages {
+        line.push('\t');
+        line.push_str(&escape(message));
+    }
+    line
+}
+
+/// Parses the whispers of `transcript`.
+///
+/// # Errors
+///
+/// Returns `WhisperError::Transcript` if the header is missing or a line is malformed.
+fn parse_transcript(transcript: &str) -> Result<Vec<Whisper>, WhisperError> {
+    let mut lines = transcript.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(WhisperError::Transcript);
+    }
+    lines
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_entry(line).ok_or(WhisperError::Transcript))
+        .collect()
+}
+
+/// Parses the whisper of one transcript line, `None` if it is malformed.
+fn parse_entry(line: &str) -> Option<Whisper> {
+    let mut fields = line.split('\t');
+    fields.next()?.parse::<u128>().ok()?;
+    let level_name = fields.next()?;
+    let level = all::<Level>().find(|level| level.as_str() == level_name)?;
+    let icon_name = fields.next()?;
+    let mut whisper = Whisper::new().level(level).messages(fields.map(unescape));
+    if icon_name != "-" {
+        let icon_kind = all::<IconKind>().find(|icon_kind| icon_kind.as_str() == icon_name)?;
+        whisper = whisper.icon(icon_kind);
+    }
+    Some(whisper)
+}
+
+/// Escapes tabs, newlines and backslashes of `message`.
+fn escape(message: &str) -> String {
+    message
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Reverts `escape`.
+fn unescape(field: &str) -> String {
+    let mut message = String::with_capacity(field.len());
+    let mut characters = field.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            message.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('t') => message.push('\t'),
+            Some('n') => message.push('\n'),
+            Some('r') => message.push('\r'),
+            Some(other) => message.push(other),
+            None => message.push('\\'),
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod transcript_tests {
+    use super::*;
+    use crate::RenderOptions;
+
+    #[test]
+    fn test_transcript_round_trip() {
+        let whispers = [
+            Whisper::new()
+                .icon(IconKind::NfFaWarning)
+                .message("tab\there")
+                .message("new\nline \\ backslash"),
+            Whisper::new().message("no icon").level(Level::Debug),
+            Whisper::new().icon(IconKind::UnicodeCheckMark),
+        ];
+        let mut transcript = format!("{HEADER}\n");
+        for whisper in &whispers {
+            let rendered = RenderedWhisper::new(whisper, &RenderOptions::plain());
+            transcript.push_str(&format_entry(1_700_000_000_000, &rendered));
+            transcript.push('\n');
+        }
+        let replayed = parse_transcript(&transcript).unwrap();
+        assert_eq!(replayed[0], whispers[0].clone().level(Level::Warn));
+        assert_eq!(replayed[1], whispers[1]);
+        assert_eq!(replayed[2], whispers[2].clone().level(Level::Success));
+    }
+
+    #[test]
+    fn test_malformed_transcripts() {
+        assert!(matches!(
+            parse_transcript("not a transcript"),
+            Err(WhisperError::Transcript)
+        ));
+        assert!(matches!(
+            parse_transcript(&format!("{HEADER}\n1\twarn\tNoSuchIcon\tmessage")),
+            Err(WhisperError::Transcript)
+        ));
+    }
+
+    #[test]
+    fn test_replay_file() {
+        let path = std::env::temp_dir().join(format!("murmur-{}.transcript", std::process::id()));
+        fs::write(
+            &path,
+            format!("{HEADER}\n1\tinfo\tNfFaInfoCircle\treplayed\n"),
+        )
+        .unwrap();
+        let result = replay(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_ok());
+        assert!(matches!(
+            replay("/nonexistent/murmur.transcript"),
+            Err(WhisperError::Transcript)
+        ));
+    }
+}
@@ -0,0 +1,330 @@
+//! The `webhook` module posts important whispers to a chat through an incoming webhook.
+//!
+//! `WebhookSink` is available with the `webhook` feature. It emits whispers at or above a minimum
+//! `Level` as a JSON message for Slack, Microsoft Teams or Discord, with the icon mapped to an emoji.
+//!
+//! The built-in transport speaks plain HTTP, which is enough for local relays and proxies. Chat
+//! services only accept HTTPS, plug the HTTP client of the application in with `WebhookSink::transport`.
+//!
+//! ```no_run
+//! use murmur::{IconKind, Level, WebhookFormat, WebhookSink, Whisper};
+//!
+//! murmur::add_sink(
+//!     WebhookSink::new("http://localhost:8080/hooks/deploy", WebhookFormat::Slack).min_level(Level::Warn),
+//! );
+//!
+//! // Printed to the terminal and posted to the chat
+//! Whisper::new().icon(IconKind::NfFaTimes).message("deploy failed").whisper().unwrap();
+//! ```
+//!
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::sink::escape_json;
+use crate::{IconKind, Level, RenderedWhisper, Sink, WhisperError};
+
+/// The timeout for connecting to the webhook, writing the request and reading the answer.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A function posting a JSON `body` to a `url`.
+type Transport = Box<dyn Fn(&str, &str) -> io::Result<()> + Send + Sync>;
+
+/// `WebhookFormat` is the chat service a webhook belongs to, which decides the JSON payload.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum WebhookFormat {
+    /// A Slack incoming webhook, `{"text": "..."}`.
+    Slack,
+    /// A Microsoft Teams incoming webhook, `{"text": "..."}`.
+    Teams,
+    /// A Discord webhook, `{"content": "..."}`.
+    Discord,
+}
+
+/// `WebhookSink` posts whispers at or above a minimum `Level` to a chat webhook.
+pub struct WebhookSink {
+    /// The URL of the webhook.
+    url: String,
+    /// The chat service of the webhook.
+    format: WebhookFormat,
+    /// The lowest level posted.
+    min_level: Level,
+    /// Posts the payload to the URL.
+    transport: Transport,
+}
+
+impl WebhookSink {
+    /// Creates a sink posting `Level::Error` whispers to the webhook at `url`.
+    #[must_use]
+    pub fn new<S: Into<String>>(url: S, format: WebhookFormat) -> Self {
+        Self {
+            url: url.into(),
+            format,
+            min_level: Level::Error,
+            transport: Box::new(post_http),
+        }
+    }
+
+    /// Posts whispers at or above `min_level` instead of only errors.
+    #[must_use]
+    pub const fn min_level(mut self, min_level: Level) -> Self {
+        self.min_level = min_level;
+        self
+    }
+
+    /// Replaces the built-in plain HTTP transport, e.g. with an HTTPS client.
+    ///
+    /// `transport` is called with the URL and the JSON body, and should fail on non-success statuses.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::{WebhookFormat, WebhookSink};
+    ///
+    /// let sink = WebhookSink::new("https://hooks.slack.com/services/T0/B0/X", WebhookFormat::Slack)
+    ///     .transport(|url, body| {
+    ///         // e.g. ureq::post(url).set("Content-Type", "application/json").send_string(body)
+    ///         println!("POST {url} {body}");
+    ///         Ok(())
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn transport<F>(mut self, transport: F) -> Self
+    where
+        F: Fn(&str, &str) -> io::Result<()> + Send + Sync + 'static,
+    {
+        self.transport = Box::new(transport);
+        self
+    }
+
+    /// Returns the JSON payload posted for `rendered`.
+    #[must_use]
+    pub fn payload(&self, rendered: &RenderedWhisper) -> String {
+        let emoji = rendered
+            .icon_kind
+            .as_ref()
+            .map_or_else(|| level_emoji(rendered.level), IconKind::emoji);
+        let text = format!("{emoji} {}", rendered.messages.join("\n"));
+        let key = match self.format {
+            WebhookFormat::Slack | WebhookFormat::Teams => "text",
+            WebhookFormat::Discord => "content",
+        };
+        format!("{{\"{key}\":\"{}\"}}", escape_json(&text))
+    }
+}
+
+impl Sink for WebhookSink {
+    fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+        if rendered.level < self.min_level {
+            return Ok(());
+        }
+        (self.transport)(&self.url, &self.payload(rendered)).map_err(|_| WhisperError::Sink)
+    }
+}
+
+impl IconKind {
+    /// Returns the emoji standing for the icon in chat messages.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::IconKind;
+    ///
+    /// assert_eq!(IconKind::NfFaRocket.emoji(), "\u{1f680}");
+    /// ```
+    #[must_use]
+    pub fn emoji(&self) -> &'static str {
+        match self.to_nerd_font() {
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaRocket => "\u{1f680}",
+            #[cfg(feature = "icons-oct")]
+            Self::NfOctPackage => "\u{1f4e6}",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaLock => "\u{1f512}",
+            Self::NfFaKey => "\u{1f511}",
+            Self::NfFaClock => "\u{23f0}",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaWrench => "\u{1f527}",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaFolder | Self::NfFaFolderOpen => "\u{1f4c1}",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsUp => "\u{1f44d}",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaThumbsDown => "\u{1f44e}",
+            #[cfg(feature = "icons-fa")]
+            Self::NfFaHourglass => "\u{23f3}",
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherDaySunny => "\u{2600}\u{fe0f}",
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherCloudy => "\u{2601}\u{fe0f}",
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherRain => "\u{1f327}\u{fe0f}",
+            #[cfg(feature = "icons-weather")]
+            Self::NfWeatherNightClear => "\u{1f319}",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevRust => "\u{1f980}",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevPython => "\u{1f40d}",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevJavascript => "\u{1f4dc}",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevDocker => "\u{1f433}",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevLinux => "\u{1f427}",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevApple => "\u{1f34e}",
+            #[cfg(feature = "icons-dev")]
+            Self::NfDevWindows => "\u{1fa9f}",
+            Self::NfFaQuestion | Self::NfFaQuestionCircle => "\u{2753}",
+            Self::NfFaBug => "\u{1f41b}",
+            icon_kind => level_emoji(icon_kind.level()),
+        }
+    }
+}
+
+/// Returns the emoji standing for `level` in chat messages.
+const fn level_emoji(level: Level) -> &'static str {
+    match level {
+        Level::Trace | Level::Debug => "\u{1f50d}",
+        Level::Info => "\u{2139}\u{fe0f}",
+        Level::Success => "\u{2705}",
+        Level::Warn => "\u{26a0}\u{fe0f}",
+        Level::Error => "\u{274c}",
+    }
+}
+
+/// Posts `body` to an `http://` `url` with a minimal HTTP/1.1 client.
+fn post_http(url: &str, body: &str) -> io::Result<()> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "only http:// webhooks are built in, use WebhookSink::transport for https://",
+        )
+    })?;
+    let (authority, path) = rest
+        .find('/')
+        .map_or((rest, "/"), |index| rest.split_at(index));
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+    let address = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "webhook host not found"))?;
+
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: {authority}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status.starts_with('2') {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "webhook answered {}",
+            status_line.trim_end()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod webhook_tests {
+    use super::*;
+    use crate::{RenderOptions, Whisper};
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Renders `whisper` for a sink.
+    fn rendered(whisper: &Whisper) -> RenderedWhisper {
+        RenderedWhisper::new(whisper, &RenderOptions::plain())
+    }
+
+    #[test]
+    fn test_payload_formats() {
+        let whisper = rendered(
+            &Whisper::new()
+                .icon(IconKind::NfFaTimes)
+                .message("deploy \"prod\" failed")
+                .message("exit code 1"),
+        );
+        let slack = WebhookSink::new("http://localhost", WebhookFormat::Slack);
+        assert_eq!(
+            slack.payload(&whisper),
+            "{\"text\":\"\u{274c} deploy \\\"prod\\\" failed\\nexit code 1\"}"
+        );
+        let discord = WebhookSink::new("http://localhost", WebhookFormat::Discord);
+        assert!(discord.payload(&whisper).starts_with("{\"content\":"));
+    }
+
+    #[test]
+    fn test_min_level_filters_whispers() {
+        let posted = Arc::new(Mutex::new(Vec::new()));
+        let sink = WebhookSink::new("https://example.com/hook", WebhookFormat::Teams)
+            .min_level(Level::Warn)
+            .transport({
+                let posted = Arc::clone(&posted);
+                move |url, body| {
+                    posted.lock().unwrap().push(format!("{url} {body}"));
+                    Ok(())
+                }
+            });
+        sink.emit(&rendered(
+            &Whisper::new().icon(IconKind::NfFaCheck).message("ok"),
+        ))
+        .unwrap();
+        sink.emit(&rendered(
+            &Whisper::new().icon(IconKind::NfFaWarning).message("slow"),
+        ))
+        .unwrap();
+        sink.emit(&rendered(
+            &Whisper::new().message("info").level(Level::Error),
+        ))
+        .unwrap();
+        let posted = posted.lock().unwrap().clone();
+        assert_eq!(posted.len(), 2);
+        assert!(posted[0].starts_with("https://example.com/hook {\"text\":\"\u{26a0}\u{fe0f} slow"));
+    }
+
+    #[test]
+    fn test_post_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0_u8; 1024];
+            while !String::from_utf8_lossy(&request).ends_with("{\"text\":\"hi\"}") {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        post_http(
+            &format!("http://127.0.0.1:{port}/hook"),
+            "{\"text\":\"hi\"}",
+        )
+        .unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /hook HTTP/1.1\r\n"));
+        assert!(request.contains("Content-Length: 13\r\n"));
+    }
+
+    #[test]
+    fn test_https_needs_transport() {
+        let error = post_http("https://hooks.slack.com/services/x", "{}").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    }
+}
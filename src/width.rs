@@ -0,0 +1,233 @@
+//! The `width` module measures how many terminal columns a string takes.
+//!
+//! Terminals print East Asian wide characters and emoji in 2 columns, combining marks and variation
+//! selectors in none, so the number of `char`s is not the width of a string. Nerd Font glyphs are
+//! private use characters and take 1 column.
+//!
+//! Widths are measured per grapheme cluster, the characters a terminal prints as one symbol: a
+//! character with its combining marks, an emoji with its skin tone, an emoji ZWJ sequence like
+//! the family emoji, or a flag made of 2 regional indicators.
+//!
+//! Terminals disagree on the width of a character followed by `U+FE0F`, some print it in 2 columns
+//! and others in 1, so `Presentation` rewrites the variation selectors of glyphs to keep them aligned.
+//!
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+use crate::IconKind;
+
+/// The zero width joiner, which glues emoji into a single symbol.
+const ZWJ: char = '\u{200d}';
+
+/// The variation selector asking for the text presentation of the previous character.
+const TEXT_PRESENTATION: char = '\u{fe0e}';
+
+/// The variation selector asking for the emoji presentation of the previous character.
+const EMOJI_PRESENTATION: char = '\u{fe0f}';
+
+/// `Presentation` decides how the variation selectors of glyphs are printed, see `RenderOptions::presentation`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum Presentation {
+    /// Print glyphs as they are, e.g. `U+26A0 U+FE0F` for the warning sign in 2 columns.
+    #[default]
+    Emoji,
+    /// Replace `U+FE0F` by `U+FE0E`, asking for the 1 column text presentation.
+    Text,
+    /// Remove the variation selectors, the terminal prints its default presentation.
+    Strip,
+}
+
+impl Presentation {
+    /// Returns `glyph` with its variation selectors rewritten for this presentation.
+    ///
+    /// # Examples
+    /// ```
+    /// use murmur::Presentation;
+    ///
+    /// assert_eq!(Presentation::Text.apply("\u{26a0}\u{fe0f} "), "\u{26a0}\u{fe0e} ");
+    /// assert_eq!(Presentation::Strip.apply("\u{26a0}\u{fe0f} "), "\u{26a0} ");
+    /// assert_eq!(Presentation::Emoji.apply("\u{26a0}\u{fe0f} "), "\u{26a0}\u{fe0f} ");
+    /// ```
+    #[must_use]
+    pub fn apply(self, glyph: &str) -> Cow<'_, str> {
+        let selector = |character| matches!(character, TEXT_PRESENTATION | EMOJI_PRESENTATION);
+        match self {
+            Self::Emoji => Cow::Borrowed(glyph),
+            _ if !glyph.contains(selector) => Cow::Borrowed(glyph),
+            Self::Text => Cow::Owned(glyph.replace(EMOJI_PRESENTATION, "\u{fe0e}")),
+            Self::Strip => Cow::Owned(glyph.chars().filter(|&c| !selector(c)).collect::<String>()),
+        }
+    }
+}
+
+/// Returns the number of columns `text` takes in a terminal.
+///
+/// A character followed by `U+FE0F` takes 2 columns, and 1 column if followed by `U+FE0E`.
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    // The width of the current grapheme cluster
+    let mut cluster = 0;
+    let mut joined = false;
+    let mut regional_indicator = false;
+    for character in text.chars() {
+        match character {
+            ZWJ => joined = cluster > 0,
+            EMOJI_PRESENTATION if cluster == 1 => cluster = 2,
+            TEXT_PRESENTATION if cluster == 2 => cluster = 1,
+            // The second regional indicator of a flag
+            '\u{1f1e6}'..='\u{1f1ff}' if regional_indicator => {
+                cluster = 2;
+                regional_indicator = false;
+            }
+            _ => {
+                let char_width = char_width(character);
+                if joined {
+                    // The joined emoji is printed over the previous one
+                    joined = false;
+                } else if char_width > 0 {
+                    width += cluster;
+                    cluster = char_width;
+                    regional_indicator = matches!(character, '\u{1f1e6}'..='\u{1f1ff}');
+                }
+            }
+        }
+    }
+    width + cluster
+}
+
+/// Returns the number of columns `character` takes, 0 for control and zero-width characters.
+const fn char_width(character: char) -> usize {
+    match character as u32 {
+        0x00..=0x1f
+        | 0x7f..=0x9f
+        | 0x0300..=0x036f
+        | 0x0483..=0x0489
+        | 0x0591..=0x05bd
+        | 0x0610..=0x061a
+        | 0x064b..=0x065f
+        | 0x0e31
+        | 0x0e34..=0x0e3a
+        | 0x0e47..=0x0e4e
+        | 0x1160..=0x11ff
+        | 0x1ab0..=0x1aff
+        | 0x1dc0..=0x1dff
+        | 0x200b..=0x200f
+        | 0x20d0..=0x20ff
+        | 0xfe00..=0xfe0f
+        | 0xfe20..=0xfe2f
+        | 0x1f3fb..=0x1f3ff
+        | 0xe0000..=0xe007f
+        | 0xe0100..=0xe01ef => 0,
+        0x1100..=0x115f
+        | 0x231a..=0x231b
+        | 0x23e9..=0x23ec
+        | 0x23f0
+        | 0x23f3
+        | 0x25fd..=0x25fe
+        | 0x2614..=0x2615
+        | 0x2648..=0x2653
+        | 0x26a1
+        | 0x26aa..=0x26ab
+        | 0x26bd..=0x26be
+        | 0x26c4..=0x26c5
+        | 0x26d4
+        | 0x26ea
+        | 0x26f2..=0x26f5
+        | 0x26fa
+        | 0x26fd
+        | 0x2705
+        | 0x270a..=0x270b
+        | 0x2728
+        | 0x274c
+        | 0x274e
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27b0
+        | 0x27bf
+        | 0x2b1b..=0x2b1c
+        | 0x2b50
+        | 0x2b55
+        | 0x2e80..=0x303e
+        | 0x3041..=0x33ff
+        | 0x3400..=0x4dbf
+        | 0x4e00..=0x9fff
+        | 0xa000..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x1f004
+        | 0x1f0cf
+        | 0x1f18e
+        | 0x1f191..=0x1f19a
+        | 0x1f200..=0x1f2ff
+        | 0x1f300..=0x1f64f
+        | 0x1f680..=0x1f6ff
+        | 0x1f900..=0x1f9ff
+        | 0x1fa70..=0x1faff
+        | 0x20000..=0x3fffd => 2,
+        _ => 1,
+    }
+}
+
+/// Returns the width of the widest built-in icon, including the space following it.
+pub fn widest_icon() -> usize {
+    enum_iterator::all::<IconKind>()
+        .map(|icon_kind| display_width(icon_kind.glyph()))
+        .max()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod width_tests {
+    use super::*;
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("\u{f00c} "), 2);
+        assert_eq!(display_width("\u{2714}\u{fe0f} "), 3);
+        assert_eq!(display_width("\u{274c} "), 3);
+        assert_eq!(display_width("\u{65e5}\u{672c}"), 4);
+        assert_eq!(display_width("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn test_grapheme_clusters() {
+        // Family: man, ZWJ, woman, ZWJ, girl
+        assert_eq!(
+            display_width("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}"),
+            2
+        );
+        // Thumbs up with a skin tone
+        assert_eq!(display_width("\u{1f44d}\u{1f3fd}"), 2);
+        // The flags of France and Japan
+        assert_eq!(display_width("\u{1f1eb}\u{1f1f7}\u{1f1ef}\u{1f1f5}"), 4);
+        // A heart in text presentation, and in emoji presentation
+        assert_eq!(display_width("\u{2764}\u{fe0e}"), 1);
+        assert_eq!(display_width("\u{2764}\u{fe0f}"), 2);
+        // Hangul syllable made of jamo
+        assert_eq!(display_width("\u{1100}\u{1161}\u{11a8}"), 2);
+        assert_eq!(display_width("\u{200d}a"), 1);
+    }
+
+    #[test]
+    fn test_presentation() {
+        let check_mark = IconKind::UnicodeCheckMark.glyph();
+        assert_eq!(display_width(&Presentation::Emoji.apply(check_mark)), 3);
+        assert_eq!(display_width(&Presentation::Text.apply(check_mark)), 2);
+        assert_eq!(display_width(&Presentation::Strip.apply(check_mark)), 2);
+        // Glyphs without variation selectors are not copied
+        assert!(matches!(
+            Presentation::Strip.apply(IconKind::NfFaCheck.glyph()),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_widest_icon() {
+        assert_eq!(widest_icon(), 3);
+    }
+}
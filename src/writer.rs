@@ -0,0 +1,126 @@
+//! The `writer` module adapts whispers to code writing into a `fmt::Write`.
+//!
+//! `WhisperWriter` accumulates what is written with `write!` and whispers a copy of its template
+//! whisper for every complete line, the line as its last message. The incomplete last line is
+//! whispered by `flush`, or when the writer is dropped.
+//!
+//! ```
+//! use std::fmt::Write;
+//! use murmur::{IconKind, Whisper, WhisperWriter};
+//!
+//! let mut writer = WhisperWriter::new(Whisper::new().icon(IconKind::NfFaInfoCircle));
+//! let (x, y) = (1, 2);
+//! writeln!(writer, "x = {x}").unwrap();
+//! write!(writer, "y = ").unwrap();
+//! write!(writer, "{y}").unwrap();
+//! writer.flush().unwrap();
+//! ```
+//!
+use core::fmt;
+
+use crate::{Whisper, WhisperError};
+
+/// `WhisperWriter` whispers every line written into it, see the module documentation.
+#[derive(Debug, Default)]
+pub struct WhisperWriter {
+    /// The whisper each line is added to before it is whispered.
+    template: Whisper,
+    /// The incomplete line written so far.
+    line: String,
+}
+
+impl WhisperWriter {
+    /// Creates a `WhisperWriter` whispering every line as the last message of `template`.
+    #[must_use]
+    pub const fn new(template: Whisper) -> Self {
+        Self {
+            template,
+            line: String::new(),
+        }
+    }
+
+    /// Whispers the incomplete last line, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `Whisper::whisper()`.
+    pub fn flush(&mut self) -> Result<(), WhisperError> {
+        if self.line.is_empty() {
+            return Ok(());
+        }
+        let line = core::mem::take(&mut self.line);
+        self.template.clone().message(line).whisper()
+    }
+}
+
+impl From<Whisper> for WhisperWriter {
+    fn from(template: Whisper) -> Self {
+        Self::new(template)
+    }
+}
+
+impl fmt::Write for WhisperWriter {
+    /// Whispers every line completed by `text`, and keeps the rest for the next write.
+    ///
+    /// # Errors
+    ///
+    /// Returns `fmt::Error` if a line fails to be whispered.
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        let mut lines = text.split('\n');
+        // The text before the first newline completes the current line
+        self.line.push_str(lines.next().unwrap_or_default());
+        for line in lines {
+            let complete = core::mem::replace(&mut self.line, String::from(line));
+            self.template
+                .clone()
+                .message(complete)
+                .whisper()
+                .map_err(|_| fmt::Error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for WhisperWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod writer_tests {
+    use super::*;
+    use crate::{RenderedWhisper, Sink};
+    use core::fmt::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// A sink recording the last message of the whispers starting with `writer_tests`.
+    struct Messages(Arc<Mutex<Vec<String>>>);
+
+    impl Sink for Messages {
+        fn emit(&self, rendered: &RenderedWhisper) -> Result<(), WhisperError> {
+            if let Some(message) = rendered.messages.last() {
+                if message.starts_with("writer_tests") {
+                    self.0.lock().unwrap().push(message.clone());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_whisper_per_line() {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        crate::add_sink(Messages(Arc::clone(&messages)));
+        let mut writer = WhisperWriter::new(Whisper::new().message("template"));
+        write!(writer, "writer_tests a\nwriter_tests ").unwrap();
+        assert_eq!(*messages.lock().unwrap(), ["writer_tests a"]);
+        writeln!(writer, "b = {}", 1).unwrap();
+        write!(writer, "writer_tests c").unwrap();
+        drop(writer);
+        assert_eq!(
+            *messages.lock().unwrap(),
+            ["writer_tests a", "writer_tests b = 1", "writer_tests c"]
+        );
+    }
+}